@@ -0,0 +1,61 @@
+use std::env;
+use std::process;
+
+use tiny_http::{Header, Response as HttpResponse, Server};
+
+use routes::Api;
+
+mod routes;
+
+const DEFAULT_PORT: u16 = 8080;
+
+/// The port from `--port <port>`
+fn port_from_args(args: &[String]) -> Result<u16, String> {
+    match args.iter().position(|arg| arg == "--port") {
+        Some(i) => match args.get(i + 1) {
+            Some(port) => port.parse().map_err(|_| format!("Expected a port number, found \"{}\"", port)),
+            None => Err("--port needs a port number".to_string()),
+        },
+        None => Ok(DEFAULT_PORT),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let port = match port_from_args(&args) {
+        Ok(port) => port,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let server = match Server::http(("0.0.0.0", port)) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Couldn't listen on port {}: {}", port, e);
+            process::exit(1);
+        }
+    };
+    println!("Listening on http://localhost:{}", port);
+
+    let json_header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let mut api = Api::new();
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => api.handle(request.method().as_str(), request.url(), &body),
+            Err(_) => routes::Response {
+                status: 400,
+                body: serde_json::json!({ "error": "The body isn't UTF-8" }),
+            },
+        };
+
+        let reply = HttpResponse::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(json_header.clone());
+        if let Err(e) = request.respond(reply) {
+            eprintln!("Couldn't answer a request: {}", e);
+        }
+    }
+}
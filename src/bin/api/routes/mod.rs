@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use tic_tac_torture::*;
+use tic_tac_torture::bot::expectimax;
+
+#[cfg(test)]
+mod tests;
+
+/// A response to send back, always with a JSON body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response { status, body: json!({ "error": message }) }
+    }
+}
+
+/// The games played through the API, each known by an id
+pub struct Api {
+    games: BTreeMap<u64, Game>,
+    next_id: u64,
+}
+
+impl Default for Api {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Api {
+    pub fn new() -> Api {
+        Api {
+            games: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Answers a request for `path` with a JSON `body`, which may be empty
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> Response {
+        let body: Value = if body.trim().is_empty() {
+            json!({})
+        } else {
+            match serde_json::from_str(body) {
+                Ok(body) => body,
+                Err(e) => return Response::error(400, &format!("The body isn't valid JSON: {}", e)),
+            }
+        };
+
+        let segments: Vec<&str> = path.split('?').next().unwrap_or("")
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let result = match (method, segments.as_slice()) {
+            ("POST", ["games"]) => self.create_game(&body),
+            ("GET", ["games", id]) => self.game(id).map(|(id, game)| Response::ok(game_json(id, game))),
+            ("POST", ["games", id, "moves"]) => self.play(id, &body),
+            ("POST", ["analyze"]) => analyze(&body),
+            (_, ["games"]) | (_, ["games", _]) | (_, ["games", _, "moves"]) | (_, ["analyze"]) => {
+                Err(Response::error(405, &format!("{} isn't allowed on {}", method, path)))
+            },
+            _ => Err(Response::error(404, &format!("There's nothing at {}", path))),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    /// `POST /games`, with optional `x` and `o` of `"human"` or `"bot"`,
+    /// `first` and `difficulty`. The default is a human X against the bot.
+    fn create_game(&mut self, body: &Value) -> Result<Response, Response> {
+        let mut config = GameConfig::new();
+        for &player in &[PlayerKind::PlayerX, PlayerKind::PlayerO] {
+            let key = player.to_char().to_lowercase().to_string();
+            match body.get(&key).map(|value| value.as_str()) {
+                None => (),
+                Some(Some("human")) => config = config.human(player, true),
+                Some(Some("bot")) => config = config.human(player, false),
+                Some(_) => return Err(Response::error(400, &format!("\"{}\" should be \"human\" or \"bot\"", key))),
+            }
+        }
+        if let Some(first) = body.get("first") {
+            config = config.first_player(parse_player(first, "first")?);
+        }
+        if let Some(difficulty) = body.get("difficulty") {
+            let difficulty = difficulty.as_str()
+                .ok_or_else(|| "Expected a difficulty, easy, medium or hard".to_string())
+                .and_then(|difficulty| difficulty.parse::<Difficulty>())
+                .map_err(|e| Response::error(400, &e))?;
+            config = config.difficulty(difficulty);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut game = config.build();
+        play_bot_moves(&mut game);
+        let response = Response { status: 201, body: game_json(id, &game) };
+        self.games.insert(id, game);
+        Ok(response)
+    }
+
+    /// `POST /games/{id}/moves`, with a `square` like `"b2"`, or a `row` and
+    /// `col` from 1 to 3. The bot replies straight away if it has the turn.
+    fn play(&mut self, id: &str, body: &Value) -> Result<Response, Response> {
+        let coord = parse_move(body)?;
+        let (id, game) = self.game(id)?;
+        let mut game = game.clone();
+
+        if game.status == GameStatus::InProgress && !game.current_player_is_human() {
+            return Err(Response::error(409, "It's the bot's turn"));
+        }
+        // the same checks a network game's host makes
+        net::check_move(&game, game.current_player, coord).map_err(|e| Response::error(409, &e))?;
+        game.make_move(coord.row, coord.col);
+        play_bot_moves(&mut game);
+
+        let response = Response::ok(game_json(id, &game));
+        self.games.insert(id, game);
+        Ok(response)
+    }
+
+    fn game(&self, id: &str) -> Result<(u64, &Game), Response> {
+        id.parse::<u64>().ok()
+            .and_then(|id| self.games.get(&id).map(|game| (id, game)))
+            .ok_or_else(|| Response::error(404, &format!("There's no game {}", id)))
+    }
+}
+
+fn play_bot_moves(game: &mut Game) {
+    while game.status == GameStatus::InProgress && !game.current_player_is_human() {
+        let (row, col) = bot::get_bot_move(game);
+        game.make_move(row, col);
+    }
+}
+
+fn parse_player(value: &Value, key: &str) -> Result<PlayerKind, Response> {
+    match value.as_str().map(str::to_uppercase).as_deref() {
+        Some("X") => Ok(PlayerKind::PlayerX),
+        Some("O") => Ok(PlayerKind::PlayerO),
+        _ => Err(Response::error(400, &format!("\"{}\" should be \"X\" or \"O\"", key))),
+    }
+}
+
+fn parse_move(body: &Value) -> Result<Coord, Response> {
+    if let Some(square) = body.get("square") {
+        return square.as_str()
+            .ok_or_else(|| "\"square\" should be a string like \"b2\"".to_string())
+            .and_then(|square| square.parse::<Coord>())
+            .map_err(|e| Response::error(400, &e));
+    }
+
+    let index = |key: &str| match body.get(key).and_then(Value::as_u64) {
+        Some(n) if (1..=BOARD_SIZE as u64).contains(&n) => Ok(n as usize - 1),
+        _ => Err(Response::error(400, &format!("Expected a \"square\", or a \"row\" and \"col\" from 1 to {}",
+                                               BOARD_SIZE))),
+    };
+    Ok(Coord::new(index("row")?, index("col")?))
+}
+
+fn square_json(coord: Coord) -> Value {
+    json!({ "square": coord.to_string(), "row": coord.row + 1, "col": coord.col + 1 })
+}
+
+fn game_json(id: u64, game: &Game) -> Value {
+    let (status, winner, lines) = match &game.status {
+        GameStatus::InProgress => ("in_progress", None, &[][..]),
+        GameStatus::Win(player, lines) => ("win", Some(player.to_char().to_string()), &lines[..]),
+        GameStatus::Tie => ("tie", None, &[][..]),
+    };
+    let win_lines: Vec<Vec<String>> = lines.iter()
+        .map(|line| line.cells.iter().map(Coord::to_string).collect())
+        .collect();
+    let moves: Vec<String> = game.history.iter()
+        .map(|&(row, col, _)| Coord::new(row, col).to_string())
+        .collect();
+    let kind = |player: &Player| if player.is_human { "human" } else { "bot" };
+
+    json!({
+        "id": id,
+        "board": board_string(&game.board),
+        "next": game.current_player.to_char().to_string(),
+        "status": status,
+        "winner": winner,
+        "win_lines": win_lines,
+        "moves": moves,
+        "x": kind(&game.player1),
+        "o": kind(&game.player2),
+        "difficulty": game.difficulty.to_string(),
+    })
+}
+
+/// `POST /analyze`, with a `board` like `"X.O/.X./..."` and optionally the
+/// `player` to move, which is otherwise worked out from the number of
+/// pieces. Answers with the best move by the same search that gives the
+/// evaluation for the player to move, and the rule of the perfect strategy
+/// that picks it, or a null rule when the rules pick a worse move.
+fn analyze(body: &Value) -> Result<Response, Response> {
+    let board = body.get("board").and_then(Value::as_str)
+        .ok_or_else(|| Response::error(400, "Expected a \"board\" like \"X.O/.X./...\""))
+        .and_then(|board| parse_board_string(board).map_err(|e| Response::error(400, &e)))?;
+    let player = match body.get("player") {
+        Some(player) => parse_player(player, "player")?,
+        None => match count_stones(PlayerKind::PlayerX, &board) as i64 - count_stones(PlayerKind::PlayerO, &board) as i64 {
+            0 => PlayerKind::PlayerX,
+            1 => PlayerKind::PlayerO,
+            _ => return Err(Response::error(400, "Couldn't tell whose turn it is, so give the \"player\"")),
+        },
+    };
+    if has_won(PlayerKind::PlayerX, &board) || has_won(PlayerKind::PlayerO, &board) || is_full(&board) {
+        return Err(Response::error(422, "The game is already over"));
+    }
+
+    let values = expectimax::move_values(player, &board, &Ruleset::default());
+    let evaluation = values.iter().map(|&(_, value)| value).fold(f64::NEG_INFINITY, f64::max);
+    let (rule_move, rule) = bot::explain_move(player, &board);
+    let (square, reason) = if values.contains(&(rule_move, evaluation)) {
+        (rule_move, Some(rule))
+    } else {
+        let &(square, _) = values.iter().find(|&&(_, value)| value == evaluation).expect("The game isn't over");
+        (square, None)
+    };
+    let outcome = if evaluation > 0.0 {
+        "win"
+    } else if evaluation < 0.0 {
+        "loss"
+    } else {
+        "draw"
+    };
+
+    Ok(Response::ok(json!({
+        "player": player.to_char().to_string(),
+        "move": square_json(square.into()),
+        "reason": reason.map(|reason| reason.to_string()),
+        "description": reason.map_or("Found by searching every continuation.", |reason| reason.description()),
+        "evaluation": evaluation,
+        "outcome": outcome,
+    })))
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+#[test]
+fn create_and_play_against_bot_test() {
+    let mut api = Api::new();
+    let response = api.handle("POST", "/games", "");
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body["id"], 1);
+    assert_eq!(response.body["board"], ".../.../...");
+    assert_eq!(response.body["o"], "bot");
+
+    // the bot's reply comes back with the move
+    let response = api.handle("POST", "/games/1/moves", r#"{"square": "a1"}"#);
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["moves"], json!(["a1", "b2"]));
+    assert_eq!(response.body["next"], "X");
+
+    let response = api.handle("POST", "/games/1/moves", r#"{"row": 2, "col": 2}"#);
+    assert_eq!(response, Response::error(409, "b2 is taken"));
+
+    assert_eq!(api.handle("GET", "/games/1", "").body["board"], "X../.O./...");
+}
+
+#[test]
+fn two_humans_and_a_win_test() {
+    let mut api = Api::new();
+    let response = api.handle("POST", "/games", r#"{"o": "human", "first": "O"}"#);
+    assert_eq!(response.body["next"], "O");
+
+    for square in &["a1", "a2", "b1", "b2", "c1"] {
+        api.handle("POST", "/games/1/moves", &format!(r#"{{"square": "{}"}}"#, square));
+    }
+    let game = api.handle("GET", "/games/1", "").body;
+    assert_eq!(game["status"], "win");
+    assert_eq!(game["winner"], "O");
+    assert_eq!(game["win_lines"], json!([["a1", "b1", "c1"]]));
+
+    let response = api.handle("POST", "/games/1/moves", r#"{"square": "c3"}"#);
+    assert_eq!(response, Response::error(409, "The game is over"));
+}
+
+#[test]
+fn bad_requests_test() {
+    let mut api = Api::new();
+    assert_eq!(api.handle("GET", "/games/7", "").status, 404);
+    assert_eq!(api.handle("GET", "/nowhere", "").status, 404);
+    assert_eq!(api.handle("DELETE", "/games/1", "").status, 405);
+    assert_eq!(api.handle("POST", "/games", "{").status, 400);
+    assert_eq!(api.handle("POST", "/games", r#"{"x": "robot"}"#).status, 400);
+    assert_eq!(api.handle("POST", "/games", r#"{"difficulty": "expert"}"#).status, 400);
+
+    api.handle("POST", "/games", "");
+    assert_eq!(api.handle("POST", "/games/1/moves", r#"{"row": 4, "col": 1}"#).status, 400);
+    assert_eq!(api.handle("POST", "/games/1/moves", "{}").status, 400);
+}
+
+#[test]
+fn analyze_test() {
+    let mut api = Api::new();
+    let response = api.handle("POST", "/analyze", r#"{"board": "XX./OO./..."}"#);
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["player"], "X");
+    assert_eq!(response.body["move"], json!({ "square": "c1", "row": 1, "col": 3 }));
+    assert_eq!(response.body["reason"], "win");
+    assert_eq!(response.body["outcome"], "win");
+
+    let response = api.handle("POST", "/analyze", r#"{"board": "X../.../..."}"#);
+    assert_eq!(response.body["player"], "O");
+    assert_eq!(response.body["reason"], "center");
+    assert_eq!(response.body["evaluation"], 0.0);
+    assert_eq!(response.body["outcome"], "draw");
+
+    // a position perfect play never reaches still gets an empty square
+    let response = api.handle("POST", "/analyze", r#"{"board": "XOO/..X/.X.", "player": "O"}"#);
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["move"]["square"], "b2");
+
+    // where the perfect strategy's rules settle for less, the search's move
+    // is suggested, so the evaluation is the suggested move's
+    let response = api.handle("POST", "/analyze", r#"{"board": "X.O/.../..."}"#);
+    assert_eq!(response.body["reason"], Value::Null);
+    assert_eq!(response.body["evaluation"], 3.0);
+    assert_eq!(response.body["outcome"], "win");
+    assert_ne!(response.body["move"]["square"], "b2");
+
+    assert_eq!(api.handle("POST", "/analyze", r#"{"board": "XXX/OO./..."}"#).status, 422);
+    assert_eq!(api.handle("POST", "/analyze", r#"{"board": "XX./.../..."}"#).status, 400);
+    assert_eq!(api.handle("POST", "/analyze", r#"{"board": "XX./.../...", "player": "O"}"#).status, 200);
+    assert_eq!(api.handle("POST", "/analyze", r#"{"board": "XX"}"#).status, 400);
+}
@@ -0,0 +1,449 @@
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+use tic_tac_torture::*;
+use tic_tac_torture::net::{self, Role};
+use tic_tac_torture::records::{self, LoadedRecords, RecordStore, Recorder};
+use tic_tac_torture::render::{self, Format, RenderOptions};
+use tic_tac_torture::replay::{self, Replay};
+use tic_tac_torture::scheduler::BotScheduler;
+
+use script::Script;
+
+mod network;
+mod order_chaos;
+mod replay_viewer;
+mod script;
+mod stats;
+#[cfg(test)]
+mod tests;
+
+const DISPLAY_EMPTY_SQUARE: char = '*';
+
+/// A line of input at the prompt
+#[derive(Debug, PartialEq)]
+enum Command {
+    Move(Coord),
+    Undo,
+    Hint,
+    New,
+    Swap,
+    Board,
+    Export(Format, PathBuf),
+    Help,
+    Quit,
+}
+
+fn print_instructions() {
+    println!("Welcome to Tic Tac Toe");
+    println!("Type `help` for a list of commands.");
+    println!();
+}
+
+fn print_help() {
+    println!("Moves can be entered by file and rank, e.g. `b2` for the center square.");
+    println!("Files are the columns, a to c from left to right, and ranks are the rows,");
+    println!("1 to 3 from top to bottom. A row and column number works too, e.g. `2 2`.");
+    println!();
+    println!("Commands:");
+    println!("  undo    take back your last move");
+    println!("  hint    suggest a move for the current player");
+    println!("  new     start a new match with the same settings");
+    println!("  swap    swap sides with the bot");
+    println!("  board   show the board");
+    println!("  export  save the board as an image, e.g. `export board.svg` or `export board.png`,");
+    println!("          or every move as an animated GIF, e.g. `export game.gif`");
+    println!("  help    show this message");
+    println!("  quit    leave the game");
+    println!();
+    println!("Options: --play-as <X|O|both|none>, --first <X|O>, --best-of <N>,");
+    println!("         --difficulty <easy|medium|hard>, --random-turn,");
+    println!("         --double-first-move <X|O>, --stones <X|O> <square>...");
+    println!("Scripting: --moves \"<square>...\" or --stdin, with --respond for a bot reply.");
+    println!("Images: --export-svg <file>, --export-png <file> or --export-gif <file> with a script");
+    println!("        or a replay, with --size <pixels>, --theme <dark|light|high-contrast> and");
+    println!("        --frame-delay <ms> for a GIF.");
+    println!("Network: --host [port] or --join <address[:port]>, with --name <name>.");
+    println!("Replay: --replay \"<square>...\" or --replay-game <n>, 1 for the latest recorded");
+    println!("        game, with --records <file> to pick from another record file and --speed <ms>");
+    println!("        for autoplay.");
+    println!("Run `cli stats` for a summary of every recorded game, or `cli order-chaos` to play");
+    println!("Order and Chaos on a 6x6 board.");
+    println!();
+}
+
+// prints the board with the files labelled along the top and the ranks down
+// the side, the same names moves are entered with. the squares of a winning
+// line are marked with brackets
+fn print_board(game: &Game) {
+    let win_lines: &[Line] = match &game.status {
+        GameStatus::Win(_, lines) => lines,
+        _ => &[],
+    };
+
+    println!();
+    print!("    ");
+    for j in 0..game.board[0].len() {
+        print!(" {} ", file_char(j));
+    }
+    println!();
+    for i in 0..game.board.len() {
+        print!("  {} ", i + 1);
+        for j in 0..game.board[0].len() {
+            let symbol = match game.board[i][j] {
+                Some(p) => p.to_char(),
+                None => DISPLAY_EMPTY_SQUARE,
+            };
+            if win_lines.iter().any(|line| line.contains(Coord::new(i, j))) {
+                print!("[{}]", symbol);
+            } else {
+                print!(" {} ", symbol);
+            }
+        }
+        println!()
+    }
+    println!();
+}
+
+/// Names the squares of a line, e.g. "a1 b2 c3"
+fn line_name(line: &Line) -> String {
+    let cells: Vec<String> = line.cells.iter().map(Coord::to_string).collect();
+    cells.join(" ")
+}
+
+fn print_exit_message(game: &Game) {
+    match &game.status {
+        GameStatus::Tie => println!("Tie game."),
+        GameStatus::Win(player, lines) if lines.is_empty() => {
+            println!("Player {} has won, {} resigned.", player.to_char(), player.opposite().to_char());
+        },
+        GameStatus::Win(player, lines) => {
+            let lines: Vec<String> = lines.iter().map(line_name).collect();
+            println!("Player {} has won with {}!", player.to_char(), lines.join(" and "));
+        },
+        _ => (),
+    }
+}
+
+fn print_match_result(series: &Match) {
+    match series.status() {
+        MatchStatus::Win(player) => {
+            println!("Player {} wins the match, {}!", player.to_char(), series.score())
+        },
+        MatchStatus::Draw => println!("The match is drawn, {}.", series.score()),
+        MatchStatus::InProgress => (),
+    }
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    // the file name keeps its case, so it's split off before lowercasing
+    let mut words = line.splitn(2, char::is_whitespace);
+    if words.next().map(str::to_lowercase).as_deref() == Some("export") {
+        let path = match words.next().map(str::trim) {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => return Err("Type `export` and a file to save the board to, e.g. `export board.svg`.".to_string()),
+        };
+        return Format::from_path(&path).map(|format| Command::Export(format, path));
+    }
+    match line.to_lowercase().as_str() {
+        "undo" | "u" => Ok(Command::Undo),
+        "hint" => Ok(Command::Hint),
+        "new" | "n" => Ok(Command::New),
+        "swap" => Ok(Command::Swap),
+        "board" | "b" => Ok(Command::Board),
+        "help" | "h" | "?" => Ok(Command::Help),
+        "quit" | "q" | "exit" => Ok(Command::Quit),
+        _ => line.parse::<Coord>().map(Command::Move),
+    }
+}
+
+/// Lets the bot move for as long as it has the turn, then reports the
+/// result if the game ended
+fn play_bot_moves(series: &mut Match, recorder: &mut Recorder) {
+    // there's nothing to draw while the bot thinks at a prompt, so its moves
+    // are waited for with no delay
+    let mut bot = BotScheduler::new(Duration::from_millis(0));
+    while let Some((row, col)) = bot.play(&mut series.game) {
+        let player = series.game.current_player;
+        series.make_move(row, col);
+        println!("Bot plays {} at {}", player.to_char(), Coord::new(row, col));
+    }
+    if bot.has_failed(series.game.turn_id) {
+        eprintln!("The bot couldn't find a move.");
+        process::exit(1);
+    }
+    record(recorder, &series.game);
+}
+
+/// Stores the game once it's finished
+fn record(recorder: &mut Recorder, game: &Game) {
+    let (x, o) = records::local_names(game);
+    if let Err(e) = recorder.update(game, &x, &o) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Saves every image asked for on the command line of `game`, exiting if
+/// one can't be written
+fn export_or_exit(game: &Game, exports: &[(Format, PathBuf)], options: &RenderOptions) {
+    for (format, path) in exports {
+        if let Err(e) = render::save_game(game, *format, path, options) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Called after every move. Announces a game as it finishes, along with the
+/// score in a longer match, and starts the next game of the match if there
+/// is one, timing it in `recorder` as a new game. The finished game is kept
+/// in `last_game`, so it can still be exported, and so it's only announced
+/// once.
+fn after_move(series: &mut Match, recorder: &mut Recorder, last_game: &mut Option<Game>) {
+    if series.game.status == GameStatus::InProgress {
+        return;
+    }
+    // every move changes the turn id, so a finished position is announced
+    // the first time it's seen
+    if last_game.as_ref().map(|game| game.turn_id) == Some(series.game.turn_id) {
+        return;
+    }
+    *last_game = Some(series.game.clone());
+
+    print_board(&series.game);
+    print_exit_message(&series.game);
+
+    if series.best_of > 1 {
+        println!("Score: {}", series);
+        println!();
+    }
+
+    match series.status() {
+        MatchStatus::InProgress => {
+            series.next_game();
+            recorder.restart();
+            println!("Starting the next game.");
+        },
+        _ => {
+            print_match_result(series);
+            println!("Type `new` to play again or `quit` to leave.");
+        },
+    }
+}
+
+fn swap_sides(series: &mut Match) {
+    let game = &mut series.game;
+    game.player1.is_human = !game.player1.is_human;
+    game.player2.is_human = !game.player2.is_human;
+
+    let config = &mut series.config;
+    config.x_is_human = game.player1.is_human;
+    config.o_is_human = game.player2.is_human;
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.get(1).map(String::as_str) == Some("stats") {
+        let store = RecordStore::open_default();
+        match store.as_ref().map_or(Ok(LoadedRecords::default()), RecordStore::load) {
+            Ok(loaded) => {
+                if let Some(warning) = store.as_ref().and_then(|store| loaded.warning(store)) {
+                    eprintln!("{}", warning);
+                }
+                stats::print_stats(&loaded.records);
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("order-chaos") {
+        match order_chaos::game_from_args(&args) {
+            Ok(game) => order_chaos::run(game),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+        println!("Goodbye.");
+        return;
+    }
+
+    let images = RenderOptions::from_args(&args).and_then(|options| Ok((options, render::exports_from_args(&args)?)));
+    let (options, exports) = match images {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    match Script::from_args(&args).and_then(|script| Ok((script, GameConfig::from_args(&args)?))) {
+        Ok((Some(script), config)) => {
+            let mut game = config.build();
+            let (report, code) = script.run(&mut game);
+            println!("{}", report);
+            export_or_exit(&game, &exports, &options);
+            process::exit(code);
+        },
+        Ok((None, _)) => (),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(script::EXIT_INVALID);
+        }
+    }
+
+    match Replay::from_args(&args).and_then(|replay| Ok((replay, replay::delay_from_args(&args)?))) {
+        // with images to export, the game is saved instead of shown
+        Ok((Some(replay), _)) if !exports.is_empty() => {
+            export_or_exit(&replay.plies[replay.last()].game, &exports, &options);
+            return;
+        },
+        Ok((Some(replay), delay)) => {
+            replay_viewer::run(replay, delay);
+            println!("Goodbye.");
+            return;
+        },
+        Ok((None, _)) => (),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+
+    if !exports.is_empty() {
+        eprintln!("--export-svg, --export-png and --export-gif save a game given by --moves, --stdin, --replay \
+                   or --replay-game. In a game, use the `export` command instead.");
+        process::exit(2);
+    }
+
+    match Role::from_args(&args).and_then(|role| Ok((role, GameConfig::from_args(&args)?))) {
+        Ok((Some(role), config)) => {
+            network::run(&role, &net::player_name(&args), config);
+            println!("Goodbye.");
+            return;
+        },
+        Ok((None, _)) => (),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+
+    let mut series = match Match::from_args(&args) {
+        Ok(series) => series,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let mut editor = Editor::<()>::new();
+    let mut recorder = Recorder::new(RecordStore::open_default());
+    // the last game to finish, which `export` saves once the next has begun
+    let mut last_game = None;
+    // the position last printed, which isn't printed again until it changes
+    let mut shown_turn = None;
+    print_instructions();
+
+    loop {
+        play_bot_moves(&mut series, &mut recorder);
+        after_move(&mut series, &mut recorder, &mut last_game);
+        if !series.game.current_player_is_human() && series.status() == MatchStatus::InProgress {
+            continue;
+        }
+
+        let prompt = if series.status() == MatchStatus::InProgress {
+            if shown_turn != Some(series.game.turn_id) {
+                print_board(&series.game);
+                shown_turn = Some(series.game.turn_id);
+            }
+            format!("{} >> ", series.game.current_player.to_char())
+        } else {
+            ">> ".to_string()
+        };
+
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // ctrl-d or ctrl-c
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => break,
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                process::exit(1);
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str());
+
+        let command = match parse_command(&line) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        match command {
+            Command::Move(coord) => {
+                if series.status() != MatchStatus::InProgress {
+                    println!("The match is over. Type `new` to play again.");
+                } else if series.game.board[coord.row][coord.col].is_some() {
+                    println!("{} is taken.", coord);
+                } else {
+                    series.make_move(coord.row, coord.col);
+                    record(&mut recorder, &series.game);
+                    after_move(&mut series, &mut recorder, &mut last_game);
+                }
+            },
+            Command::Undo => {
+                if !series.undo_to_human() {
+                    println!("There are no moves to undo.");
+                }
+            },
+            Command::Hint => {
+                if series.game.status == GameStatus::InProgress {
+                    let hint: Coord = bot::get_game_move(&series.game).into();
+                    println!("Try {}.", hint);
+                } else {
+                    println!("The game is over.");
+                }
+            },
+            Command::New => {
+                series = Match::new(series.config.clone(), series.best_of);
+                recorder.restart();
+            },
+            Command::Swap => swap_sides(&mut series),
+            Command::Board => {
+                print_board(&series.game);
+                shown_turn = Some(series.game.turn_id);
+            },
+            Command::Export(format, path) => {
+                let game = match &last_game {
+                    Some(game) if series.game.history.is_empty() => game,
+                    _ => &series.game,
+                };
+                match render::save_game(game, format, &path, &options) {
+                    Ok(()) => println!("Saved the game to {}.", path.display()),
+                    Err(e) => println!("{}", e),
+                }
+            },
+            Command::Help => print_help(),
+            Command::Quit => break,
+        }
+    }
+
+    println!("Goodbye.");
+}
@@ -0,0 +1,200 @@
+use std::process;
+
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+use tic_tac_torture::*;
+use tic_tac_torture::net::{Role, Session, SessionEvent};
+use tic_tac_torture::records::{RecordStore, Recorder};
+
+use super::print_board;
+
+/// A line of input at the prompt in a network game
+#[derive(Debug, PartialEq)]
+enum Command {
+    Move(Coord),
+    Resign,
+    Rematch,
+    Board,
+    Help,
+    Quit,
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    match line.to_lowercase().as_str() {
+        "resign" => Ok(Command::Resign),
+        "rematch" | "r" => Ok(Command::Rematch),
+        "board" | "b" => Ok(Command::Board),
+        "help" | "h" | "?" => Ok(Command::Help),
+        "quit" | "q" | "exit" => Ok(Command::Quit),
+        _ => line.parse::<Coord>().map(Command::Move),
+    }
+}
+
+fn print_help() {
+    println!("Moves are entered as in a local game, e.g. `b2` for the center square.");
+    println!();
+    println!("Commands:");
+    println!("  resign   give up the game");
+    println!("  rematch  ask for another game once this one is over");
+    println!("  board    show the board");
+    println!("  help     show this message");
+    println!("  quit     leave the game");
+    println!();
+}
+
+/// Says how the game ended, from this end's point of view
+fn print_result(session: &Session) {
+    let opponent = &session.opponent_name;
+    match &session.game.status {
+        GameStatus::Win(player, lines) if *player == session.side => {
+            if lines.is_empty() {
+                println!("{} resigned, you win!", opponent);
+            } else {
+                println!("You win!");
+            }
+        },
+        GameStatus::Win(_, lines) if lines.is_empty() => println!("You resigned, {} wins.", opponent),
+        GameStatus::Win(_, _) => println!("{} wins.", opponent),
+        GameStatus::Tie => println!("Tie game."),
+        GameStatus::InProgress => return,
+    }
+    println!("Type `rematch` to play again, or `quit` to leave.");
+}
+
+/// Stores the game once it's finished, under this end's name and the other
+/// player's
+fn record(recorder: &mut Recorder, session: &Session, name: &str) {
+    let (x, o) = session.player_names(name);
+    if let Err(e) = recorder.update(&session.game, x, o) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Shows what happened, returning false once the other player has left
+fn report(session: &Session, event: SessionEvent, recorder: &mut Recorder, name: &str) -> bool {
+    match event {
+        SessionEvent::Updated => {
+            record(recorder, session, name);
+            print_board(&session.game);
+            print_result(session);
+        },
+        SessionEvent::RematchOffered => {
+            println!("{} wants a rematch. Type `rematch` to play again.", session.opponent_name);
+        },
+        SessionEvent::Refused(e) => println!("{}.", e),
+        SessionEvent::Closed => {
+            println!("{} has left.", session.opponent_name);
+            return false;
+        },
+    }
+    true
+}
+
+/// Plays a game against another player over the network, taking this end's
+/// moves at a prompt
+pub fn run(role: &Role, name: &str, config: GameConfig) {
+    match role {
+        Role::Host(port) => println!("Waiting for a player to join on port {}...", port),
+        Role::Join(address) => println!("Joining {}...", address),
+    }
+    let mut session = match role.connect(name, config) {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    println!("Playing {} against {}. Type `help` for a list of commands.",
+             session.side.to_char(), session.opponent_name);
+    print_board(&session.game);
+
+    let mut editor = Editor::<()>::new();
+    let mut recorder = Recorder::new(RecordStore::open_default());
+    loop {
+        // anything that came in while this end was at the prompt
+        while let Some(event) = session.poll() {
+            if !report(&session, event, &mut recorder, name) {
+                return;
+            }
+        }
+
+        let game_over = session.game.status != GameStatus::InProgress;
+        if (!game_over && !session.is_my_turn()) || (game_over && session.rematch_requested()) {
+            println!("Waiting for {}...", session.opponent_name);
+            let event = session.wait();
+            if !report(&session, event, &mut recorder, name) {
+                return;
+            }
+            continue;
+        }
+
+        let prompt = if game_over {
+            ">> ".to_string()
+        } else {
+            format!("{} >> ", session.side.to_char())
+        };
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => return,
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                process::exit(1);
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str());
+
+        let command = match parse_command(&line) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        let result = match command {
+            Command::Move(coord) => session.play(coord),
+            Command::Resign => session.resign(),
+            Command::Rematch => session.rematch(),
+            Command::Board => {
+                print_board(&session.game);
+                continue;
+            },
+            Command::Help => {
+                print_help();
+                continue;
+            },
+            Command::Quit => return,
+        };
+        if let Err(e) = result {
+            println!("{}.", e);
+            continue;
+        }
+
+        match command {
+            // the host's own moves are played straight away
+            Command::Move(_) | Command::Resign if session.is_host => {
+                record(&mut recorder, &session, name);
+                print_board(&session.game);
+                print_result(&session);
+            },
+            // while the guest's come back once the host has checked them
+            Command::Move(_) | Command::Resign => {
+                let event = session.wait();
+                if !report(&session, event, &mut recorder, name) {
+                    return;
+                }
+            },
+            Command::Rematch if session.is_host && !session.rematch_requested() => {
+                record(&mut recorder, &session, name);
+                print_board(&session.game);
+            },
+            _ => (),
+        }
+    }
+}
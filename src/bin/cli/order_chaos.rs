@@ -0,0 +1,186 @@
+use std::process;
+
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+use tic_tac_torture::order_chaos::{self, OrderChaosGame, OrderChaosStatus, Role, ORDER_CHAOS_BOARD_SIZE,
+                                   ORDER_CHAOS_WIN_LENGTH};
+use tic_tac_torture::{file_char, Coord, PlayerKind};
+
+use super::DISPLAY_EMPTY_SQUARE;
+
+/// A line of input at the prompt in Order and Chaos
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Move(usize, usize, PlayerKind),
+    New,
+    Board,
+    Help,
+    Quit,
+}
+
+fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::Order => "Order",
+        Role::Chaos => "Chaos",
+    }
+}
+
+/// Sets up a game from `--play-as <order|chaos|both|none>`, the human
+/// playing Order against the bot by default
+pub fn game_from_args(args: &[String]) -> Result<OrderChaosGame, String> {
+    let mut game = OrderChaosGame::new();
+    if let Some(i) = args.iter().position(|arg| arg == "--play-as") {
+        let (order, chaos) = match args.get(i + 1).map(|arg| arg.to_lowercase()).as_deref() {
+            Some("order") => (true, false),
+            Some("chaos") => (false, true),
+            Some("both") => (true, true),
+            Some("none") => (false, false),
+            _ => return Err("--play-as needs a role, order, chaos, both or none".to_string()),
+        };
+        game.order_is_human = order;
+        game.chaos_is_human = chaos;
+    }
+    Ok(game)
+}
+
+/// Parses a square on the 6x6 board by file and rank, e.g. `c4`
+fn parse_square(s: &str) -> Option<(usize, usize)> {
+    let mut chars = s.chars();
+    let file = chars.next()?.to_ascii_lowercase();
+    let rank = chars.as_str().parse::<usize>().ok()?;
+    let col = (file as usize).checked_sub('a' as usize)?;
+    if col < ORDER_CHAOS_BOARD_SIZE && (1..=ORDER_CHAOS_BOARD_SIZE).contains(&rank) {
+        Some((rank - 1, col))
+    } else {
+        None
+    }
+}
+
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    match line.to_lowercase().as_str() {
+        "new" | "n" => return Ok(Command::New),
+        "board" | "b" => return Ok(Command::Board),
+        "help" | "h" | "?" => return Ok(Command::Help),
+        "quit" | "q" | "exit" => return Ok(Command::Quit),
+        _ => (),
+    }
+
+    // a square and a symbol, in either order
+    let words: Vec<String> = line.split_whitespace().map(str::to_uppercase).collect();
+    let (square, symbol) = match words.as_slice() {
+        [square, symbol] if symbol == "X" || symbol == "O" => (square, symbol),
+        [symbol, square] if symbol == "X" || symbol == "O" => (square, symbol),
+        _ => return Err("Type a square and the symbol to put there, e.g. `c4 X`.".to_string()),
+    };
+    let (row, col) = parse_square(square)
+        .ok_or_else(|| format!("\"{}\" isn't a square, try a file and rank from a1 to f6", square.to_lowercase()))?;
+    let symbol = if symbol == "X" { PlayerKind::PlayerX } else { PlayerKind::PlayerO };
+    Ok(Command::Move(row, col, symbol))
+}
+
+fn print_instructions() {
+    println!("Welcome to Order and Chaos");
+    println!("Both sides place X or O on a 6x6 board. Order wins with {} of one symbol in a row,",
+             ORDER_CHAOS_WIN_LENGTH);
+    println!("Chaos wins if the board fills up first. Type `help` for a list of commands.");
+    println!();
+}
+
+fn print_help() {
+    println!("Moves are a square by file and rank and the symbol to put there, e.g. `c4 X`.");
+    println!("Files are the columns, a to f from left to right, and ranks are the rows,");
+    println!("1 to 6 from top to bottom.");
+    println!();
+    println!("Commands:");
+    println!("  new     start a new game");
+    println!("  board   show the board");
+    println!("  help    show this message");
+    println!("  quit    leave the game");
+    println!();
+    println!("Options: --play-as <order|chaos|both|none>");
+    println!();
+}
+
+fn print_board(game: &OrderChaosGame) {
+    println!();
+    print!("    ");
+    for col in 0..ORDER_CHAOS_BOARD_SIZE {
+        print!(" {} ", file_char(col));
+    }
+    println!();
+    for (row, squares) in game.board.iter().enumerate() {
+        print!("  {} ", row + 1);
+        for square in squares.iter() {
+            print!(" {} ", square.map_or(DISPLAY_EMPTY_SQUARE, |symbol| symbol.to_char()));
+        }
+        println!();
+    }
+    println!();
+}
+
+/// Plays Order and Chaos at the prompt against the bot in `order_chaos::bot`
+pub fn run(mut game: OrderChaosGame) {
+    let mut editor = Editor::<()>::new();
+    print_instructions();
+    print_board(&game);
+    let mut announced = false;
+
+    loop {
+        if let OrderChaosStatus::Win(role) = game.status {
+            if !announced {
+                announced = true;
+                println!("{} wins!", role_name(role));
+                println!("Type `new` to play again or `quit` to leave.");
+            }
+        } else if !game.current_role_is_human() {
+            let role = game.current_role;
+            let (row, col, symbol) = order_chaos::bot::get_move(role, &game.board);
+            game.make_move(row, col, symbol);
+            println!("{} plays {} at {}", role_name(role), symbol.to_char(), Coord::new(row, col));
+            print_board(&game);
+            continue;
+        }
+
+        let prompt = match game.status {
+            OrderChaosStatus::InProgress => format!("{} >> ", role_name(game.current_role)),
+            OrderChaosStatus::Win(_) => ">> ".to_string(),
+        };
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // ctrl-d or ctrl-c
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => break,
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                process::exit(1);
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str());
+
+        match parse_command(&line) {
+            Ok(Command::Move(row, col, symbol)) => {
+                if game.status != OrderChaosStatus::InProgress {
+                    println!("The game is over. Type `new` to play again.");
+                } else if game.make_move(row, col, symbol) {
+                    print_board(&game);
+                } else {
+                    println!("{} is taken.", Coord::new(row, col));
+                }
+            },
+            Ok(Command::New) => {
+                game.reset();
+                announced = false;
+                print_board(&game);
+            },
+            Ok(Command::Board) => print_board(&game),
+            Ok(Command::Help) => print_help(),
+            Ok(Command::Quit) => break,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+use tic_tac_torture::replay::Replay;
+
+use super::{print_board, print_exit_message};
+
+/// A line of input at the prompt in a replay
+#[derive(Debug, PartialEq)]
+enum Command {
+    Next,
+    Back,
+    First,
+    Last,
+    Jump(usize),
+    Play,
+    Help,
+    Quit,
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    match line.to_lowercase().as_str() {
+        // a bare Enter steps forward, so a game can be read by tapping it
+        "" | "next" | "n" => Ok(Command::Next),
+        "back" | "prev" | "p" => Ok(Command::Back),
+        "first" | "start" => Ok(Command::First),
+        "last" | "end" => Ok(Command::Last),
+        "play" => Ok(Command::Play),
+        "help" | "h" | "?" => Ok(Command::Help),
+        "quit" | "q" | "exit" => Ok(Command::Quit),
+        _ => line.parse::<usize>().map(Command::Jump)
+            .map_err(|_| format!("Unknown command \"{}\". Type `help` for a list of commands.", line)),
+    }
+}
+
+fn print_help() {
+    println!("Commands:");
+    println!("  next    show the next move, or just press Enter");
+    println!("  back    take back a move");
+    println!("  first   go back to the empty board");
+    println!("  last    go to the end of the game");
+    println!("  <n>     go to the position after n moves");
+    println!("  play    play the rest of the game at the autoplay speed");
+    println!("  help    show this message");
+    println!("  quit    leave the replay");
+    println!();
+}
+
+/// Shows the position on screen along with the move that led to it and what
+/// the bot makes of it
+fn print_ply(replay: &Replay) {
+    let ply = replay.ply();
+    print_board(&ply.game);
+
+    match ply.last_move {
+        Some((coord, player)) => {
+            println!("Move {} of {}: {} played {}.", replay.current, replay.last(), player.to_char(), coord)
+        },
+        None => println!("Start of the game, {} moves to go.", replay.last()),
+    }
+    if ply.blunder {
+        let previous = &replay.plies[replay.current - 1];
+        print!("Blunder! The result with best play went from {} to {}.", previous.outcome, ply.outcome);
+        match previous.best_move {
+            Some(best) => println!(" The bot would have played {}.", best),
+            None => println!(),
+        }
+    }
+    match ply.evaluation {
+        Some(score) => {
+            println!("Evaluation for {}: {:+} ({} with best play)", ply.game.current_player.to_char(), score,
+                     ply.outcome)
+        },
+        None => print_exit_message(&ply.game),
+    }
+}
+
+/// Steps through a finished game at a prompt, with autoplay waiting `delay`
+/// between moves
+pub fn run(mut replay: Replay, delay: Duration) {
+    println!("Replaying a game of {} moves. Type `help` for a list of commands.", replay.last());
+    print_ply(&replay);
+
+    let mut editor = Editor::<()>::new();
+    loop {
+        let prompt = format!("{}/{} >> ", replay.current, replay.last());
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => return,
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                process::exit(1);
+            }
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str());
+        }
+
+        let command = match parse_command(&line) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        match command {
+            Command::Next if !replay.forward() => println!("That's the end of the game."),
+            Command::Back if !replay.back() => println!("That's the start of the game."),
+            Command::Next | Command::Back => print_ply(&replay),
+            Command::First => {
+                replay.jump(0);
+                print_ply(&replay);
+            },
+            Command::Last => {
+                replay.jump(replay.last());
+                print_ply(&replay);
+            },
+            Command::Jump(ply) if ply > replay.last() => {
+                println!("The game only has {} moves.", replay.last());
+            },
+            Command::Jump(ply) => {
+                replay.jump(ply);
+                print_ply(&replay);
+            },
+            Command::Play => {
+                // playing from the end starts over
+                if replay.at_end() {
+                    replay.jump(0);
+                    print_ply(&replay);
+                }
+                while replay.forward() {
+                    thread::sleep(delay);
+                    print_ply(&replay);
+                }
+            },
+            Command::Help => print_help(),
+            Command::Quit => return,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::{self, Read};
+
+use tic_tac_torture::*;
+
+use super::line_name;
+
+// exit codes for a scripted game, chosen to stay clear of 1, which is used
+// when the program fails for some other reason
+pub const EXIT_IN_PROGRESS: i32 = 0;
+pub const EXIT_INVALID: i32 = 2;
+pub const EXIT_X_WON: i32 = 3;
+pub const EXIT_O_WON: i32 = 4;
+pub const EXIT_TIE: i32 = 5;
+
+/// A sequence of moves to play without prompting, given by `--moves` or
+/// `--stdin`
+pub struct Script {
+    /// Each move as a square, e.g. `b2` or `2,2`
+    pub moves: Vec<String>,
+    /// Let the bot play one more move once the script's moves are played
+    pub respond: bool,
+}
+
+impl Script {
+    /// Reads a script from command line arguments, returning `None` when
+    /// the CLI should run interactively:
+    ///
+    /// * `--moves "<square>..."` - the moves to play, separated by spaces
+    /// * `--stdin` - read the moves from standard input instead
+    /// * `--respond` - have the bot reply to the last move
+    pub fn from_args(args: &[String]) -> Result<Option<Script>, String> {
+        let respond = args.iter().any(|arg| arg == "--respond");
+
+        let text = if let Some(i) = args.iter().position(|arg| arg == "--moves") {
+            match args.get(i + 1) {
+                Some(moves) => moves.clone(),
+                None => return Err("--moves needs a list of squares, e.g. --moves \"b2 a1\"".to_string()),
+            }
+        } else if args.iter().any(|arg| arg == "--stdin") {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read moves from stdin: {}", e))?;
+            text
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(Script {
+            moves: text.split_whitespace().map(String::from).collect(),
+            respond,
+        }))
+    }
+
+    /// Plays the script on `game`, returning a report of the result as
+    /// `key=value` lines and the exit code for it. Moves are played for
+    /// whichever player is to move, and the script stops at the first move
+    /// that can't be played.
+    pub fn run(&self, game: &mut Game) -> (String, i32) {
+        let mut played = Vec::new();
+        let mut error = None;
+
+        for square in &self.moves {
+            let coord = match square.parse::<Coord>() {
+                Ok(coord) => coord,
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            };
+
+            if game.status != GameStatus::InProgress {
+                error = Some(format!("{} was played after the game ended", coord));
+                break;
+            }
+            if game.board[coord.row][coord.col].is_some() {
+                error = Some(format!("{} is already taken", coord));
+                break;
+            }
+
+            game.make_move(coord.row, coord.col);
+            played.push(coord);
+        }
+
+        let mut bot_move = None;
+        if self.respond && error.is_none() && game.status == GameStatus::InProgress {
+            let coord: Coord = bot::get_bot_move(game).into();
+            if game.make_move(coord.row, coord.col) {
+                bot_move = Some(coord);
+            } else {
+                error = Some(format!("The bot picked {}, which is already taken", coord));
+            }
+        }
+
+        let moves: Vec<String> = played.iter().map(Coord::to_string).collect();
+        let mut report = vec![
+            format!("board={}", board_string(&game.board)),
+            format!("moves={}", moves.join(" ")),
+        ];
+        if let Some(coord) = bot_move {
+            report.push(format!("bot_move={}", coord));
+        }
+
+        let code = match &game.status {
+            GameStatus::InProgress => {
+                report.push("status=in_progress".to_string());
+                report.push(format!("next={}", game.current_player.to_char()));
+                EXIT_IN_PROGRESS
+            },
+            GameStatus::Tie => {
+                report.push("status=tie".to_string());
+                EXIT_TIE
+            },
+            GameStatus::Win(player, lines) => {
+                let lines: Vec<String> = lines.iter().map(line_name).collect();
+                report.push("status=win".to_string());
+                report.push(format!("winner={}", player.to_char()));
+                report.push(format!("win_lines={}", lines.join(",")));
+                match player {
+                    PlayerKind::PlayerX => EXIT_X_WON,
+                    PlayerKind::PlayerO => EXIT_O_WON,
+                }
+            },
+        };
+
+        let code = match error {
+            Some(e) => {
+                report.push(format!("error={}", e));
+                EXIT_INVALID
+            },
+            None => code,
+        };
+
+        (report.join("\n"), code)
+    }
+}
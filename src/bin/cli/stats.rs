@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use tic_tac_torture::*;
+use tic_tac_torture::records::{self, GameRecord};
+
+const OPENING_LENGTH: usize = 2;
+const LIMIT: usize = 5;
+
+fn squares(moves: &[Coord]) -> String {
+    let squares: Vec<String> = moves.iter().map(Coord::to_string).collect();
+    squares.join(" ")
+}
+
+/// A duration like "1m 05.3s", or "5.3s" under a minute
+fn duration_name(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 60.0 {
+        format!("{:.1}s", seconds)
+    } else {
+        format!("{}m {:04.1}s", duration.as_secs() / 60, seconds % 60.0)
+    }
+}
+
+fn result_name(record: &GameRecord) -> String {
+    match record.winner {
+        Some(player) if record.resigned => format!("{} won by resignation", record.name(player)),
+        Some(player) => format!("{} won", record.name(player)),
+        None => "tie".to_string(),
+    }
+}
+
+/// Prints a summary of every game in the record store: each player's
+/// record, the most common openings and the longest games
+pub fn print_stats(records: &[GameRecord]) {
+    if records.is_empty() {
+        println!("No games have been recorded yet.");
+        return;
+    }
+
+    println!("Games recorded: {}", records.len());
+    println!();
+    println!("Players:");
+    for stats in records::player_stats(records) {
+        println!("  {:<12} {} ({:.0}% won)", stats.name, stats, stats.win_rate() * 100.0);
+    }
+
+    println!();
+    println!("Most common openings:");
+    for (moves, count) in records::common_openings(records, OPENING_LENGTH, LIMIT) {
+        println!("  {:<8} {} game{}", squares(&moves), count, if count == 1 { "" } else { "s" });
+    }
+
+    println!();
+    println!("Longest games:");
+    for record in records::longest_games(records, LIMIT) {
+        println!("  {:>9}  {} (X) vs {} (O), {} in {} moves", duration_name(record.duration),
+                 record.x, record.o, result_name(record), record.moves.len());
+    }
+}
@@ -0,0 +1,105 @@
+use super::*;
+use super::script::*;
+use super::order_chaos;
+
+fn script(moves: &str, respond: bool) -> Script {
+    Script {
+        moves: moves.split_whitespace().map(String::from).collect(),
+        respond,
+    }
+}
+
+#[test]
+fn script_reports_win_test() {
+    let (report, code) = script("a1 b1 a2 b2 a3", false).run(&mut Game::new());
+    assert_eq!(report, "board=XO./XO./X..\nmoves=a1 b1 a2 b2 a3\nstatus=win\nwinner=X\nwin_lines=a1 a2 a3");
+    assert_eq!(code, EXIT_X_WON);
+}
+
+#[test]
+fn script_reports_tie_test() {
+    let (report, code) = script("a1 b2 c3 b1 b3 a3 c1 c2 a2", false).run(&mut Game::new());
+    assert!(report.contains("status=tie"));
+    assert_eq!(code, EXIT_TIE);
+}
+
+#[test]
+fn script_stops_at_invalid_move_test() {
+    let (report, code) = script("b2 b2 a1", false).run(&mut Game::new());
+    assert!(report.contains("moves=b2\n"));
+    assert!(report.contains("error=b2 is already taken"));
+    assert_eq!(code, EXIT_INVALID);
+
+    let (_, code) = script("b2 d4", false).run(&mut Game::new());
+    assert_eq!(code, EXIT_INVALID);
+
+    let (report, code) = script("a1 b1 a2 b2 a3 c3", false).run(&mut Game::new());
+    assert!(report.contains("error=c3 was played after the game ended"));
+    assert_eq!(code, EXIT_INVALID);
+}
+
+#[test]
+fn script_bot_responds_test() {
+    // the bot blocks X's row
+    let (report, code) = script("a1 b2 b1", true).run(&mut Game::new());
+    assert!(report.contains("bot_move=c1"));
+    assert!(report.contains("next=X"));
+    assert_eq!(code, EXIT_IN_PROGRESS);
+
+    // off the perfect-play path, the bot still plays an empty square
+    let (report, code) = script("a1 b1 c2 c1 b3", true).run(&mut Game::new());
+    assert!(report.contains("board=XOO/.OX/.X."), "{}", report);
+    assert!(report.contains("bot_move=b2"));
+    assert_eq!(code, EXIT_IN_PROGRESS);
+}
+
+#[test]
+fn parse_command_test() {
+    assert_eq!(parse_command(" undo "), Ok(Command::Undo));
+    assert_eq!(parse_command("Q"), Ok(Command::Quit));
+    assert_eq!(parse_command("c1"), Ok(Command::Move(Coord::new(0, 2))));
+    assert!(parse_command("c4").is_err());
+    assert_eq!(parse_command("Export Games/Final.SVG"),
+               Ok(Command::Export(Format::Svg, PathBuf::from("Games/Final.SVG"))));
+    assert!(parse_command("export").is_err());
+    assert!(parse_command("export board.txt").is_err());
+}
+
+#[test]
+fn order_chaos_command_test() {
+    use order_chaos::Command;
+
+    assert_eq!(order_chaos::parse_command("c4 x"), Ok(Command::Move(3, 2, PlayerKind::PlayerX)));
+    assert_eq!(order_chaos::parse_command("O f6"), Ok(Command::Move(5, 5, PlayerKind::PlayerO)));
+    assert_eq!(order_chaos::parse_command("new"), Ok(Command::New));
+    assert!(order_chaos::parse_command("g1 X").is_err());
+    assert!(order_chaos::parse_command("a7 O").is_err());
+    assert!(order_chaos::parse_command("c4").is_err());
+
+    let args: Vec<String> = "cli order-chaos --play-as chaos".split_whitespace().map(String::from).collect();
+    let game = order_chaos::game_from_args(&args).unwrap();
+    assert!(!game.order_is_human && game.chaos_is_human);
+    assert!(order_chaos::game_from_args(&["cli".to_string(), "--play-as".to_string()]).is_err());
+}
+
+#[test]
+fn bot_match_records_every_game_test() {
+    let path = std::env::temp_dir()
+        .join(format!("tic-tac-torture-cli-{}", std::process::id()))
+        .join("records.redb");
+    let store = RecordStore::new(path.clone());
+    let mut recorder = Recorder::new(Some(RecordStore::new(path.clone())));
+    let config = GameConfig::new().human(PlayerKind::PlayerX, false).human(PlayerKind::PlayerO, false);
+    let mut series = Match::new(config, 3);
+    let mut last_game = None;
+
+    // as the REPL plays a match between bots, without a prompt in between
+    while series.status() == MatchStatus::InProgress {
+        play_bot_moves(&mut series, &mut recorder);
+        after_move(&mut series, &mut recorder, &mut last_game);
+    }
+
+    assert_eq!(series.games_played(), 3);
+    assert_eq!(store.load().unwrap().records.len(), 3);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,33 @@
+use std::io::{self, BufRead, Write};
+
+use protocol::Engine;
+
+mod protocol;
+
+/// Speaks the engine protocol on stdin and stdout until told to quit or
+/// the input ends
+fn main() {
+    let mut engine = Engine::new();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let replies = match engine.handle(&line) {
+            Some(replies) => replies,
+            None => break,
+        };
+        for reply in replies {
+            if writeln!(out, "{}", reply).is_err() {
+                return;
+            }
+        }
+        // arenas wait for each answer before sending more
+        if out.flush().is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use tic_tac_torture::*;
+use tic_tac_torture::bot::expectimax;
+
+#[cfg(test)]
+mod tests;
+
+/// The engine's side of the protocol: the position it was last given and
+/// its options. Each line read from the GUI or arena is answered by zero or
+/// more lines, in the style of UCI:
+///
+/// * `uci` - names the engine and lists its options, ending with `uciok`
+/// * `isready` - answered by `readyok`
+/// * `newgame` - clears the board, with X to move
+/// * `position startpos [moves <square>...]` or `position <board> [X|O]
+///   [moves <square>...]` - sets the position, with the board written like
+///   `X.O/.X./...` and the player to move worked out from it if not given
+/// * `go [movetime <ms>]` - answered by `info score <n> reason <rule>`, then
+///   `bestmove <square>`, or `bestmove none` once the game is over
+/// * `setoption name difficulty value <easy|medium|hard>`
+/// * `quit`
+///
+/// Anything the engine can't follow is answered by `info string <error>`.
+pub struct Engine {
+    pub game: Game,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine { game: Game::new() }
+    }
+
+    /// Answers a line of input, or returns `None` for `quit`
+    pub fn handle(&mut self, line: &str) -> Option<Vec<String>> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            [] => Ok(Vec::new()),
+            ["quit"] => return None,
+            ["uci"] => Ok(vec![
+                "id name Tic-Tac-Torture".to_string(),
+                "option name difficulty type combo default hard var easy var medium var hard".to_string(),
+                "uciok".to_string(),
+            ]),
+            ["isready"] => Ok(vec!["readyok".to_string()]),
+            ["newgame"] | ["ucinewgame"] => {
+                self.game.set_position([[None; BOARD_SIZE]; BOARD_SIZE], PlayerKind::PlayerX);
+                Ok(Vec::new())
+            },
+            ["position", args @ ..] => self.position(args).map(|_| Vec::new()),
+            ["go", args @ ..] => self.go(args),
+            ["setoption", "name", name, "value", value] | ["setoption", name, value] => {
+                self.set_option(name, value).map(|_| Vec::new())
+            },
+            _ => Err(format!("Unknown command \"{}\"", line.trim())),
+        };
+        Some(result.unwrap_or_else(|e| vec![format!("info string {}", e)]))
+    }
+
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let (board, rest) = match args {
+            ["startpos", rest @ ..] => ([[None; BOARD_SIZE]; BOARD_SIZE], rest),
+            [board, rest @ ..] => (parse_board_string(board)?, rest),
+            [] => return Err("position needs startpos or a board like X.O/.X./...".to_string()),
+        };
+        let (player, rest) = match rest {
+            [side, rest @ ..] if side.eq_ignore_ascii_case("X") => (PlayerKind::PlayerX, rest),
+            [side, rest @ ..] if side.eq_ignore_ascii_case("O") => (PlayerKind::PlayerO, rest),
+            _ => (player_to_move(&board), rest),
+        };
+        let moves = match rest {
+            ["moves", moves @ ..] => moves,
+            [] => &[],
+            _ => return Err(format!("Expected moves after the position, found \"{}\"", rest.join(" "))),
+        };
+
+        // the position is only changed once every move has been checked
+        let mut game = self.game.clone();
+        game.set_position(board, player);
+        for square in moves {
+            let coord: Coord = square.parse()?;
+            net::check_move(&game, game.current_player, coord)?;
+            game.make_move(coord.row, coord.col);
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    fn go(&mut self, args: &[&str]) -> Result<Vec<String>, String> {
+        match args {
+            [] => (),
+            // every position is searched to the end well within any time
+            // limit, so the move is always sent straight away
+            ["movetime", millis] => {
+                millis.parse::<u64>().map_err(|_| format!("Expected a time in milliseconds, found \"{}\"", millis))?;
+            },
+            _ => return Err(format!("Unknown go options \"{}\"", args.join(" "))),
+        }
+
+        if self.game.status != GameStatus::InProgress {
+            return Ok(vec!["bestmove none".to_string()]);
+        }
+        let player = self.game.current_player;
+        let score = expectimax::evaluate(player, &self.game.board, &self.game.ruleset);
+        let (row, col) = bot::get_bot_move(&mut self.game);
+        // moves that aren't the perfect strategy's are the bot's blunders
+        let reason = match bot::explain_move(player, &self.game.board) {
+            (best, reason) if best == (row, col) => reason.to_string(),
+            _ => "random".to_string(),
+        };
+        Ok(vec![
+            format!("info score {} reason {}", score, reason),
+            format!("bestmove {}", Coord::new(row, col)),
+        ])
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name.to_lowercase().as_str() {
+            "difficulty" => {
+                self.game.difficulty = value.parse()?;
+                Ok(())
+            },
+            _ => Err(format!("Unknown option \"{}\"", name)),
+        }
+    }
+}
+
+/// X moves first, so it's X's turn whenever the pieces are level
+fn player_to_move(board: &Board) -> PlayerKind {
+    if count_stones(PlayerKind::PlayerX, board) > count_stones(PlayerKind::PlayerO, board) {
+        PlayerKind::PlayerO
+    } else {
+        PlayerKind::PlayerX
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use super::*;
+
+fn answer(engine: &mut Engine, line: &str) -> Vec<String> {
+    engine.handle(line).expect("the engine quit")
+}
+
+#[test]
+fn handshake_test() {
+    let mut engine = Engine::new();
+    assert_eq!(answer(&mut engine, "uci").last().map(String::as_str), Some("uciok"));
+    assert_eq!(answer(&mut engine, "isready"), ["readyok"]);
+    assert!(answer(&mut engine, "").is_empty());
+    assert_eq!(engine.handle("quit"), None);
+}
+
+#[test]
+fn position_and_go_test() {
+    let mut engine = Engine::new();
+    assert!(answer(&mut engine, "position startpos moves a1 b2 b1").is_empty());
+    assert_eq!(engine.game.current_player, PlayerKind::PlayerO);
+    assert_eq!(answer(&mut engine, "go"), ["info score 0 reason block", "bestmove c1"]);
+
+    // a board with the side to move given, then worked out
+    answer(&mut engine, "position XX./OO./... O");
+    assert_eq!(answer(&mut engine, "go movetime 100"), ["info score 5 reason win", "bestmove c2"]);
+    answer(&mut engine, "position XX./OO./...");
+    assert_eq!(answer(&mut engine, "go")[1], "bestmove c1");
+
+    answer(&mut engine, "position XXX/OO./...");
+    assert_eq!(answer(&mut engine, "go"), ["bestmove none"]);
+
+    answer(&mut engine, "newgame");
+    assert_eq!(answer(&mut engine, "go"), ["info score 0 reason center", "bestmove b2"]);
+}
+
+/// Sends every position reachable from `game` to the engine, checking that
+/// each move it picks is on an empty square
+fn legal_moves_helper(engine: &mut Engine, game: &Game, seen: &mut HashSet<Board>) {
+    if game.status != GameStatus::InProgress || !seen.insert(game.board) {
+        return;
+    }
+    let position = format!("position {} {}", board_string(&game.board), game.current_player.to_char());
+    assert!(answer(engine, &position).is_empty());
+    let reply = answer(engine, "go");
+    let coord: Coord = reply[1].trim_start_matches("bestmove ").parse().unwrap();
+    assert!(game.board[coord.row][coord.col].is_none(), "{} for {}", reply[1], position);
+
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            if game.board[row][col].is_none() {
+                let mut next = game.clone();
+                next.make_move(row, col);
+                legal_moves_helper(engine, &next, seen);
+            }
+        }
+    }
+}
+
+#[test]
+fn go_plays_legal_moves_test() {
+    let mut engine = Engine::new();
+    answer(&mut engine, "position XOO/..X/.X. O");
+    assert_eq!(answer(&mut engine, "go")[1], "bestmove b2");
+
+    legal_moves_helper(&mut engine, &Game::new(), &mut HashSet::new());
+}
+
+#[test]
+fn bad_input_test() {
+    let mut engine = Engine::new();
+    answer(&mut engine, "position startpos moves b2");
+
+    // a bad position leaves the last one in place
+    assert_eq!(answer(&mut engine, "position startpos moves a1 a1"), ["info string a1 is taken"]);
+    assert_eq!(engine.game.board, parse_board_string(".../.X./...").unwrap());
+    assert_eq!(answer(&mut engine, "position XX"),
+               ["info string Expected a board like X.O/.X./..., found \"XX\""]);
+    assert_eq!(answer(&mut engine, "go depth 3"), ["info string Unknown go options \"depth 3\""]);
+    assert_eq!(answer(&mut engine, "setoption name speed value 3"), ["info string Unknown option \"speed\""]);
+    assert_eq!(answer(&mut engine, "fly"), ["info string Unknown command \"fly\""]);
+}
+
+#[test]
+fn difficulty_option_test() {
+    let mut engine = Engine::new();
+    assert!(answer(&mut engine, "setoption name difficulty value easy").is_empty());
+    assert_eq!(engine.game.difficulty, Difficulty::Easy);
+    assert!(answer(&mut engine, "setoption difficulty medium").is_empty());
+    assert_eq!(engine.game.difficulty, Difficulty::Medium);
+    assert!(answer(&mut engine, "setoption difficulty expert")[0].starts_with("info string"));
+}
@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use tic_tac_torture::{Board, Coord, Game, GameStatus, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+/// How long a piece takes to draw its strokes
+pub const PIECE_DURATION: Duration = Duration::from_millis(250);
+/// How long the winning line takes to fade in, once the last piece is drawn
+pub const WIN_LINE_DURATION: Duration = Duration::from_millis(400);
+/// How long the end of game overlay waits before fading in, so the last
+/// move and the winning line can be seen
+pub const OVERLAY_DELAY: Duration = Duration::from_millis(900);
+pub const OVERLAY_DURATION: Duration = Duration::from_millis(250);
+
+/// Keeps track of when things changed on the board, so each frame knows how
+/// far along its animations are. Times are passed in rather than read from
+/// the clock, so one frame is drawn with a single `now`.
+pub struct Animations {
+    /// The board as of the last update
+    board: Board,
+    /// When each piece on the board was placed
+    placed: [[Option<Instant>; BOARD_SIZE]; BOARD_SIZE],
+    /// When the game ended, if it has
+    ended: Option<Instant>,
+}
+
+impl Default for Animations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Animations {
+    pub fn new() -> Animations {
+        Animations {
+            board: [[None; BOARD_SIZE]; BOARD_SIZE],
+            placed: [[None; BOARD_SIZE]; BOARD_SIZE],
+            ended: None,
+        }
+    }
+
+    /// Starts animations for anything on `game` that changed since the last
+    /// update. Pieces that are taken back, by an undo or a new game, simply
+    /// disappear.
+    pub fn update(&mut self, game: &Game, now: Instant) {
+        for (i, row) in game.board.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                if cell != self.board[i][j] {
+                    self.placed[i][j] = cell.map(|_| now);
+                }
+            }
+        }
+        self.board = game.board;
+
+        match (&game.status, self.ended) {
+            (GameStatus::InProgress, _) => self.ended = None,
+            (_, None) => self.ended = Some(now),
+            _ => (),
+        }
+    }
+
+    /// How much of the piece at `coord` is drawn, from 0 to 1
+    pub fn piece_progress(&self, coord: Coord, now: Instant) -> f64 {
+        match self.placed[coord.row][coord.col] {
+            Some(placed) => progress(placed, now, PIECE_DURATION),
+            None => 1.0,
+        }
+    }
+
+    /// How far the winning line has faded in, from 0 to 1
+    pub fn win_line_progress(&self, now: Instant) -> f64 {
+        match self.ended {
+            Some(ended) => progress(ended + PIECE_DURATION, now, WIN_LINE_DURATION),
+            None => 0.0,
+        }
+    }
+
+    /// How far the end of game overlay has faded in, from 0 to 1
+    pub fn overlay_progress(&self, now: Instant) -> f64 {
+        match self.ended {
+            Some(ended) => progress(ended + OVERLAY_DELAY, now, OVERLAY_DURATION),
+            None => 0.0,
+        }
+    }
+}
+
+/// How far through an animation of `duration` starting at `start` we are at
+/// `now`, from 0 to 1
+fn progress(start: Instant, now: Instant, duration: Duration) -> f64 {
+    if now <= start {
+        return 0.0;
+    }
+    ((now - start).as_secs_f64() / duration.as_secs_f64()).min(1.0)
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+use tic_tac_torture::PlayerKind::*;
+
+#[test]
+fn new_pieces_animate_test() {
+    let start = Instant::now();
+    let mut game = Game::new();
+    let mut animations = Animations::new();
+    animations.update(&game, start);
+
+    game.make_move(1, 1);
+    animations.update(&game, start);
+    let center = Coord::new(1, 1);
+    assert_eq!(animations.piece_progress(center, start), 0.0);
+    assert_eq!(animations.piece_progress(center, start + PIECE_DURATION / 2), 0.5);
+    assert_eq!(animations.piece_progress(center, start + PIECE_DURATION * 3), 1.0);
+
+    // a piece that was already there doesn't start over
+    game.make_move(0, 0);
+    let later = start + PIECE_DURATION;
+    animations.update(&game, later);
+    assert_eq!(animations.piece_progress(center, later), 1.0);
+    assert_eq!(animations.piece_progress(Coord::new(0, 0), later), 0.0);
+
+    // empty squares have nothing to animate
+    assert_eq!(animations.piece_progress(Coord::new(2, 2), later), 1.0);
+}
+
+#[test]
+fn end_of_game_animations_test() {
+    let start = Instant::now();
+    let mut game = Game::new();
+    let mut animations = Animations::new();
+
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+        game.make_move(row, col);
+    }
+    animations.update(&game, start);
+    assert_eq!(animations.win_line_progress(start + OVERLAY_DELAY), 0.0);
+
+    game.make_move(0, 2);
+    assert_eq!(game.status.winner(), Some(PlayerX));
+    animations.update(&game, start);
+
+    // the line waits for the last piece, then fades in
+    assert_eq!(animations.win_line_progress(start + PIECE_DURATION), 0.0);
+    assert_eq!(animations.win_line_progress(start + PIECE_DURATION + WIN_LINE_DURATION), 1.0);
+
+    assert_eq!(animations.overlay_progress(start + OVERLAY_DELAY), 0.0);
+    assert_eq!(animations.overlay_progress(start + OVERLAY_DELAY + OVERLAY_DURATION), 1.0);
+
+    // the end time sticks until a new game starts
+    let later = start + OVERLAY_DELAY * 2;
+    animations.update(&game, later);
+    assert_eq!(animations.overlay_progress(later), 1.0);
+
+    game.reset();
+    animations.update(&game, later);
+    assert_eq!(animations.overlay_progress(later + OVERLAY_DELAY * 2), 0.0);
+}
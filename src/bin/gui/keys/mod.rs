@@ -0,0 +1,87 @@
+use piston_window::Key;
+
+use tic_tac_torture::{Coord, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+/// The way an arrow key moves the focus, as steps across and down
+pub fn arrow_direction(key: Key) -> Option<(i32, i32)> {
+    match key {
+        Key::Left => Some((-1, 0)),
+        Key::Right => Some((1, 0)),
+        Key::Up => Some((0, -1)),
+        Key::Down => Some((0, 1)),
+        _ => None,
+    }
+}
+
+/// Whether `key` presses the focused button or plays the focused square
+pub fn is_activate(key: Key) -> bool {
+    matches!(key, Key::Return | Key::NumPadEnter | Key::Space)
+}
+
+/// The number on a number key, on the number row or the keypad
+pub fn digit(key: Key) -> Option<usize> {
+    match key {
+        Key::D0 | Key::NumPad0 => Some(0),
+        Key::D1 | Key::NumPad1 => Some(1),
+        Key::D2 | Key::NumPad2 => Some(2),
+        Key::D3 | Key::NumPad3 => Some(3),
+        Key::D4 | Key::NumPad4 => Some(4),
+        Key::D5 | Key::NumPad5 => Some(5),
+        Key::D6 | Key::NumPad6 => Some(6),
+        Key::D7 | Key::NumPad7 => Some(7),
+        Key::D8 | Key::NumPad8 => Some(8),
+        Key::D9 | Key::NumPad9 => Some(9),
+        _ => None,
+    }
+}
+
+/// The square a number key plays, 1 to 9 in reading order from the top
+/// left, on the number row or the keypad
+pub fn digit_coord(key: Key) -> Option<Coord> {
+    match digit(key) {
+        Some(digit) if digit > 0 => Some(Coord::new((digit - 1) / BOARD_SIZE, (digit - 1) % BOARD_SIZE)),
+        _ => None,
+    }
+}
+
+/// The square reached from `focus` by moving `dx` across and `dy` down,
+/// stopping at the edges. With no focus yet the first key press focuses
+/// the center.
+pub fn move_board_focus(focus: Option<Coord>, dx: i32, dy: i32) -> Coord {
+    move_focus_on(focus, dx, dy, BOARD_SIZE)
+}
+
+/// `move_board_focus` on a board `squares` squares a side
+pub fn move_focus_on(focus: Option<Coord>, dx: i32, dy: i32, squares: usize) -> Coord {
+    let step = |index: usize, delta: i32| (index as i32 + delta).clamp(0, squares as i32 - 1) as usize;
+    match focus {
+        Some(coord) => Coord::new(step(coord.row, dy), step(coord.col, dx)),
+        None => Coord::new(squares / 2, squares / 2),
+    }
+}
+
+/// The board and square reached from `focus` across `board_count` Notakto
+/// boards. Moving across past a board's edge carries on to the next or
+/// previous board, in the order they're stored, and moving down stops at
+/// the edges. With no focus yet the first key press focuses the center of
+/// the first board.
+pub fn move_notakto_focus(focus: Option<(usize, Coord)>, dx: i32, dy: i32, board_count: usize) -> (usize, Coord) {
+    let (board, coord) = match focus {
+        Some(focus) => focus,
+        None => return (0, move_board_focus(None, dx, dy)),
+    };
+
+    let row = (coord.row as i32 + dy).clamp(0, BOARD_SIZE as i32 - 1) as usize;
+    let col = coord.col as i32 + dx;
+    let size = BOARD_SIZE as i32;
+    if col < 0 && board > 0 {
+        (board - 1, Coord::new(row, (col + size) as usize))
+    } else if col >= size && board + 1 < board_count {
+        (board + 1, Coord::new(row, (col - size) as usize))
+    } else {
+        (board, Coord::new(row, col.clamp(0, size - 1) as usize))
+    }
+}
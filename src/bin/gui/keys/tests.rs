@@ -0,0 +1,57 @@
+use super::*;
+
+#[test]
+fn digit_coord_test() {
+    assert_eq!(digit_coord(Key::D1), Some(Coord::new(0, 0)));
+    assert_eq!(digit_coord(Key::D3), Some(Coord::new(0, 2)));
+    assert_eq!(digit_coord(Key::NumPad5), Some(Coord::new(1, 1)));
+    assert_eq!(digit_coord(Key::D7), Some(Coord::new(2, 0)));
+    assert_eq!(digit_coord(Key::NumPad9), Some(Coord::new(2, 2)));
+    assert_eq!(digit_coord(Key::D0), None);
+    assert_eq!(digit_coord(Key::A), None);
+}
+
+#[test]
+fn digit_test() {
+    assert_eq!(digit(Key::D0), Some(0));
+    assert_eq!(digit(Key::NumPad7), Some(7));
+    assert_eq!(digit(Key::Minus), None);
+}
+
+#[test]
+fn move_board_focus_test() {
+    assert_eq!(move_board_focus(None, 1, 0), Coord::new(1, 1));
+    assert_eq!(move_board_focus(Some(Coord::new(1, 1)), 1, 0), Coord::new(1, 2));
+    assert_eq!(move_board_focus(Some(Coord::new(1, 1)), 0, -1), Coord::new(0, 1));
+
+    // the focus stops at the edges rather than wrapping
+    assert_eq!(move_board_focus(Some(Coord::new(0, 2)), 1, -1), Coord::new(0, 2));
+    assert_eq!(move_board_focus(Some(Coord::new(2, 0)), -1, 1), Coord::new(2, 0));
+
+    // on a bigger board too
+    assert_eq!(move_focus_on(None, 0, 1, 7), Coord::new(3, 3));
+    assert_eq!(move_focus_on(Some(Coord::new(2, 6)), 1, 1, 7), Coord::new(3, 6));
+}
+
+#[test]
+fn move_notakto_focus_test() {
+    assert_eq!(move_notakto_focus(None, 1, 0, 3), (0, Coord::new(1, 1)));
+    assert_eq!(move_notakto_focus(Some((0, Coord::new(1, 1))), 0, 1, 3), (0, Coord::new(2, 1)));
+
+    // moving across past an edge carries on to the neighbouring board
+    assert_eq!(move_notakto_focus(Some((0, Coord::new(1, 2))), 1, 0, 3), (1, Coord::new(1, 0)));
+    assert_eq!(move_notakto_focus(Some((2, Coord::new(0, 0))), -1, 0, 3), (1, Coord::new(0, 2)));
+
+    // and stops at the first and last boards
+    assert_eq!(move_notakto_focus(Some((0, Coord::new(2, 0))), -1, 1, 3), (0, Coord::new(2, 0)));
+    assert_eq!(move_notakto_focus(Some((2, Coord::new(0, 2))), 1, -1, 3), (2, Coord::new(0, 2)));
+}
+
+#[test]
+fn arrow_direction_test() {
+    assert_eq!(arrow_direction(Key::Up), Some((0, -1)));
+    assert_eq!(arrow_direction(Key::Right), Some((1, 0)));
+    assert_eq!(arrow_direction(Key::Return), None);
+    assert!(is_activate(Key::Return) && is_activate(Key::Space));
+    assert!(!is_activate(Key::Escape));
+}
@@ -0,0 +1,166 @@
+use tic_tac_torture::{Coord, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+/// A rectangle as `[x, y, width, height]`, with x and y at its top left
+/// corner, the same as piston's `types::Rectangle`
+pub type Rect = [f64; 4];
+
+/// Fraction of the window's shorter side left empty around the board
+pub const BOARD_MARGIN: f64 = 0.05;
+
+/// Fraction of the window's height taken by the status bar, which is never
+/// shorter than `MIN_STATUS_BAR_HEIGHT`
+pub const STATUS_BAR_FRACTION: f64 = 0.08;
+pub const MIN_STATUS_BAR_HEIGHT: f64 = 24.0;
+
+/// Where a board's squares are drawn. Everything here is in window
+/// coordinates, the same space as cursor positions and the drawing
+/// transform, so it holds for any window size and DPI scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    /// The square the board fills
+    pub rect: Rect,
+    /// The squares on each side of the board
+    pub squares: usize,
+}
+
+impl BoardLayout {
+    /// Fits the largest square board in a window of `window_size`, centred,
+    /// with a margin around it
+    pub fn new(window_size: [f64; 2]) -> BoardLayout {
+        let [width, height] = window_size;
+        let margin = width.min(height) * BOARD_MARGIN;
+        BoardLayout::in_rect([margin, margin, width - 2.0 * margin, height - 2.0 * margin])
+    }
+
+    /// The largest square board that fits in `rect`, centred in it
+    pub fn in_rect(rect: Rect) -> BoardLayout {
+        let [x, y, width, height] = rect;
+        let size = width.min(height).max(0.0);
+        BoardLayout {
+            rect: [x + (width - size) / 2.0, y + (height - size) / 2.0, size, size],
+            squares: BOARD_SIZE,
+        }
+    }
+
+    /// The same space divided into `squares` squares a side, for a bigger
+    /// board than the classic one
+    pub fn with_squares(self, squares: usize) -> BoardLayout {
+        BoardLayout { squares, ..self }
+    }
+
+    pub fn size(&self) -> f64 {
+        self.rect[2]
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.size() / self.squares as f64
+    }
+
+    pub fn cell_rect(&self, coord: Coord) -> Rect {
+        let [x, y, _, _] = self.rect;
+        let cell_size = self.cell_size();
+        [x + coord.col as f64 * cell_size, y + coord.row as f64 * cell_size, cell_size, cell_size]
+    }
+
+    // the board's drawing works from its rect, so only the tests need this
+    #[cfg(test)]
+    pub fn cell_center(&self, coord: Coord) -> [f64; 2] {
+        let [x, y, size, _] = self.cell_rect(coord);
+        [x + size / 2.0, y + size / 2.0]
+    }
+
+    /// The square under `pos`, or `None` if it's off the board. A position
+    /// on the board's far edge belongs to the last row or column.
+    pub fn coord_at(&self, pos: [f64; 2]) -> Option<Coord> {
+        let [x, y, size, _] = self.rect;
+        let [pos_x, pos_y] = pos;
+
+        // written so a NaN position also lands here
+        let on_board = pos_x >= x && pos_x <= x + size && pos_y >= y && pos_y <= y + size;
+        if !on_board || size <= 0.0 {
+            return None;
+        }
+
+        let cell_size = self.cell_size();
+        let index = |offset: f64| ((offset / cell_size) as usize).min(self.squares - 1);
+        Some(Coord::new(index(pos_y - y), index(pos_x - x)))
+    }
+}
+
+/// Splits the window into the board, fitted to the space left above the
+/// status bar, and the status bar along the bottom
+pub fn split_status_bar(window_size: [f64; 2]) -> (BoardLayout, Rect) {
+    let [width, height] = window_size;
+    let bar_height = (height * STATUS_BAR_FRACTION).max(MIN_STATUS_BAR_HEIGHT).min(height.max(0.0));
+    let board = BoardLayout::new([width, height - bar_height]);
+    (board, [0.0, height - bar_height, width, bar_height])
+}
+
+/// Splits `window_size` into a centred grid of `count` equal square slots,
+/// as close to square overall as possible, and returns each slot in order
+/// from the top left, row by row
+pub fn grid_slots(count: usize, window_size: [f64; 2]) -> Vec<Rect> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let [width, height] = window_size;
+    let columns = (count as f64).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+
+    let slot_size = (width / columns as f64).min(height / rows as f64);
+    let offset_x = (width - slot_size * columns as f64) / 2.0;
+    let offset_y = (height - slot_size * rows as f64) / 2.0;
+
+    (0..count).map(|i| {
+        let x = offset_x + (i % columns) as f64 * slot_size;
+        let y = offset_y + (i / columns) as f64 * slot_size;
+        [x, y, slot_size, slot_size]
+    }).collect()
+}
+
+/// `rect` shrunk by `fraction` of its width on every side
+pub fn pad(rect: Rect, fraction: f64) -> Rect {
+    let [x, y, width, height] = rect;
+    let pad = width.min(height) * fraction;
+    [x + pad, y + pad, width - 2.0 * pad, height - 2.0 * pad]
+}
+
+pub fn contains(rect: Rect, pos: [f64; 2]) -> bool {
+    let [x, y, width, height] = rect;
+    pos[0] >= x && pos[0] < x + width && pos[1] >= y && pos[1] < y + height
+}
+
+/// The panel shown over the board once a game is over, with the result at
+/// the top and a row of two buttons below it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlayLayout {
+    pub panel: Rect,
+    /// Where the result is shown
+    pub result: Rect,
+    pub play_again: Rect,
+    pub menu: Rect,
+}
+
+impl OverlayLayout {
+    pub fn new(window_size: [f64; 2]) -> OverlayLayout {
+        let [width, height] = window_size;
+        let unit = width.min(height) / 10.0;
+
+        // the panel is 6 units wide and 5 tall: a 2 unit result over 1.5
+        // unit buttons, with half a unit above and below them and a unit
+        // beside and between the buttons
+        let panel = [(width - 6.0 * unit) / 2.0, (height - 5.0 * unit) / 2.0, 6.0 * unit, 5.0 * unit];
+        let [x, y, _, _] = panel;
+
+        OverlayLayout {
+            panel,
+            result: [x + 2.0 * unit, y + 0.5 * unit, 2.0 * unit, 2.0 * unit],
+            play_again: [x + unit, y + 3.0 * unit, 1.5 * unit, 1.5 * unit],
+            menu: [x + 3.5 * unit, y + 3.0 * unit, 1.5 * unit, 1.5 * unit],
+        }
+    }
+}
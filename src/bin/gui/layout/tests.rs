@@ -0,0 +1,166 @@
+use super::*;
+
+fn assert_rect_eq(actual: Rect, expected: Rect) {
+    for i in 0..4 {
+        assert!((actual[i] - expected[i]).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn board_is_centred_square_test() {
+    // a square window leaves the margin on every side
+    let layout = BoardLayout::new([600.0, 600.0]);
+    assert_rect_eq(layout.rect, [30.0, 30.0, 540.0, 540.0]);
+
+    // a wide window centres the board horizontally
+    let layout = BoardLayout::new([1000.0, 600.0]);
+    assert_rect_eq(layout.rect, [230.0, 30.0, 540.0, 540.0]);
+
+    // and a tall one vertically
+    let layout = BoardLayout::new([600.0, 1000.0]);
+    assert_rect_eq(layout.rect, [30.0, 230.0, 540.0, 540.0]);
+}
+
+#[test]
+fn cells_tile_the_board_test() {
+    let layout = BoardLayout::in_rect([10.0, 20.0, 300.0, 300.0]);
+    assert_eq!(layout.cell_size(), 100.0);
+    assert_rect_eq(layout.cell_rect(Coord::new(0, 0)), [10.0, 20.0, 100.0, 100.0]);
+    assert_rect_eq(layout.cell_rect(Coord::new(2, 1)), [110.0, 220.0, 100.0, 100.0]);
+    assert_eq!(layout.cell_center(Coord::new(1, 2)), [260.0, 170.0]);
+}
+
+#[test]
+fn coord_at_test() {
+    let layout = BoardLayout::in_rect([10.0, 20.0, 300.0, 300.0]);
+    assert_eq!(layout.coord_at([10.0, 20.0]), Some(Coord::new(0, 0)));
+    assert_eq!(layout.coord_at([260.0, 170.0]), Some(Coord::new(1, 2)));
+    assert_eq!(layout.coord_at([109.9, 120.0]), Some(Coord::new(1, 0)));
+
+    // the far edges belong to the last row and column, rather than a fourth
+    assert_eq!(layout.coord_at([310.0, 320.0]), Some(Coord::new(2, 2)));
+
+    assert_eq!(layout.coord_at([9.9, 100.0]), None);
+    assert_eq!(layout.coord_at([100.0, 320.1]), None);
+    assert_eq!(layout.coord_at([-50.0, -50.0]), None);
+    assert_eq!(layout.coord_at([f64::NAN, 100.0]), None);
+}
+
+#[test]
+fn every_cell_maps_back_to_itself_test() {
+    for &window in &[[640.0, 640.0], [1280.0, 720.0], [333.0, 901.0], [37.0, 41.0]] {
+        let layout = BoardLayout::new(window);
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                let coord = Coord::new(row, col);
+                assert_eq!(layout.coord_at(layout.cell_center(coord)), Some(coord));
+            }
+        }
+    }
+}
+
+#[test]
+fn bigger_board_test() {
+    let layout = BoardLayout::in_rect([10.0, 20.0, 300.0, 300.0]).with_squares(5);
+    assert_eq!(layout.cell_size(), 60.0);
+    assert_rect_eq(layout.cell_rect(Coord::new(4, 1)), [70.0, 260.0, 60.0, 60.0]);
+    assert_eq!(layout.coord_at([310.0, 320.0]), Some(Coord::new(4, 4)));
+    for row in 0..5 {
+        for col in 0..5 {
+            let coord = Coord::new(row, col);
+            assert_eq!(layout.coord_at(layout.cell_center(coord)), Some(coord));
+        }
+    }
+}
+
+#[test]
+fn tiny_window_test() {
+    let layout = BoardLayout::new([0.0, 0.0]);
+    assert_eq!(layout.size(), 0.0);
+    assert_eq!(layout.coord_at([0.0, 0.0]), None);
+
+    // a window narrower than its margins doesn't give a negative board
+    let layout = BoardLayout::in_rect([0.0, 0.0, -5.0, 10.0]);
+    assert_eq!(layout.size(), 0.0);
+}
+
+#[test]
+fn grid_slots_test() {
+    assert!(grid_slots(0, [100.0, 100.0]).is_empty());
+
+    // one board fills the shorter side
+    assert_eq!(grid_slots(1, [200.0, 100.0]), vec![[50.0, 0.0, 100.0, 100.0]]);
+
+    // three boards go on a 2x2 grid, row by row
+    let slots = grid_slots(3, [200.0, 200.0]);
+    assert_eq!(slots, vec![
+        [0.0, 0.0, 100.0, 100.0],
+        [100.0, 0.0, 100.0, 100.0],
+        [0.0, 100.0, 100.0, 100.0],
+    ]);
+
+    // five boards take three columns and two rows, centred vertically
+    let slots = grid_slots(5, [300.0, 300.0]);
+    assert_eq!(slots.len(), 5);
+    assert_eq!(slots[0], [0.0, 50.0, 100.0, 100.0]);
+    assert_eq!(slots[4], [100.0, 150.0, 100.0, 100.0]);
+}
+
+#[test]
+fn pad_and_contains_test() {
+    assert_rect_eq(pad([0.0, 0.0, 100.0, 100.0], 0.1), [10.0, 10.0, 80.0, 80.0]);
+
+    let rect = [10.0, 10.0, 20.0, 20.0];
+    assert!(contains(rect, [10.0, 10.0]));
+    assert!(contains(rect, [29.9, 29.9]));
+    assert!(!contains(rect, [30.0, 15.0]));
+    assert!(!contains(rect, [5.0, 15.0]));
+}
+
+#[test]
+fn overlay_fits_inside_panel_test() {
+    for &window in &[[640.0, 640.0], [1280.0, 720.0], [300.0, 900.0]] {
+        let overlay = OverlayLayout::new(window);
+        let [px, py, pw, ph] = overlay.panel;
+
+        // centred in the window
+        assert!((px * 2.0 + pw - window[0]).abs() < 1e-9);
+        assert!((py * 2.0 + ph - window[1]).abs() < 1e-9);
+
+        for &[x, y, w, h] in &[overlay.result, overlay.play_again, overlay.menu] {
+            assert!(x >= px && y >= py && x + w <= px + pw && y + h <= py + ph);
+        }
+    }
+}
+
+#[test]
+fn overlay_buttons_dont_overlap_test() {
+    let overlay = OverlayLayout::new([640.0, 640.0]);
+    let [x, y, w, h] = overlay.play_again;
+    let center = [x + w / 2.0, y + h / 2.0];
+    assert!(contains(overlay.play_again, center));
+    assert!(!contains(overlay.menu, center));
+    assert!(!contains(overlay.result, center));
+
+    let [x, y, w, h] = overlay.menu;
+    assert!(!contains(overlay.play_again, [x + w / 2.0, y + h / 2.0]));
+}
+
+#[test]
+fn status_bar_test() {
+    let (board, bar) = split_status_bar([600.0, 650.0]);
+    assert_rect_eq(bar, [0.0, 598.0, 600.0, 52.0]);
+
+    // the board sits above the bar, with its margin
+    let [_, y, _, size] = board.rect;
+    assert!(y + size <= bar[1]);
+
+    // the bar keeps a readable height in a short window
+    let (_, bar) = split_status_bar([600.0, 100.0]);
+    assert_eq!(bar[3], MIN_STATUS_BAR_HEIGHT);
+
+    // but never more than the window has
+    let (board, bar) = split_status_bar([600.0, 10.0]);
+    assert_rect_eq(bar, [0.0, 0.0, 600.0, 10.0]);
+    assert_eq!(board.size(), 0.0);
+}
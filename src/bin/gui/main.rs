@@ -0,0 +1,673 @@
+use std::env;
+use std::time::{Duration, Instant};
+
+extern crate piston_window;
+
+use piston_window::*;
+
+use tic_tac_torture::*;
+use tic_tac_torture::net::{self, Role};
+use tic_tac_torture::notakto::{self, NotaktoGame, NotaktoStatus, Seat};
+use tic_tac_torture::records::{self, PlayerStats, RecordStore, Recorder};
+use tic_tac_torture::render::{self, Palette, Stroke};
+use tic_tac_torture::replay::{self, Replay};
+use tic_tac_torture::scheduler::BotScheduler;
+use tic_tac_torture::settings::Settings;
+use animation::Animations;
+use layout::BoardLayout;
+use status_bar::StatusBar;
+use PlayerKind::*;
+
+mod animation;
+mod keys;
+mod layout;
+mod menu;
+mod mnk;
+mod network;
+mod overlay;
+mod replay_viewer;
+mod settings_menu;
+mod status_bar;
+mod text;
+
+const WINDOW_WIDTH: f64 = 640.0;
+const WINDOW_HEIGHT: f64 = 640.0;
+
+// the font for all text in the window, built into the binary so it doesn't
+// depend on where the game is run from
+const FONT: &[u8] = include_bytes!("../../../assets/FiraSans-Regular.ttf");
+
+// opacity of the piece shown under the cursor before it's played
+const GHOST_ALPHA: f32 = 0.3;
+
+// opacity and density of the patterns behind pieces, when they're turned on
+const PATTERN_ALPHA: f32 = 0.35;
+const PATTERN_STEPS: usize = 8;
+
+// fraction of each Notakto board's slot left empty around the board
+const NOTAKTO_BOARD_PADDING: f64 = 0.08;
+
+/// `color` with its opacity scaled by `alpha`
+fn fade(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    [color[0], color[1], color[2], color[3] * alpha]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw(
+    game: &Game,
+    settings: &Settings,
+    layout: &BoardLayout,
+    animations: &Animations,
+    focus: Option<Coord>,
+    now: Instant,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let palette = settings.theme.palette();
+    clear(palette.background, graphics);
+
+    // set color to draw game pieces, based on game status
+    // if game is a tie, set all pieces to the loss color. if a player has won,
+    // set the opposite player to use the loss color, else use the X or O color
+    // respectively
+    let (x_color, o_color) = match game.status {
+        GameStatus::Win(player, _) => match player {
+            PlayerX => (palette.x, palette.loss),
+            PlayerO => (palette.loss, palette.o),
+        },
+        GameStatus::Tie => (palette.loss, palette.loss),
+        GameStatus::InProgress => (palette.x, palette.o),
+    };
+
+    for (i, row) in game.board.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            let coord = Coord::new(i, j);
+            let cell_rect = layout.cell_rect(coord);
+            let progress = animations.piece_progress(coord, now);
+            if let (Some(player), true) = (cell, settings.patterns) {
+                let color = if *player == PlayerX { x_color } else { o_color };
+                draw_pattern(context, graphics, cell_rect, *player, fade(color, PATTERN_ALPHA * progress as f32));
+            }
+            match cell {
+                Some(PlayerX) => draw_x(context, graphics, cell_rect, x_color, progress),
+                Some(PlayerO) => draw_o(context, graphics, cell_rect, o_color, progress),
+                None => (),
+            }
+        }
+    }
+
+    // the focused square is outlined, with a faint piece where a human's
+    // click or key press would play
+    if let Some(coord) = focus {
+        let is_open = game.status == GameStatus::InProgress && game.board[coord.row][coord.col].is_none();
+        if is_open && game.current_player_is_human() {
+            let cell_rect = layout.cell_rect(coord);
+            Rectangle::new_border(palette.selected, 1.5)
+                .draw(layout::pad(cell_rect, 0.04), &context.draw_state, context.transform, graphics);
+            match game.current_player {
+                PlayerX => draw_x(context, graphics, cell_rect, fade(palette.x, GHOST_ALPHA), 1.0),
+                PlayerO => draw_o(context, graphics, cell_rect, fade(palette.o, GHOST_ALPHA), 1.0),
+            }
+        }
+    }
+
+    draw_board_grid(context, graphics, layout.rect, BOARD_SIZE, palette.grid);
+
+    if let GameStatus::Win(player, lines) = &game.status {
+        let color = match player {
+            PlayerX => palette.x,
+            PlayerO => palette.o,
+        };
+        let color = fade(color, animations.win_line_progress(now) as f32);
+        for line in lines {
+            draw_strike_through(context, graphics, layout, line, color);
+        }
+    }
+}
+
+// draws a line through the centers of the winning squares, running a little
+// past the first and last square
+fn draw_strike_through(
+    context: &Context,
+    graphics: &mut G2d,
+    layout: &BoardLayout,
+    line: &tic_tac_torture::Line,
+    color: [f32; 4]
+) {
+    draw_stroke(context, graphics, &render::strike_through(layout.rect, line), color);
+}
+
+/// Draws a stroke of the board's shared geometry: square ended strokes as
+/// lines, which run past their ends the same way, and the rest as the
+/// rectangle they cover
+fn draw_stroke(context: &Context, graphics: &mut G2d, stroke: &Stroke, color: [f32; 4]) {
+    if stroke.square_ends {
+        line_from_to(color, stroke.width / 2.0, stroke.from, stroke.to, context.transform, graphics);
+    } else {
+        polygon(color, &stroke.corners(), context.transform, graphics);
+    }
+}
+
+/// Fills `rect` with diagonal stripes for X or a grid of dots for O, so the
+/// squares each side holds can be told apart by more than colour
+fn draw_pattern(context: &Context, graphics: &mut G2d, rect: types::Rectangle, player: PlayerKind, color: [f32; 4]) {
+    let [x, y, size, _] = rect;
+    let spacing = size / PATTERN_STEPS as f64;
+    match player {
+        PlayerX => {
+            // each stripe runs from the top or right edge down to the left or
+            // bottom edge
+            for i in 1..2 * PATTERN_STEPS {
+                let t = i as f64 * spacing;
+                let from = [x + t.min(size), y + (t - size).max(0.0)];
+                let to = [x + (t - size).max(0.0), y + t.min(size)];
+                line_from_to(color, size / 120.0, from, to, context.transform, graphics);
+            }
+        },
+        PlayerO => {
+            let radius = size / 60.0;
+            for i in 0..PATTERN_STEPS {
+                for j in 0..PATTERN_STEPS {
+                    let center = [x + (i as f64 + 0.5) * spacing, y + (j as f64 + 0.5) * spacing];
+                    ellipse(color, ellipse::circle(center[0], center[1], radius), context.transform, graphics);
+                }
+            }
+        },
+    }
+}
+
+/// Draws an X in `rect`, with `progress` from 0 to 1 being how much of it to
+/// draw: the first stroke is drawn over the first half, then the second
+fn draw_x(context: &Context, graphics: &mut G2d, rect: types::Rectangle, color: [f32; 4], progress: f64) {
+    let [first, second] = render::x_strokes(rect);
+    draw_stroke(context, graphics, &first.partial(progress * 2.0), color);
+    if progress > 0.5 {
+        draw_stroke(context, graphics, &second.partial(progress * 2.0 - 1.0), color);
+    }
+}
+
+/// Draws an O in `rect`, with `progress` from 0 to 1 being how much of the
+/// ring to draw, clockwise from the top
+fn draw_o(context: &Context, graphics: &mut G2d, rect: types::Rectangle, color: [f32; 4], progress: f64) {
+    let ring = render::o_ring(rect);
+    // the ring is drawn as a thick arc along its middle
+    let [center_x, center_y] = ring.center;
+    let ring_rect = ellipse::centered([center_x, center_y, ring.radius, ring.radius]);
+
+    let start = -std::f64::consts::FRAC_PI_2;
+    let end = start + std::f64::consts::TAU * progress.clamp(0.0, 1.0);
+    if end > start {
+        circle_arc(color, ring.width / 2.0, start, end, ring_rect, context.transform, graphics);
+    }
+}
+
+fn main() {
+    let (mut settings, warnings) = Settings::load();
+    for warning in warnings {
+        eprintln!("{}", warning);
+    }
+    let args: Vec<String> = env::args().collect();
+
+    // `gui --host [port]` and `gui --join <address>` play over the network.
+    // the other player is found before the window opens, so it doesn't sit
+    // there unresponsive while the host waits
+    let session = match Role::from_args(&args).and_then(|role| Ok((role, GameConfig::from_args(&args)?))) {
+        Ok((Some(role), config)) => {
+            match &role {
+                Role::Host(port) => println!("Waiting for a player to join on port {}...", port),
+                Role::Join(address) => println!("Joining {}...", address),
+            }
+            match role.connect(&net::player_name(&args), config) {
+                Ok(session) => Some(session),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            }
+        },
+        Ok((None, _)) => None,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
+    // `gui --replay "<square>..."` and `gui --replay-game <n>` step through
+    // a finished game
+    let replay = match Replay::from_args(&args).and_then(|replay| Ok((replay, replay::delay_from_args(&args)?))) {
+        Ok((Some(replay), delay)) => Some((replay, delay)),
+        Ok((None, _)) => None,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
+    let mut window: PistonWindow =
+        WindowSettings::new("Tic-Tac-Torture", [WINDOW_WIDTH, WINDOW_HEIGHT])
+            .exit_on_esc(false)
+            .resizable(true)
+            .build()
+            .unwrap();
+
+    // `gui --notakto [board count]` plays Notakto instead of Tic-Tac-Toe
+    if let Some(session) = session {
+        network::run(&mut window, session, &net::player_name(&args), &settings);
+    } else if let Some((replay, delay)) = replay {
+        replay_viewer::run(&mut window, replay, delay, &settings);
+    } else if let Some(i) = args.iter().position(|arg| arg == "--notakto") {
+        let board_count = args.get(i + 1)
+            .and_then(|count| count.parse().ok())
+            .filter(|&count| count > 0)
+            .unwrap_or(notakto::DEFAULT_BOARD_COUNT);
+        run_notakto(&mut window, board_count, &settings);
+    } else {
+        match Match::from_args(&args) {
+            Ok(series) => {
+                // options on the command line win over the saved settings
+                settings.apply_args(&args, &series);
+                run_classic(&mut window, series.config, settings);
+            },
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// What's on screen. The game moves to `GameOver` as soon as it ends, and
+/// the end of game overlay fades in over the final board.
+enum Screen {
+    Menu,
+    Settings,
+    Playing,
+    GameOver,
+}
+
+/// The window title while playing, which is where the score is shown
+fn match_title(series: &Match) -> String {
+    match (series.game.status.clone(), series.status()) {
+        (GameStatus::InProgress, _) | (_, MatchStatus::InProgress) => {
+            format!("Tic-Tac-Torture - {}", series)
+        },
+        (_, MatchStatus::Win(player)) => {
+            format!("{} wins the match, {}", player.to_char(), series.score())
+        },
+        (_, MatchStatus::Draw) => format!("The match is drawn, {}", series.score()),
+    }
+}
+
+/// The lifetime record of whoever's playing on this computer, as kept in
+/// the record store
+fn lifetime_stats(recorder: &Recorder) -> Option<PlayerStats> {
+    match recorder.store.as_ref()?.stats_for(&records::local_name()) {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        },
+    }
+}
+
+/// Plays matches set up by `settings`, on top of `base_config` for the
+/// rules, starting from the menu
+fn run_classic(window: &mut PistonWindow, base_config: GameConfig, mut settings: Settings) {
+    let mut series = Match::new(settings.config(base_config.clone()), settings.best_of);
+    let mut screen = Screen::Menu;
+
+    let mut cursor_pos: [f64; 2] = [0.0, 0.0];
+    let mut window_size: [f64; 2] = [WINDOW_WIDTH, WINDOW_HEIGHT];
+
+    // what's focused on each screen, by the cursor or the keys
+    let mut menu_focus: Option<menu::MenuItem> = None;
+    let mut settings_focus: Option<settings_menu::Choice> = None;
+    let mut board_focus: Option<Coord> = None;
+    let mut overlay_focus: Option<overlay::Button> = None;
+
+    let mut bot = BotScheduler::new(Duration::from_millis(settings.bot_delay_millis));
+    let mut recorder = Recorder::new(RecordStore::open_default());
+    let mut lifetime = lifetime_stats(&recorder);
+    let mut animations = Animations::new();
+    let mut status_bar = StatusBar::new();
+    let mut glyphs = match Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new()) {
+        Ok(glyphs) => glyphs,
+        Err(_) => panic!("The bundled font couldn't be loaded."),
+    };
+
+    while let Some(event) = window.next() {
+        let now = Instant::now();
+        let palette = settings.theme.palette();
+        if let Screen::Playing | Screen::GameOver = screen {
+            animations.update(&series.game, now);
+        }
+        let overlay_alpha = animations.overlay_progress(now);
+
+        if let Some(render_args) = event.render_args() {
+            window_size = render_args.window_size;
+            window.draw_2d(&event, |context, graphics, device| {
+                match screen {
+                    Screen::Menu => {
+                        clear(palette.background, graphics);
+                        menu::draw(&palette, menu_focus, lifetime.as_ref(), window_size, &mut glyphs, &context, graphics);
+                    },
+                    Screen::Settings => {
+                        clear(palette.background, graphics);
+                        settings_menu::draw(&settings, settings_focus, window_size, &mut glyphs, &context, graphics);
+                    },
+                    Screen::Playing | Screen::GameOver => {
+                        let (layout, bar) = layout::split_status_bar(window_size);
+                        draw(&series.game, &settings, &layout, &animations, board_focus, now, &context, graphics);
+                        let bot_failed = bot.has_failed(series.game.turn_id);
+                        status_bar.draw(&series, bot_failed, &palette, bar, &mut glyphs, now, &context, graphics);
+                        if overlay_alpha > 0.0 {
+                            overlay::draw(&series.game.status, &palette, overlay_focus, window_size,
+                                          overlay_alpha as f32, &context, graphics);
+                        }
+                    },
+                }
+                glyphs.factory.encoder.flush(device);
+            });
+        }
+
+        if event.update_args().is_some() {
+            if let Screen::Playing = screen {
+                // with some rulesets the bot can move first or several times
+                // in a row, so this is checked on every update rather than
+                // after each click
+                if let Some((row, col)) = bot.update(&mut series.game) {
+                    series.make_move(row, col);
+                    window.set_title(match_title(&series));
+                }
+
+                if series.game.status != GameStatus::InProgress {
+                    screen = Screen::GameOver;
+                    overlay_focus = None;
+
+                    let (x, o) = records::local_names(&series.game);
+                    match recorder.update(&series.game, &x, &o) {
+                        Ok(()) => lifetime = lifetime_stats(&recorder),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+        }
+
+        if let Some(pos) = event.mouse_cursor_args() {
+            cursor_pos = pos;
+            match screen {
+                Screen::Menu => menu_focus = menu::item_at(window_size, cursor_pos),
+                Screen::Settings => settings_focus = settings_menu::choice_at(window_size, cursor_pos),
+                Screen::Playing => board_focus = layout::split_status_bar(window_size).0.coord_at(cursor_pos),
+                Screen::GameOver if overlay_alpha > 0.0 => {
+                    overlay_focus = overlay::button_at(window_size, cursor_pos);
+                    window.set_title(overlay::title(&series, overlay_focus).unwrap_or_else(|| match_title(&series)));
+                },
+                Screen::GameOver => (),
+            }
+        }
+
+        let (clicked, key) = match event.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => (true, None),
+            Some(Button::Keyboard(key)) => (false, Some(key)),
+            _ => continue,
+        };
+        let activated = clicked || key.is_some_and(keys::is_activate);
+        let arrow = key.and_then(keys::arrow_direction);
+        let escaped = key == Some(Key::Escape);
+
+        match screen {
+            Screen::Menu => {
+                if let Some((_, dy)) = arrow {
+                    menu_focus = Some(menu::move_focus(menu_focus, dy));
+                }
+                let target = if clicked { menu::item_at(window_size, cursor_pos) } else { menu_focus };
+                match target {
+                    _ if escaped => window.set_should_close(true),
+                    // any board but the classic one is played as a game of
+                    // its own, which comes back here on Escape
+                    Some(menu::MenuItem::Play) if activated && !settings.is_classic_board() => {
+                        mnk::run(window, &settings);
+                        window.set_title("Tic-Tac-Torture".to_string());
+                    },
+                    Some(menu::MenuItem::Play) if activated => {
+                        series = Match::new(settings.config(base_config.clone()), settings.best_of);
+                        bot.delay = Duration::from_millis(settings.bot_delay_millis);
+                        status_bar.clear_message();
+                        recorder.restart();
+                        screen = Screen::Playing;
+                        window.set_title(match_title(&series));
+                    },
+                    Some(menu::MenuItem::Settings) if activated => screen = Screen::Settings,
+                    Some(menu::MenuItem::Quit) if activated => window.set_should_close(true),
+                    _ => (),
+                }
+            },
+            Screen::Settings => {
+                if let Some((dx, dy)) = arrow {
+                    settings_focus = Some(settings_menu::move_focus(settings_focus, dx, dy));
+                }
+                let target = if clicked { settings_menu::choice_at(window_size, cursor_pos) } else { settings_focus };
+                match target {
+                    Some(settings_menu::Choice::Back) if activated => leave_settings(&settings, &mut screen),
+                    _ if escaped => leave_settings(&settings, &mut screen),
+                    Some(choice) if activated => choice.select(&mut settings),
+                    _ => (),
+                }
+            },
+            Screen::Playing => {
+                if let Some((dx, dy)) = arrow {
+                    board_focus = Some(keys::move_board_focus(board_focus, dx, dy));
+                }
+                let digit = key.and_then(keys::digit_coord);
+                if digit.is_some() {
+                    board_focus = digit;
+                }
+                let target = if clicked {
+                    layout::split_status_bar(window_size).0.coord_at(cursor_pos)
+                } else {
+                    board_focus
+                };
+
+                if escaped {
+                    screen = Screen::Menu;
+                    window.set_title("Tic-Tac-Torture".to_string());
+                } else if let (Some(coord), true) = (target, activated || digit.is_some()) {
+                    if !series.game.current_player_is_human() {
+                        status_bar.show_message("Wait for the bot to move", now);
+                    } else if series.game.board[coord.row][coord.col].is_some() {
+                        status_bar.show_message(&format!("{} is taken", coord), now);
+                    } else {
+                        status_bar.clear_message();
+                        series.make_move(coord.row, coord.col);
+                        window.set_title(match_title(&series));
+                    }
+                }
+            },
+            // only the overlay's buttons do anything once the game is over,
+            // and only once it's showing
+            Screen::GameOver if overlay_alpha > 0.0 => {
+                if let Some((dx, dy)) = arrow {
+                    overlay_focus = Some(if dx + dy < 0 { overlay::Button::PlayAgain } else { overlay::Button::Menu });
+                    window.set_title(overlay::title(&series, overlay_focus).unwrap_or_else(|| match_title(&series)));
+                }
+                let target = if clicked {
+                    overlay::button_at(window_size, cursor_pos)
+                } else {
+                    // Enter plays again unless the menu button is focused
+                    overlay_focus.or(Some(overlay::Button::PlayAgain))
+                };
+                match target {
+                    _ if escaped => {
+                        screen = Screen::Menu;
+                        window.set_title("Tic-Tac-Torture".to_string());
+                    },
+                    Some(overlay::Button::PlayAgain) if activated => {
+                        if series.status() == MatchStatus::InProgress {
+                            series.next_game();
+                        } else {
+                            series = Match::new(series.config.clone(), series.best_of);
+                        }
+                        recorder.restart();
+                        screen = Screen::Playing;
+                        window.set_title(match_title(&series));
+                    },
+                    Some(overlay::Button::Menu) if activated => {
+                        screen = Screen::Menu;
+                        window.set_title("Tic-Tac-Torture".to_string());
+                    },
+                    _ => (),
+                }
+            },
+            Screen::GameOver => (),
+        }
+    }
+}
+
+/// Saves the settings on the way back to the menu
+fn leave_settings(settings: &Settings, screen: &mut Screen) {
+    if let Err(e) = settings.save() {
+        eprintln!("Couldn't save the settings: {}", e);
+    }
+    *screen = Screen::Menu;
+}
+
+/// The rectangle of each Notakto board, in the order they're stored
+fn notakto_board_rects(board_count: usize, window_size: [f64; 2]) -> Vec<layout::Rect> {
+    layout::grid_slots(board_count, window_size).into_iter()
+        .map(|slot| layout::pad(slot, NOTAKTO_BOARD_PADDING))
+        .collect()
+}
+
+fn draw_notakto(
+    game: &NotaktoGame,
+    palette: &Palette,
+    window_size: [f64; 2],
+    focus: Option<(usize, Coord)>,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    clear(palette.background, graphics);
+
+    let rects = notakto_board_rects(game.boards.len(), window_size);
+    for (index, rect) in rects.iter().enumerate() {
+        // dead boards are greyed out
+        let (piece_color, grid_color) = if game.is_dead(index) {
+            (palette.loss, palette.loss)
+        } else {
+            (palette.x, palette.grid)
+        };
+
+        let board_layout = BoardLayout::in_rect(*rect);
+        for (i, row) in game.boards[index].iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                if cell.is_some() {
+                    draw_x(context, graphics, board_layout.cell_rect(Coord::new(i, j)), piece_color, 1.0);
+                }
+            }
+        }
+
+        // as on the classic board, an open focused square is outlined with
+        // a faint X while a human is to move
+        if let Some((focus_index, coord)) = focus {
+            let is_open = game.status == NotaktoStatus::InProgress && !game.is_dead(index)
+                && game.boards[index][coord.row][coord.col].is_none();
+            if focus_index == index && is_open && game.current_seat_is_human() {
+                let cell_rect = board_layout.cell_rect(coord);
+                Rectangle::new_border(palette.selected, 1.5)
+                    .draw(layout::pad(cell_rect, 0.04), &context.draw_state, context.transform, graphics);
+                draw_x(context, graphics, cell_rect, fade(palette.x, GHOST_ALPHA), 1.0);
+            }
+        }
+
+        draw_board_grid(context, graphics, *rect, BOARD_SIZE, grid_color);
+    }
+}
+
+/// Draws the inner lines of a single board's grid inside `rect`, `squares`
+/// squares a side
+fn draw_board_grid(context: &Context, graphics: &mut G2d, rect: types::Rectangle, squares: usize, color: [f32; 4]) {
+    for stroke in render::grid_lines(rect, squares, 4.0) {
+        draw_stroke(context, graphics, &stroke, color);
+    }
+}
+
+/// Returns the board and square under the cursor, if any
+fn notakto_square_at(game: &NotaktoGame, window_size: [f64; 2], cursor_pos: [f64; 2]) -> Option<(usize, Coord)> {
+    let rects = notakto_board_rects(game.boards.len(), window_size);
+    let index = rects.iter().position(|&rect| layout::contains(rect, cursor_pos))?;
+    let coord = BoardLayout::in_rect(rects[index]).coord_at(cursor_pos)?;
+    Some((index, coord))
+}
+
+// the window title is the only place the result can be shown
+fn notakto_title(game: &NotaktoGame) -> String {
+    match game.status {
+        NotaktoStatus::InProgress => format!("Notakto ({} boards)", game.boards.len()),
+        NotaktoStatus::Win(Seat::First) => "Notakto - first player wins, press any key to play again".to_string(),
+        NotaktoStatus::Win(Seat::Second) => "Notakto - second player wins, press any key to play again".to_string(),
+    }
+}
+
+fn run_notakto(window: &mut PistonWindow, board_count: usize, settings: &Settings) {
+    let mut game = NotaktoGame::new(board_count);
+    window.set_title(notakto_title(&game));
+
+    let mut cursor_pos: [f64; 2] = [0.0, 0.0];
+    let mut window_size: [f64; 2] = [WINDOW_WIDTH, WINDOW_HEIGHT];
+    // the square focused by the cursor or the keys, with its board
+    let mut focus: Option<(usize, Coord)> = None;
+
+    let palette = settings.theme.palette();
+    let mut bot = BotScheduler::new(Duration::from_millis(settings.bot_delay_millis));
+
+    while let Some(event) = window.next() {
+        if let Some(render_args) = event.render_args() {
+            window_size = render_args.window_size;
+            window.draw_2d(&event, |context, graphics, _device| {
+                draw_notakto(&game, &palette, window_size, focus, &context, graphics);
+            });
+        }
+
+        if event.update_args().is_some() {
+            if let Some((index, row, col)) = bot.update_notakto(&game) {
+                game.make_move(index, row, col);
+                window.set_title(notakto_title(&game));
+            }
+        }
+
+        if let Some(pos) = event.mouse_cursor_args() {
+            cursor_pos = pos;
+            focus = notakto_square_at(&game, window_size, cursor_pos);
+        }
+
+        // the same keys as the classic board: arrows move the focus, which
+        // carries on across boards, Enter or Space plays it, a number key
+        // plays that square of the focused board and Escape quits
+        let (clicked, key) = match event.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => (true, None),
+            Some(Button::Keyboard(key)) => (false, Some(key)),
+            _ => continue,
+        };
+
+        if key == Some(Key::Escape) {
+            window.set_should_close(true);
+        } else if let NotaktoStatus::Win(_) = game.status {
+            game.reset();
+            window.set_title(notakto_title(&game));
+        } else {
+            if let Some((dx, dy)) = key.and_then(keys::arrow_direction) {
+                focus = Some(keys::move_notakto_focus(focus, dx, dy, game.boards.len()));
+            }
+            let digit = key.and_then(keys::digit_coord);
+            if let Some(coord) = digit {
+                focus = Some((focus.map_or(0, |(index, _)| index), coord));
+            }
+            let target = if clicked { notakto_square_at(&game, window_size, cursor_pos) } else { focus };
+            let activated = clicked || digit.is_some() || key.is_some_and(keys::is_activate);
+
+            if let (Some((index, coord)), true) = (target, activated) {
+                if game.current_seat_is_human() && game.make_move(index, coord.row, coord.col) {
+                    window.set_title(notakto_title(&game));
+                }
+            }
+        }
+    }
+}
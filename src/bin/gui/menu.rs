@@ -0,0 +1,89 @@
+use piston_window::*;
+
+use tic_tac_torture::records::PlayerStats;
+use tic_tac_torture::render::Palette;
+
+use super::layout::{self, Rect};
+use super::text;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuItem {
+    Play,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    pub const ALL: [MenuItem; 3] = [MenuItem::Play, MenuItem::Settings, MenuItem::Quit];
+
+    fn label(&self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play",
+            MenuItem::Settings => "Settings",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// Lays out the buttons in a column below the title, centered in the window
+fn items(window_size: [f64; 2]) -> Vec<(MenuItem, Rect)> {
+    let [width, height] = window_size;
+    let button_width = width * 0.4;
+    let button_height = height * 0.1;
+    let gap = button_height * 0.4;
+    let x = (width - button_width) / 2.0;
+
+    MenuItem::ALL.iter().enumerate()
+        .map(|(i, &item)| {
+            let y = height * 0.4 + i as f64 * (button_height + gap);
+            (item, [x, y, button_width, button_height])
+        })
+        .collect()
+}
+
+pub fn item_at(window_size: [f64; 2], cursor_pos: [f64; 2]) -> Option<MenuItem> {
+    items(window_size).into_iter()
+        .find(|&(_, rect)| layout::contains(rect, cursor_pos))
+        .map(|(item, _)| item)
+}
+
+/// The item `steps` down from `focus`, stopping at the ends. With no focus
+/// yet the first key press focuses the top item.
+pub fn move_focus(focus: Option<MenuItem>, steps: i32) -> MenuItem {
+    let index = match focus.and_then(|focus| MenuItem::ALL.iter().position(|&item| item == focus)) {
+        Some(index) => (index as i32 + steps).clamp(0, MenuItem::ALL.len() as i32 - 1) as usize,
+        None => 0,
+    };
+    MenuItem::ALL[index]
+}
+
+/// Draws the menu with `focus`, under the cursor or picked with the keys,
+/// outlined, and the player's lifetime record under the title once they've
+/// finished a game
+pub fn draw(
+    palette: &Palette,
+    focus: Option<MenuItem>,
+    lifetime: Option<&PlayerStats>,
+    window_size: [f64; 2],
+    glyphs: &mut Glyphs,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let [width, height] = window_size;
+    text::draw_centered("Tic-Tac-Torture", palette.text, text::font_size(window_size, 0.1),
+                        [0.0, height * 0.1, width, height * 0.2], glyphs, context, graphics);
+    if let Some(stats) = lifetime.filter(|stats| stats.games > 0) {
+        text::draw_centered(&format!("Lifetime: {}", stats), palette.grid, text::font_size(window_size, 0.035),
+                            [0.0, height * 0.28, width, height * 0.08], glyphs, context, graphics);
+    }
+
+    let font_size = text::font_size(window_size, 0.045);
+    for (item, rect) in items(window_size) {
+        let border_color = if Some(item) == focus { palette.selected } else { palette.grid };
+        Rectangle::new_border(border_color, 2.0)
+            .draw(rect, &context.draw_state, context.transform, graphics);
+
+        let text_color = if item == MenuItem::Play { palette.accent } else { palette.text };
+        text::draw_centered(item.label(), text_color, font_size, rect, glyphs, context, graphics);
+    }
+}
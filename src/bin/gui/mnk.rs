@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use piston_window::*;
+
+use tic_tac_torture::Coord;
+use tic_tac_torture::mnk::{MnkGame, MnkStatus};
+use tic_tac_torture::render;
+use tic_tac_torture::scheduler::BotScheduler;
+use tic_tac_torture::settings::Settings;
+use tic_tac_torture::PlayerKind::*;
+
+use super::layout::{self, BoardLayout};
+use super::{draw_board_grid, draw_o, draw_pattern, draw_stroke, draw_x, fade, keys, text, FONT, GHOST_ALPHA,
+            PATTERN_ALPHA};
+
+/// What's happening in the game. `bot_failed` is whether the bot stopped
+/// without a move for this turn.
+fn status_text(game: &MnkGame, bot_failed: bool) -> String {
+    let player = game.current_player.to_char();
+    match game.status {
+        MnkStatus::InProgress if bot_failed => format!("{} couldn't find a move", player),
+        MnkStatus::InProgress if game.current_player_is_human() => format!("{} to play", player),
+        MnkStatus::InProgress => format!("{} is thinking...", player),
+        MnkStatus::Win(winner, _) => format!("{} wins! Click to play again", winner.to_char()),
+        MnkStatus::Tie => "Tie game. Click to play again".to_string(),
+    }
+}
+
+fn draw(
+    game: &MnkGame,
+    settings: &Settings,
+    layout: &BoardLayout,
+    focus: Option<Coord>,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let palette = settings.theme.palette();
+    clear(palette.background, graphics);
+
+    // as on the classic board, the loser's pieces are greyed out, and both
+    // sides' in a tie
+    let (x_color, o_color) = match game.status {
+        MnkStatus::Win(PlayerX, _) => (palette.x, palette.loss),
+        MnkStatus::Win(PlayerO, _) => (palette.loss, palette.o),
+        MnkStatus::Tie => (palette.loss, palette.loss),
+        MnkStatus::InProgress => (palette.x, palette.o),
+    };
+
+    for (i, row) in game.board.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            let cell_rect = layout.cell_rect(Coord::new(i, j));
+            if let (Some(player), true) = (cell, settings.patterns) {
+                let color = if *player == PlayerX { x_color } else { o_color };
+                draw_pattern(context, graphics, cell_rect, *player, fade(color, PATTERN_ALPHA));
+            }
+            match cell {
+                Some(PlayerX) => draw_x(context, graphics, cell_rect, x_color, 1.0),
+                Some(PlayerO) => draw_o(context, graphics, cell_rect, o_color, 1.0),
+                None => (),
+            }
+        }
+    }
+
+    if let Some(coord) = focus {
+        let is_open = game.status == MnkStatus::InProgress && game.board[coord.row][coord.col].is_none();
+        if is_open && game.current_player_is_human() {
+            let cell_rect = layout.cell_rect(coord);
+            Rectangle::new_border(palette.selected, 1.5)
+                .draw(layout::pad(cell_rect, 0.04), &context.draw_state, context.transform, graphics);
+            match game.current_player {
+                PlayerX => draw_x(context, graphics, cell_rect, fade(palette.x, GHOST_ALPHA), 1.0),
+                PlayerO => draw_o(context, graphics, cell_rect, fade(palette.o, GHOST_ALPHA), 1.0),
+            }
+        }
+    }
+
+    draw_board_grid(context, graphics, layout.rect, game.size, palette.grid);
+
+    if let MnkStatus::Win(player, (start, end)) = game.status {
+        let color = if player == PlayerX { palette.x } else { palette.o };
+        draw_stroke(context, graphics, &render::strike_between(layout.rect, game.size, start, end), color);
+    }
+}
+
+/// Plays games on the bigger board picked in `settings`, with the win length
+/// picked there, until Escape goes back to the menu.
+/// Squares are clicked or picked with the arrow keys and Enter, and once a
+/// game is over a click or Enter starts another. These games aren't kept in
+/// the record store, which holds classic games.
+pub fn run(window: &mut PistonWindow, settings: &Settings) {
+    let mut game = settings.mnk_game();
+    window.set_title(format!("Tic-Tac-Torture - {0}x{0}, {1} in a row", game.size, game.win_length));
+
+    let palette = settings.theme.palette();
+    let mut cursor_pos: [f64; 2] = [0.0, 0.0];
+    let mut window_size: [f64; 2] = [super::WINDOW_WIDTH, super::WINDOW_HEIGHT];
+    let mut focus: Option<Coord> = None;
+    let mut bot = BotScheduler::new(Duration::from_millis(settings.bot_delay_millis));
+    let mut glyphs = match Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new()) {
+        Ok(glyphs) => glyphs,
+        Err(_) => panic!("The bundled font couldn't be loaded."),
+    };
+
+    // the board above the status bar, in a window of `window_size`
+    let size = game.size;
+    let board_layout = |window_size| layout::split_status_bar(window_size).0.with_squares(size);
+
+    while let Some(event) = window.next() {
+        if let Some(render_args) = event.render_args() {
+            window_size = render_args.window_size;
+            window.draw_2d(&event, |context, graphics, device| {
+                let (_, bar) = layout::split_status_bar(window_size);
+                draw(&game, settings, &board_layout(window_size), focus, &context, graphics);
+
+                rectangle(palette.bar, bar, context.transform, graphics);
+                let [x, y, _, height] = bar;
+                let status = status_text(&game, bot.has_failed(game.turn_id));
+                text::draw(&status, palette.text, (height * 0.5) as u32, [x + height * 0.3, y + height * 0.68],
+                           &mut glyphs, &context, graphics);
+                glyphs.factory.encoder.flush(device);
+            });
+        }
+
+        if event.update_args().is_some() {
+            if let Some((row, col)) = bot.update_mnk(&mut game) {
+                game.make_move(row, col);
+            }
+        }
+
+        if let Some(pos) = event.mouse_cursor_args() {
+            cursor_pos = pos;
+            focus = board_layout(window_size).coord_at(cursor_pos);
+        }
+
+        let (clicked, key) = match event.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => (true, None),
+            Some(Button::Keyboard(key)) => (false, Some(key)),
+            _ => continue,
+        };
+        let activated = clicked || key.is_some_and(keys::is_activate);
+
+        if key == Some(Key::Escape) {
+            return;
+        } else if game.status != MnkStatus::InProgress {
+            if activated {
+                game.reset();
+            }
+        } else {
+            if let Some((dx, dy)) = key.and_then(keys::arrow_direction) {
+                focus = Some(keys::move_focus_on(focus, dx, dy, game.size));
+            }
+            let target = if clicked { board_layout(window_size).coord_at(cursor_pos) } else { focus };
+            if let (Some(coord), true) = (target, activated) {
+                if game.current_player_is_human() {
+                    game.make_move(coord.row, coord.col);
+                }
+            }
+        }
+    }
+}
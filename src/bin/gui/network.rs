@@ -0,0 +1,164 @@
+use std::time::{Duration, Instant};
+
+use piston_window::*;
+
+use tic_tac_torture::{Coord, GameStatus, PlayerKind};
+use tic_tac_torture::net::{Session, SessionEvent};
+use tic_tac_torture::records::{RecordStore, Recorder};
+
+use super::animation::Animations;
+use tic_tac_torture::settings::Settings;
+use super::{draw, draw_o, draw_x, keys, layout, text, FONT};
+
+/// How long a message stays up in place of the status
+const MESSAGE_DURATION: Duration = Duration::from_millis(3000);
+
+/// What's happening in the game, from this end's point of view
+fn status_text(session: &Session, closed: bool) -> String {
+    let opponent = &session.opponent_name;
+    let result = match &session.game.status {
+        GameStatus::InProgress if closed => return format!("{} has left", opponent),
+        GameStatus::InProgress if session.is_my_turn() => return "Your move".to_string(),
+        GameStatus::InProgress => return format!("Waiting for {}...", opponent),
+        GameStatus::Win(player, lines) if *player == session.side && lines.is_empty() => {
+            format!("{} resigned, you win!", opponent)
+        },
+        GameStatus::Win(player, _) if *player == session.side => "You win!".to_string(),
+        GameStatus::Win(_, lines) if lines.is_empty() => "You resigned".to_string(),
+        GameStatus::Win(_, _) => format!("{} wins", opponent),
+        GameStatus::Tie => "Tie game".to_string(),
+    };
+
+    if closed {
+        format!("{} {} has left", result, opponent)
+    } else if session.rematch_requested() {
+        format!("{} Waiting for {}...", result, opponent)
+    } else {
+        format!("{} Click for a rematch", result)
+    }
+}
+
+/// Plays a game against another player over the network. This end's moves
+/// are clicked or picked with the keys, R resigns, and once the game is over
+/// a click or Enter asks for a rematch. Finished games are recorded under
+/// `name` and the other player's name.
+pub fn run(window: &mut PistonWindow, mut session: Session, name: &str, settings: &Settings) {
+    window.set_title(format!("Tic-Tac-Torture - {} against {}", session.side.to_char(), session.opponent_name));
+
+    let palette = settings.theme.palette();
+    let mut cursor_pos: [f64; 2] = [0.0, 0.0];
+    let mut window_size: [f64; 2] = [super::WINDOW_WIDTH, super::WINDOW_HEIGHT];
+    let mut board_focus: Option<Coord> = None;
+    let mut message: Option<(String, Instant)> = None;
+    // once the other player has left there's nothing more to receive
+    let mut closed = false;
+
+    let mut recorder = Recorder::new(RecordStore::open_default());
+    let mut animations = Animations::new();
+    let mut glyphs = match Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new()) {
+        Ok(glyphs) => glyphs,
+        Err(_) => panic!("The bundled font couldn't be loaded."),
+    };
+
+    while let Some(event) = window.next() {
+        let now = Instant::now();
+        animations.update(&session.game, now);
+
+        if let Some(render_args) = event.render_args() {
+            window_size = render_args.window_size;
+            window.draw_2d(&event, |context, graphics, device| {
+                let (layout, bar) = layout::split_status_bar(window_size);
+                let focus = board_focus.filter(|_| session.is_my_turn());
+                draw(&session.game, settings, &layout, &animations, focus, now, &context, graphics);
+
+                rectangle(palette.bar, bar, context.transform, graphics);
+                let [x, y, _, height] = bar;
+                let icon = [x + height * 0.15, y, height, height];
+                match session.side {
+                    PlayerKind::PlayerX => draw_x(&context, graphics, icon, palette.x, 1.0),
+                    PlayerKind::PlayerO => draw_o(&context, graphics, icon, palette.o, 1.0),
+                }
+                let shown = message.as_ref().filter(|(_, shown)| now.duration_since(*shown) < MESSAGE_DURATION);
+                let (status, color) = match shown {
+                    Some((message, _)) => (message.clone(), palette.message),
+                    None => (status_text(&session, closed), palette.text),
+                };
+                text::draw(&status, color, (height * 0.5) as u32, [x + height * 1.3, y + height * 0.68],
+                           &mut glyphs, &context, graphics);
+                glyphs.factory.encoder.flush(device);
+            });
+        }
+
+        if event.update_args().is_some() && !closed {
+            while let Some(received) = session.poll() {
+                match received {
+                    SessionEvent::Updated => message = None,
+                    SessionEvent::RematchOffered => {
+                        message = Some((format!("{} wants a rematch", session.opponent_name), now));
+                    },
+                    SessionEvent::Refused(e) => message = Some((e, now)),
+                    SessionEvent::Closed => {
+                        closed = true;
+                        break;
+                    },
+                }
+            }
+
+            // this end's own moves are caught here too, on the next update
+            let (x, o) = session.player_names(name);
+            if let Err(e) = recorder.update(&session.game, x, o) {
+                eprintln!("{}", e);
+            }
+        }
+
+        if let Some(pos) = event.mouse_cursor_args() {
+            cursor_pos = pos;
+            board_focus = layout::split_status_bar(window_size).0.coord_at(cursor_pos);
+        }
+
+        let (clicked, key) = match event.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => (true, None),
+            Some(Button::Keyboard(key)) => (false, Some(key)),
+            _ => continue,
+        };
+        if key == Some(Key::Escape) {
+            window.set_should_close(true);
+            continue;
+        }
+        if closed {
+            continue;
+        }
+
+        let result = if session.game.status != GameStatus::InProgress {
+            if clicked || key.is_some_and(keys::is_activate) {
+                session.rematch()
+            } else {
+                Ok(())
+            }
+        } else if key == Some(Key::R) {
+            session.resign()
+        } else {
+            if let Some((dx, dy)) = key.and_then(keys::arrow_direction) {
+                board_focus = Some(keys::move_board_focus(board_focus, dx, dy));
+            }
+            let digit = key.and_then(keys::digit_coord);
+            if digit.is_some() {
+                board_focus = digit;
+            }
+            let target = if clicked {
+                layout::split_status_bar(window_size).0.coord_at(cursor_pos)
+            } else {
+                board_focus
+            };
+            match target {
+                Some(coord) if clicked || digit.is_some() || key.is_some_and(keys::is_activate) => {
+                    session.play(coord)
+                },
+                _ => Ok(()),
+            }
+        };
+        if let Err(e) = result {
+            message = Some((e, now));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use piston_window::*;
+
+use tic_tac_torture::{GameStatus, Match, MatchStatus, PlayerKind};
+use tic_tac_torture::render::Palette;
+
+use super::layout::{self, OverlayLayout};
+use super::{draw_o, draw_x, fade};
+
+const SHADE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+
+/// The buttons on the end of game overlay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Button {
+    /// The next game of the match, or a new match once it's over
+    PlayAgain,
+    Menu,
+}
+
+pub fn button_at(window_size: [f64; 2], cursor_pos: [f64; 2]) -> Option<Button> {
+    let overlay = OverlayLayout::new(window_size);
+    if layout::contains(overlay.play_again, cursor_pos) {
+        Some(Button::PlayAgain)
+    } else if layout::contains(overlay.menu, cursor_pos) {
+        Some(Button::Menu)
+    } else {
+        None
+    }
+}
+
+/// The window title while the overlay is up, which describes the focused
+/// button
+pub fn title(series: &Match, focus: Option<Button>) -> Option<String> {
+    match focus? {
+        Button::PlayAgain if series.status() == MatchStatus::InProgress => {
+            Some("Play the next game".to_string())
+        },
+        Button::PlayAgain => Some("Play a new match".to_string()),
+        Button::Menu => Some("Back to the menu".to_string()),
+    }
+}
+
+/// Draws the overlay faded in by `alpha`: a shade over the board, then a
+/// panel with the winner's piece, or both pieces greyed out for a tie, above
+/// the buttons, with `focus` outlined
+pub fn draw(
+    status: &GameStatus,
+    palette: &Palette,
+    focus: Option<Button>,
+    window_size: [f64; 2],
+    alpha: f32,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let [width, height] = window_size;
+    rectangle(fade(SHADE_COLOR, alpha), [0.0, 0.0, width, height], context.transform, graphics);
+
+    let overlay = OverlayLayout::new(window_size);
+    rectangle(fade(palette.background, alpha), overlay.panel, context.transform, graphics);
+    Rectangle::new_border(fade(palette.grid, alpha), 2.0)
+        .draw(overlay.panel, &context.draw_state, context.transform, graphics);
+
+    let [x, y, size, _] = overlay.result;
+    match status {
+        GameStatus::Win(PlayerKind::PlayerX, _) => {
+            draw_x(context, graphics, overlay.result, fade(palette.x, alpha), 1.0)
+        },
+        GameStatus::Win(PlayerKind::PlayerO, _) => {
+            draw_o(context, graphics, overlay.result, fade(palette.o, alpha), 1.0)
+        },
+        _ => {
+            let half = size / 2.0;
+            draw_x(context, graphics, [x, y + half / 2.0, half, half], fade(palette.loss, alpha), 1.0);
+            draw_o(context, graphics, [x + half, y + half / 2.0, half, half], fade(palette.loss, alpha), 1.0);
+        },
+    }
+
+    for &(button, rect) in &[(Button::PlayAgain, overlay.play_again), (Button::Menu, overlay.menu)] {
+        let border_color = if focus == Some(button) { palette.selected } else { palette.grid };
+        Rectangle::new_border(fade(border_color, alpha), 2.0)
+            .draw(rect, &context.draw_state, context.transform, graphics);
+
+        let [x, y, size, _] = rect;
+        let pad = size * 0.25;
+        match button {
+            // a "play" triangle
+            Button::PlayAgain => {
+                let triangle = [
+                    [x + pad, y + pad],
+                    [x + size - pad, y + size / 2.0],
+                    [x + pad, y + size - pad],
+                ];
+                polygon(fade(palette.accent, alpha), &triangle, context.transform, graphics);
+            },
+            // three bars, the usual menu icon
+            Button::Menu => {
+                let bar_height = size / 10.0;
+                for i in 0..3 {
+                    let bar_y = y + pad + i as f64 * (size - 2.0 * pad - bar_height) / 2.0;
+                    rectangle(fade(palette.grid, alpha), [x + pad, bar_y, size - 2.0 * pad, bar_height],
+                              context.transform, graphics);
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use piston_window::*;
+
+use tic_tac_torture::replay::Replay;
+
+use super::animation::Animations;
+use tic_tac_torture::settings::Settings;
+use super::{draw, keys, layout, text, FONT};
+
+/// Autoplay never goes faster or slower than this, however often the speed
+/// is changed
+const MIN_DELAY: Duration = Duration::from_millis(100);
+const MAX_DELAY: Duration = Duration::from_millis(10_000);
+
+/// The move that led to the position on show and what the bot makes of it
+fn status_text(replay: &Replay) -> String {
+    let ply = replay.ply();
+    let last_move = match ply.last_move {
+        Some((coord, player)) => format!("{} {}", player.to_char(), coord),
+        None => "Start".to_string(),
+    };
+    let verdict = match ply.evaluation {
+        Some(score) => format!("{} ({:+} for {})", ply.outcome, score, ply.game.current_player.to_char()),
+        None => format!("{}, game over", ply.outcome),
+    };
+    if ply.blunder {
+        format!("{} blunders: {}", last_move, verdict)
+    } else {
+        format!("{}: {}", last_move, verdict)
+    }
+}
+
+/// Steps through a finished game. The arrow keys step back and forward,
+/// Home and End jump to the ends, a number key jumps to that many moves in,
+/// and a click steps forward. Space or Enter starts and stops autoplay,
+/// which waits `delay` between moves, halved by + and doubled by -.
+pub fn run(window: &mut PistonWindow, mut replay: Replay, mut delay: Duration, settings: &Settings) {
+    window.set_title("Tic-Tac-Torture - Replay".to_string());
+
+    let palette = settings.theme.palette();
+    // when autoplay last moved, while it's on
+    let mut autoplay: Option<Instant> = None;
+
+    let mut animations = Animations::new();
+    let mut glyphs = match Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new()) {
+        Ok(glyphs) => glyphs,
+        Err(_) => panic!("The bundled font couldn't be loaded."),
+    };
+
+    while let Some(event) = window.next() {
+        let now = Instant::now();
+        animations.update(&replay.ply().game, now);
+
+        if let Some(render_args) = event.render_args() {
+            let window_size = render_args.window_size;
+            window.draw_2d(&event, |context, graphics, device| {
+                let (layout, bar) = layout::split_status_bar(window_size);
+                draw(&replay.ply().game, settings, &layout, &animations, None, now, &context, graphics);
+
+                rectangle(palette.bar, bar, context.transform, graphics);
+                let [x, y, width, height] = bar;
+                let font_size = (height * 0.5) as u32;
+                let baseline = y + height * 0.68;
+                let pad = height * 0.3;
+
+                let color = if replay.ply().blunder { palette.message } else { palette.text };
+                text::draw(&status_text(&replay), color, font_size, [x + pad, baseline], &mut glyphs, &context,
+                           graphics);
+
+                let position = match autoplay {
+                    Some(_) => format!("{}/{} > {:.1}s", replay.current, replay.last(), delay.as_secs_f64()),
+                    None => format!("{}/{}", replay.current, replay.last()),
+                };
+                let position_width = text::width_of(&position, font_size, &mut glyphs);
+                text::draw(&position, palette.grid, font_size, [x + width - pad - position_width, baseline],
+                           &mut glyphs, &context, graphics);
+                glyphs.factory.encoder.flush(device);
+            });
+        }
+
+        if event.update_args().is_some() {
+            if let Some(moved) = autoplay {
+                if now.duration_since(moved) >= delay {
+                    autoplay = if replay.forward() && !replay.at_end() { Some(now) } else { None };
+                }
+            }
+        }
+
+        let (clicked, key) = match event.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => (true, None),
+            Some(Button::Keyboard(key)) => (false, Some(key)),
+            _ => continue,
+        };
+        if clicked {
+            replay.forward();
+            continue;
+        }
+
+        match key {
+            Some(Key::Escape) => window.set_should_close(true),
+            Some(Key::Home) => replay.jump(0),
+            Some(Key::End) => replay.jump(replay.last()),
+            Some(Key::Equals) | Some(Key::Plus) | Some(Key::NumPadPlus) => delay = (delay / 2).max(MIN_DELAY),
+            Some(Key::Minus) | Some(Key::NumPadMinus) => delay = (delay * 2).min(MAX_DELAY),
+            Some(key) if keys::is_activate(key) => {
+                autoplay = match autoplay {
+                    Some(_) => None,
+                    // playing from the end starts over
+                    None => {
+                        if replay.at_end() {
+                            replay.jump(0);
+                        }
+                        Some(now)
+                    },
+                };
+            },
+            Some(key) => {
+                if let Some((dx, dy)) = keys::arrow_direction(key) {
+                    if dx + dy > 0 {
+                        replay.forward();
+                    } else {
+                        replay.back();
+                    }
+                }
+                if let Some(ply) = keys::digit(key) {
+                    replay.jump(ply);
+                }
+            },
+            None => (),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use piston_window::*;
+
+use tic_tac_torture::{Difficulty, PlayerKind};
+use tic_tac_torture::PlayerKind::*;
+use tic_tac_torture::render::{Palette, Theme};
+
+use super::layout::{self, Rect};
+use tic_tac_torture::settings::{Settings, Side};
+use super::text;
+
+/// The number of games that can be picked for a match
+const SERIES_LENGTHS: [u32; 3] = [1, 3, 5];
+/// The board sizes and win lengths that can be picked
+const BOARD_SIZES: [usize; 5] = [3, 4, 5, 6, 7];
+const WIN_LENGTHS: [usize; 3] = [3, 4, 5];
+/// The bot's delays that can be picked, with their names
+const BOT_DELAYS: [(u64, &str); 3] = [(0, "Instant"), (300, "Normal"), (800, "Slow")];
+
+/// A choice on the settings screen, or the button back to the menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Choice {
+    Side(Side),
+    Difficulty(Difficulty),
+    First(PlayerKind),
+    BestOf(u32),
+    Theme(Theme),
+    BotDelay(u64),
+    Patterns(bool),
+    BoardSize(usize),
+    WinLength(usize),
+    Back,
+}
+
+impl Choice {
+    fn label(&self) -> String {
+        match *self {
+            Choice::Side(Side::X) => "You are X".to_string(),
+            Choice::Side(Side::O) => "You are O".to_string(),
+            Choice::Side(Side::Both) => "2 players".to_string(),
+            Choice::Side(Side::Neither) => "Bots only".to_string(),
+            Choice::Difficulty(difficulty) => capitalize(&difficulty.to_string()),
+            Choice::First(player) => player.to_char().to_string(),
+            Choice::BestOf(1) => "1 game".to_string(),
+            Choice::BestOf(n) => format!("Best of {}", n),
+            Choice::Theme(Theme::HighContrast) => "Contrast".to_string(),
+            Choice::Theme(theme) => capitalize(&theme.to_string()),
+            Choice::BotDelay(millis) => BOT_DELAYS.iter()
+                .find(|&&(delay, _)| delay == millis)
+                .map_or_else(|| format!("{} ms", millis), |(_, name)| name.to_string()),
+            Choice::Patterns(false) => "Off".to_string(),
+            Choice::Patterns(true) => "On".to_string(),
+            Choice::BoardSize(size) => format!("{0}x{0}", size),
+            Choice::WinLength(length) => length.to_string(),
+            Choice::Back => "Back".to_string(),
+        }
+    }
+
+    fn is_selected(&self, settings: &Settings) -> bool {
+        match *self {
+            Choice::Side(side) => settings.side() == side,
+            Choice::Difficulty(difficulty) => settings.difficulty == difficulty,
+            Choice::First(player) => settings.first_player == player,
+            Choice::BestOf(n) => settings.best_of == n,
+            Choice::Theme(theme) => settings.theme == theme,
+            Choice::BotDelay(millis) => settings.bot_delay_millis == millis,
+            Choice::Patterns(on) => settings.patterns == on,
+            Choice::BoardSize(size) => settings.board_size == size,
+            Choice::WinLength(length) => settings.win_length == length,
+            Choice::Back => false,
+        }
+    }
+
+    /// Changes `settings` to this choice
+    pub fn select(&self, settings: &mut Settings) {
+        match *self {
+            Choice::Side(side) => settings.set_side(side),
+            Choice::Difficulty(difficulty) => settings.difficulty = difficulty,
+            Choice::First(player) => settings.first_player = player,
+            Choice::BestOf(n) => settings.best_of = n,
+            Choice::Theme(theme) => settings.theme = theme,
+            Choice::BotDelay(millis) => settings.bot_delay_millis = millis,
+            Choice::Patterns(on) => settings.patterns = on,
+            Choice::BoardSize(size) => settings.set_board_size(size),
+            Choice::WinLength(length) => settings.set_win_length(length),
+            Choice::Back => (),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Each row's label and choices, top to bottom
+fn rows() -> Vec<(&'static str, Vec<Choice>)> {
+    vec![
+        ("Players", [Side::X, Side::O, Side::Both, Side::Neither].iter().map(|&side| Choice::Side(side)).collect()),
+        ("Difficulty", Difficulty::ALL.iter().map(|&difficulty| Choice::Difficulty(difficulty)).collect()),
+        ("First move", vec![Choice::First(PlayerX), Choice::First(PlayerO)]),
+        ("Match", SERIES_LENGTHS.iter().map(|&n| Choice::BestOf(n)).collect()),
+        ("Theme", Theme::ALL.iter().map(|&theme| Choice::Theme(theme)).collect()),
+        ("Bot speed", BOT_DELAYS.iter().map(|&(millis, _)| Choice::BotDelay(millis)).collect()),
+        ("Patterns", vec![Choice::Patterns(false), Choice::Patterns(true)]),
+        ("Board", BOARD_SIZES.iter().map(|&size| Choice::BoardSize(size)).collect()),
+        ("In a row", WIN_LENGTHS.iter().map(|&length| Choice::WinLength(length)).collect()),
+    ]
+}
+
+/// The choice reached from `focus` by moving `dx` across and `dy` down,
+/// where the back button is a row of its own below the others. Moving up or
+/// down keeps to the same column where the next row has one.
+pub fn move_focus(focus: Option<Choice>, dx: i32, dy: i32) -> Choice {
+    let mut grid: Vec<Vec<Choice>> = rows().into_iter().map(|(_, row)| row).collect();
+    grid.push(vec![Choice::Back]);
+
+    let (row, col) = focus
+        .and_then(|focus| grid.iter().enumerate().find_map(|(i, row)| {
+            row.iter().position(|&choice| choice == focus).map(|j| (i, j))
+        }))
+        // the first key press only shows where the focus is
+        .map_or((0, 0), |(i, j)| {
+            ((i as i32 + dy).clamp(0, grid.len() as i32 - 1) as usize, j as i32 + dx)
+        });
+    let last = grid[row].len() as i32 - 1;
+    grid[row][col.clamp(0, last) as usize]
+}
+
+/// The height of a row, and the top of the first one
+fn row_metrics(window_size: [f64; 2]) -> (f64, f64) {
+    let height = window_size[1];
+    (height * 0.07, height * 0.14)
+}
+
+/// Lays out each row's choices as equal buttons to the right of its label,
+/// then the back button below the rows
+fn choices(window_size: [f64; 2]) -> Vec<(Choice, Rect)> {
+    let width = window_size[0];
+    let (row_height, top) = row_metrics(window_size);
+    let left = width * 0.3;
+    let right = width * 0.95;
+    let button_height = row_height * 0.7;
+    let gap = width * 0.015;
+
+    let mut choices = Vec::new();
+    for (i, (_, row)) in rows().into_iter().enumerate() {
+        let y = top + i as f64 * row_height + (row_height - button_height) / 2.0;
+        let count = row.len() as f64;
+        let button_width = (right - left - (count - 1.0) * gap) / count;
+        for (j, choice) in row.into_iter().enumerate() {
+            choices.push((choice, [left + j as f64 * (button_width + gap), y, button_width, button_height]));
+        }
+    }
+
+    // below the rows
+    let back_width = width * 0.3;
+    let back_y = top + rows().len() as f64 * row_height + row_height * 0.3;
+    choices.push((Choice::Back, [(width - back_width) / 2.0, back_y, back_width, row_height]));
+    choices
+}
+
+pub fn choice_at(window_size: [f64; 2], cursor_pos: [f64; 2]) -> Option<Choice> {
+    choices(window_size).into_iter()
+        .find(|&(_, rect)| layout::contains(rect, cursor_pos))
+        .map(|(choice, _)| choice)
+}
+
+/// Draws the screen with `focus`, under the cursor or picked with the keys,
+/// filled in
+pub fn draw(
+    settings: &Settings,
+    focus: Option<Choice>,
+    window_size: [f64; 2],
+    glyphs: &mut Glyphs,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let palette: Palette = settings.theme.palette();
+    let width = window_size[0];
+    let (row_height, top) = row_metrics(window_size);
+    let font_size = text::font_size(window_size, 0.03);
+
+    text::draw_centered("Settings", palette.text, text::font_size(window_size, 0.06),
+                        [0.0, 0.0, width, top], glyphs, context, graphics);
+
+    let label_x = width * 0.05;
+    for (i, (label, _)) in rows().into_iter().enumerate() {
+        let baseline = top + (i as f64 + 0.5) * row_height + font_size as f64 * 0.35;
+        text::draw(label, palette.text, font_size, [label_x, baseline], glyphs, context, graphics);
+    }
+
+    for (choice, rect) in choices(window_size) {
+        if Some(choice) == focus {
+            rectangle(palette.hover, rect, context.transform, graphics);
+        }
+        let (border_color, border_width) = if choice.is_selected(settings) {
+            (palette.selected, 3.0)
+        } else {
+            (palette.grid, 1.5)
+        };
+        Rectangle::new_border(border_color, border_width)
+            .draw(rect, &context.draw_state, context.transform, graphics);
+
+        let text_color = match choice {
+            Choice::Side(Side::X) | Choice::First(PlayerX) => palette.x,
+            Choice::Side(Side::O) | Choice::First(PlayerO) => palette.o,
+            _ => palette.text,
+        };
+        text::draw_centered(&choice.label(), text_color, font_size, rect, glyphs, context, graphics);
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+use piston_window::*;
+
+use tic_tac_torture::{GameStatus, Match, MatchStatus, PlayerKind};
+use tic_tac_torture::render::Palette;
+
+use super::layout::Rect;
+use super::{draw_o, draw_x, text};
+
+/// How long a message stays up in place of the status
+const MESSAGE_DURATION: Duration = Duration::from_millis(2000);
+
+/// The bar along the bottom of the window, with a piece for whose turn it
+/// is, the status or the latest message, and the score on the right
+pub struct StatusBar {
+    message: Option<(String, Instant)>,
+}
+
+impl StatusBar {
+    pub fn new() -> StatusBar {
+        StatusBar { message: None }
+    }
+
+    /// Shows `text` in place of the status for a couple of seconds, for
+    /// things like clicking a square that's taken
+    pub fn show_message(&mut self, text: &str, now: Instant) {
+        self.message = Some((text.to_string(), now));
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message = None;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        series: &Match,
+        bot_failed: bool,
+        palette: &Palette,
+        rect: Rect,
+        glyphs: &mut Glyphs,
+        now: Instant,
+        context: &Context,
+        graphics: &mut G2d
+    ) {
+        rectangle(palette.bar, rect, context.transform, graphics);
+
+        let [x, y, width, height] = rect;
+        let font_size = (height * 0.5) as u32;
+        let baseline = y + height * 0.68;
+        let pad = height * 0.3;
+
+        // the piece of the player to move, or of the winner
+        let icon = [x + pad / 2.0, y, height, height];
+        let icon_player = match &series.game.status {
+            GameStatus::InProgress => Some(series.game.current_player),
+            GameStatus::Win(player, _) => Some(*player),
+            GameStatus::Tie => None,
+        };
+        match icon_player {
+            Some(PlayerKind::PlayerX) => draw_x(context, graphics, icon, palette.x, 1.0),
+            Some(PlayerKind::PlayerO) => draw_o(context, graphics, icon, palette.o, 1.0),
+            None => {
+                let half = height / 2.0;
+                draw_x(context, graphics, [icon[0], y + half / 2.0, half, half], palette.loss, 1.0);
+                draw_o(context, graphics, [icon[0] + half, y + half / 2.0, half, half], palette.loss, 1.0);
+            },
+        }
+
+        let message = self.message.as_ref()
+            .filter(|(_, shown)| now.duration_since(*shown) < MESSAGE_DURATION);
+        let (status, color) = match message {
+            Some((message, _)) => (message.clone(), palette.message),
+            None => (status_text(series, bot_failed), palette.text),
+        };
+        text::draw(&status, color, font_size, [x + pad + height, baseline], glyphs, context, graphics);
+
+        let score = series.to_string();
+        let score_width = text::width_of(&score, font_size, glyphs);
+        text::draw(&score, palette.grid, font_size, [x + width - pad - score_width, baseline],
+                  glyphs, context, graphics);
+    }
+}
+
+/// What's happening in `series`. `bot_failed` is whether the bot stopped
+/// without a move for this turn, which leaves undoing or starting over.
+fn status_text(series: &Match, bot_failed: bool) -> String {
+    let game = &series.game;
+    let game_result = match &game.status {
+        GameStatus::InProgress => {
+            let player = game.current_player.to_char();
+            return if bot_failed {
+                format!("{} couldn't find a move", player)
+            } else if !game.current_player_is_human() {
+                format!("{} is thinking...", player)
+            } else {
+                format!("{} to play", player)
+            };
+        },
+        GameStatus::Win(player, _) => format!("{} wins!", player.to_char()),
+        GameStatus::Tie => "Tie game".to_string(),
+    };
+
+    match series.status() {
+        MatchStatus::Win(player) => format!("{} {} takes the match", game_result, player.to_char()),
+        MatchStatus::Draw => format!("{} The match is drawn", game_result),
+        MatchStatus::InProgress => game_result,
+    }
+}
@@ -0,0 +1,46 @@
+use piston_window::*;
+use piston_window::character::CharacterCache;
+
+use super::layout::Rect;
+
+/// Draws `text` with its baseline starting at `pos`
+pub fn draw(
+    text: &str,
+    color: [f32; 4],
+    font_size: u32,
+    pos: [f64; 2],
+    glyphs: &mut Glyphs,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let transform = context.transform.trans(pos[0], pos[1]);
+    // a glyph that fails to render is left out rather than losing the frame
+    let _ = Text::new_color(color, font_size).draw(text, glyphs, &context.draw_state, transform, graphics);
+}
+
+/// Draws `text` centered in `rect`
+pub fn draw_centered(
+    text: &str,
+    color: [f32; 4],
+    font_size: u32,
+    rect: Rect,
+    glyphs: &mut Glyphs,
+    context: &Context,
+    graphics: &mut G2d
+) {
+    let [x, y, width, height] = rect;
+    let text_width = width_of(text, font_size, glyphs);
+    // a baseline about a third of the font size below the middle centers
+    // capitals and most lowercase letters
+    let baseline = y + height / 2.0 + font_size as f64 * 0.35;
+    draw(text, color, font_size, [x + (width - text_width) / 2.0, baseline], glyphs, context, graphics);
+}
+
+pub fn width_of(text: &str, font_size: u32, glyphs: &mut Glyphs) -> f64 {
+    glyphs.width(font_size, text).unwrap_or(0.0)
+}
+
+/// A font size that scales with the window, `fraction` of its shorter side
+pub fn font_size(window_size: [f64; 2], fraction: f64) -> u32 {
+    (window_size[0].min(window_size[1]) * fraction).max(10.0) as u32
+}
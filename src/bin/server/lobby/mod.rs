@@ -0,0 +1,507 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use tic_tac_torture::*;
+use tic_tac_torture::net::{Message, Snapshot};
+
+#[cfg(test)]
+mod tests;
+
+pub type ClientId = u64;
+pub type GameId = u64;
+
+/// A line a client sends to the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// `HELLO <name>`, the name other players see
+    Hello(String),
+    /// `LIST`, the games in the lobby
+    List,
+    /// `CREATE`, opens a game as X for someone else to join
+    Create,
+    /// `JOIN <id>`, plays O in an open game
+    Join(GameId),
+    /// `PLAY`, joins the oldest open game, or opens one if there are none
+    Play,
+    /// `BOT [difficulty]`, starts a game as X against the bot
+    Bot(Difficulty),
+    /// `WATCH <id>`, follows a game without playing in it
+    Watch(GameId),
+    /// `MOVE <row> <col>`, as in the two player protocol
+    Move(Coord),
+    Resign,
+    /// `LEAVE`, goes back to the lobby, resigning a game in progress
+    Leave,
+}
+
+impl FromStr for Request {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Request, String> {
+        let line = line.trim();
+        let (command, rest) = match line.split_once(' ') {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+        let parse_id = |s: &str| s.parse::<GameId>().map_err(|_| format!("Expected a game id, found \"{}\"", s));
+
+        match (command.to_uppercase().as_str(), rest) {
+            // names are sent inside other lines, so they can't have spaces
+            ("HELLO", name) if !name.is_empty() => {
+                Ok(Request::Hello(name.split_whitespace().collect::<Vec<_>>().join("_")))
+            },
+            ("LIST", "") => Ok(Request::List),
+            ("CREATE", "") => Ok(Request::Create),
+            ("JOIN", id) if !id.is_empty() => Ok(Request::Join(parse_id(id)?)),
+            ("PLAY", "") => Ok(Request::Play),
+            ("BOT", "") => Ok(Request::Bot(Difficulty::default())),
+            ("BOT", difficulty) => Ok(Request::Bot(difficulty.parse()?)),
+            ("WATCH", id) if !id.is_empty() => Ok(Request::Watch(parse_id(id)?)),
+            ("MOVE", _) => match format!("MOVE {}", rest).parse::<Message>()? {
+                Message::Move(coord) => Ok(Request::Move(coord)),
+                _ => Err(format!("Unknown request \"{}\"", line)),
+            },
+            ("RESIGN", "") => Ok(Request::Resign),
+            ("LEAVE", "") => Ok(Request::Leave),
+            _ => Err(format!("Unknown request \"{}\"", line)),
+        }
+    }
+}
+
+/// How a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Three in a row, or a full board
+    Board,
+    Resigned,
+    /// The player to move ran out of time
+    Timeout,
+    /// A player left or lost their connection
+    Left,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Reason::Board => "board",
+            Reason::Resigned => "resigned",
+            Reason::Timeout => "timeout",
+            Reason::Left => "left",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Who sits on one side of a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Seat {
+    Human(ClientId),
+    Bot,
+}
+
+/// A game owned by the server, with its players and spectators
+struct Table {
+    game: Game,
+    x: Seat,
+    /// Empty while the game waits for a second player
+    o: Option<Seat>,
+    spectators: Vec<ClientId>,
+    /// When the player to move got the turn
+    turn_started: Instant,
+}
+
+impl Table {
+    fn seat(&self, player: PlayerKind) -> Option<Seat> {
+        match player {
+            PlayerKind::PlayerX => Some(self.x),
+            PlayerKind::PlayerO => self.o,
+        }
+    }
+
+    fn side_of(&self, client: ClientId) -> Option<PlayerKind> {
+        if self.x == Seat::Human(client) {
+            Some(PlayerKind::PlayerX)
+        } else if self.o == Some(Seat::Human(client)) {
+            Some(PlayerKind::PlayerO)
+        } else {
+            None
+        }
+    }
+
+    /// Everyone who hears about the game: its players, then its spectators
+    fn audience(&self) -> Vec<ClientId> {
+        let players = std::iter::once(self.x).chain(self.o).filter_map(|seat| match seat {
+            Seat::Human(client) => Some(client),
+            Seat::Bot => None,
+        });
+        players.chain(self.spectators.iter().copied()).collect()
+    }
+}
+
+/// Where a client is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Place {
+    Lobby,
+    Playing(GameId),
+    Watching(GameId),
+}
+
+struct Client {
+    name: String,
+    place: Place,
+}
+
+/// The server's clients and games, driven by the lines clients send and
+/// by the clock. Lines to send back are collected in an outbox, and the
+/// result of every finished game is collected for the log.
+///
+/// Replies are `NAME <name>` on connecting, with the name others see until
+/// the client sends `HELLO`, `GAME <id> <x> <o> <status>`
+/// lines ended by `END` for `LIST`, `WAITING <id>` for a game waiting for a
+/// player, `START <id> <side> <opponent>` when a game starts, `WATCHING <id>
+/// <x> <o>`, `STATE` lines as in the two player protocol after every
+/// change, `OVER <id> <X|O|tie> <reason>` when a game ends, `LOBBY` after
+/// leaving a game, and `ERROR <message>`.
+pub struct Lobby {
+    /// How long a player has for each move before losing the game
+    pub turn_timeout: Duration,
+    clients: HashMap<ClientId, Client>,
+    tables: BTreeMap<GameId, Table>,
+    next_game_id: GameId,
+    outbox: Vec<(ClientId, String)>,
+    results: Vec<String>,
+}
+
+impl Lobby {
+    pub fn new(turn_timeout: Duration) -> Lobby {
+        Lobby {
+            turn_timeout,
+            clients: HashMap::new(),
+            tables: BTreeMap::new(),
+            next_game_id: 1,
+            outbox: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn connect(&mut self, client: ClientId) {
+        let name = format!("guest{}", client);
+        // not `WELCOME`, which in the two player protocol also gives the
+        // side and rules of a game that's already begun
+        self.send(client, format!("NAME {}", name));
+        self.clients.insert(client, Client { name, place: Place::Lobby });
+    }
+
+    /// Removes a client, who loses a game they were playing
+    pub fn disconnect(&mut self, client: ClientId) {
+        self.leave(client);
+        self.clients.remove(&client);
+    }
+
+    /// Handles a line sent by `client`
+    pub fn handle(&mut self, client: ClientId, line: &str, now: Instant) {
+        if !self.clients.contains_key(&client) {
+            return;
+        }
+        let result = line.parse::<Request>().and_then(|request| self.handle_request(client, request, now));
+        if let Err(e) = result {
+            self.send(client, Message::Error(e).to_string());
+        }
+    }
+
+    /// Ends games in which the player to move has run out of time
+    pub fn tick(&mut self, now: Instant) {
+        let timed_out: Vec<(GameId, PlayerKind)> = self.tables.iter()
+            .filter(|(_, table)| table.o.is_some() && table.game.status == GameStatus::InProgress)
+            .filter(|(_, table)| now.duration_since(table.turn_started) >= self.turn_timeout)
+            .map(|(&id, table)| (id, table.game.current_player))
+            .collect();
+        for (id, player) in timed_out {
+            self.forfeit(id, player, Reason::Timeout);
+        }
+    }
+
+    /// The lines to send since the last call, in order
+    pub fn take_outbox(&mut self) -> Vec<(ClientId, String)> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// A line for each game finished since the last call, as `key=value`
+    /// pairs
+    pub fn take_results(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.results)
+    }
+
+    fn handle_request(&mut self, client: ClientId, request: Request, now: Instant) -> Result<(), String> {
+        match request {
+            Request::Hello(name) => {
+                self.clients.get_mut(&client).unwrap().name = name;
+                Ok(())
+            },
+            Request::List => {
+                let lines: Vec<String> = self.tables.iter().map(|(id, table)| {
+                    let status = match (table.o, &table.game.status) {
+                        (None, _) => "open",
+                        (_, GameStatus::InProgress) => "playing",
+                        _ => "over",
+                    };
+                    format!("GAME {} {} {} {}", id, self.player_name(table, PlayerKind::PlayerX),
+                            self.player_name(table, PlayerKind::PlayerO), status)
+                }).collect();
+                for line in lines {
+                    self.send(client, line);
+                }
+                self.send(client, "END".to_string());
+                Ok(())
+            },
+            Request::Create => {
+                self.leave_finished(client)?;
+                let id = self.open_table(client, None, now);
+                self.send(client, format!("WAITING {}", id));
+                Ok(())
+            },
+            Request::Join(id) => {
+                self.leave_finished(client)?;
+                match self.tables.get(&id) {
+                    None => Err(format!("There's no game {}", id)),
+                    Some(table) if table.o.is_some() => Err(format!("Game {} has already started", id)),
+                    Some(table) if table.x == Seat::Human(client) => Err("You can't play yourself".to_string()),
+                    Some(_) => {
+                        self.start(id, client, now);
+                        Ok(())
+                    },
+                }
+            },
+            Request::Play => {
+                self.leave_finished(client)?;
+                let open = self.tables.iter()
+                    .find(|(_, table)| table.o.is_none() && table.x != Seat::Human(client))
+                    .map(|(&id, _)| id);
+                match open {
+                    Some(id) => self.start(id, client, now),
+                    None => {
+                        let id = self.open_table(client, None, now);
+                        self.send(client, format!("WAITING {}", id));
+                    },
+                }
+                Ok(())
+            },
+            Request::Bot(difficulty) => {
+                self.leave_finished(client)?;
+                let id = self.open_table(client, Some(difficulty), now);
+                self.send(client, format!("START {} X bot-{}", id, difficulty));
+                self.broadcast_state(id);
+                Ok(())
+            },
+            Request::Watch(id) => {
+                self.leave_finished(client)?;
+                let line = match self.tables.get_mut(&id) {
+                    Some(table) => {
+                        table.spectators.push(client);
+                        format!("WATCHING {}", id)
+                    },
+                    None => return Err(format!("There's no game {}", id)),
+                };
+                let table = &self.tables[&id];
+                let line = format!("{} {} {}", line, self.player_name(table, PlayerKind::PlayerX),
+                                   self.player_name(table, PlayerKind::PlayerO));
+                let state = Message::State(Snapshot::of(&table.game)).to_string();
+                self.clients.get_mut(&client).unwrap().place = Place::Watching(id);
+                self.send(client, line);
+                self.send(client, state);
+                Ok(())
+            },
+            Request::Move(coord) => {
+                let (id, side) = self.playing(client)?;
+                let table = self.tables.get_mut(&id).unwrap();
+                if table.o.is_none() {
+                    return Err("Wait for another player to join".to_string());
+                }
+                // the server's game decides what's allowed
+                net::check_move(&table.game, side, coord)?;
+                table.game.make_move(coord.row, coord.col);
+                table.turn_started = now;
+                self.play_bot(id, now);
+                self.broadcast_state(id);
+                self.finish_if_over(id);
+                Ok(())
+            },
+            Request::Resign => {
+                let (id, side) = self.playing(client)?;
+                if self.tables[&id].o.is_none() {
+                    return Err("The game hasn't started".to_string());
+                }
+                self.forfeit(id, side, Reason::Resigned);
+                Ok(())
+            },
+            Request::Leave => {
+                if self.clients[&client].place == Place::Lobby {
+                    return Err("You're in the lobby".to_string());
+                }
+                self.leave(client);
+                self.send(client, "LOBBY".to_string());
+                Ok(())
+            },
+        }
+    }
+
+    /// The game `client` is playing and their side in it
+    fn playing(&self, client: ClientId) -> Result<(GameId, PlayerKind), String> {
+        match self.clients[&client].place {
+            Place::Playing(id) => Ok((id, self.tables[&id].side_of(client).unwrap())),
+            _ => Err("You're not playing a game".to_string()),
+        }
+    }
+
+    /// Refuses to leave a game still being played for another one
+    fn leave_finished(&mut self, client: ClientId) -> Result<(), String> {
+        if let Place::Playing(id) = self.clients[&client].place {
+            if self.tables[&id].o.is_some() {
+                return Err("Finish or resign your game first".to_string());
+            }
+        }
+        self.leave(client);
+        Ok(())
+    }
+
+    /// Takes `client` out of whatever they're playing or watching. Leaving
+    /// a game in progress loses it, and leaving an open game closes it.
+    fn leave(&mut self, client: ClientId) {
+        let place = match self.clients.get_mut(&client) {
+            Some(state) => std::mem::replace(&mut state.place, Place::Lobby),
+            None => return,
+        };
+        match place {
+            Place::Lobby => (),
+            Place::Watching(id) => {
+                if let Some(table) = self.tables.get_mut(&id) {
+                    table.spectators.retain(|&spectator| spectator != client);
+                }
+            },
+            Place::Playing(id) => {
+                let table = &self.tables[&id];
+                if table.o.is_none() {
+                    self.close(id);
+                } else if let Some(side) = table.side_of(client) {
+                    self.forfeit(id, side, Reason::Left);
+                }
+            },
+        }
+    }
+
+    fn open_table(&mut self, client: ClientId, bot: Option<Difficulty>, now: Instant) -> GameId {
+        let id = self.next_game_id;
+        self.next_game_id += 1;
+
+        let config = GameConfig::new()
+            .human(PlayerKind::PlayerX, true)
+            .human(PlayerKind::PlayerO, bot.is_none())
+            .difficulty(bot.unwrap_or_default());
+        self.tables.insert(id, Table {
+            game: config.build(),
+            x: Seat::Human(client),
+            o: bot.map(|_| Seat::Bot),
+            spectators: Vec::new(),
+            turn_started: now,
+        });
+        self.clients.get_mut(&client).unwrap().place = Place::Playing(id);
+        id
+    }
+
+    /// Seats `client` as O in the open game `id` and starts it
+    fn start(&mut self, id: GameId, client: ClientId, now: Instant) {
+        let table = self.tables.get_mut(&id).unwrap();
+        table.o = Some(Seat::Human(client));
+        table.turn_started = now;
+        let x = table.x;
+        self.clients.get_mut(&client).unwrap().place = Place::Playing(id);
+
+        let x_name = self.player_name(&self.tables[&id], PlayerKind::PlayerX);
+        let o_name = self.clients[&client].name.clone();
+        if let Seat::Human(x) = x {
+            self.send(x, format!("START {} X {}", id, o_name));
+        }
+        self.send(client, format!("START {} O {}", id, x_name));
+        self.broadcast_state(id);
+    }
+
+    /// Lets the bot move for as long as it has the turn
+    fn play_bot(&mut self, id: GameId, now: Instant) {
+        let table = self.tables.get_mut(&id).unwrap();
+        while table.game.status == GameStatus::InProgress
+            && table.seat(table.game.current_player) == Some(Seat::Bot) {
+            let (row, col) = bot::get_bot_move(&mut table.game);
+            table.game.make_move(row, col);
+            table.turn_started = now;
+        }
+    }
+
+    /// Ends the game with a loss for `player`
+    fn forfeit(&mut self, id: GameId, player: PlayerKind, reason: Reason) {
+        let table = self.tables.get_mut(&id).unwrap();
+        table.game.resign(player);
+        self.broadcast_state(id);
+        self.finish(id, reason);
+    }
+
+    fn finish_if_over(&mut self, id: GameId) {
+        if self.tables[&id].game.status != GameStatus::InProgress {
+            self.finish(id, Reason::Board);
+        }
+    }
+
+    /// Announces and logs the result, then sends everyone at the table back
+    /// to the lobby
+    fn finish(&mut self, id: GameId, reason: Reason) {
+        let table = &self.tables[&id];
+        let result = match &table.game.status {
+            GameStatus::Win(player, _) => player.to_char().to_string(),
+            _ => "tie".to_string(),
+        };
+        self.results.push(format!("game={} x={} o={} result={} reason={} board={}",
+                                  id, self.player_name(table, PlayerKind::PlayerX),
+                                  self.player_name(table, PlayerKind::PlayerO),
+                                  result, reason, board_string(&table.game.board)));
+        let line = format!("OVER {} {} {}", id, result, reason);
+        for client in table.audience() {
+            self.send(client, line.clone());
+        }
+        self.close(id);
+    }
+
+    /// Removes a table, sending anyone still at it back to the lobby
+    fn close(&mut self, id: GameId) {
+        if let Some(table) = self.tables.remove(&id) {
+            for client in table.audience() {
+                if let Some(state) = self.clients.get_mut(&client) {
+                    state.place = Place::Lobby;
+                }
+            }
+        }
+    }
+
+    fn broadcast_state(&mut self, id: GameId) {
+        let table = &self.tables[&id];
+        let line = Message::State(Snapshot::of(&table.game)).to_string();
+        for client in table.audience() {
+            self.send(client, line.clone());
+        }
+    }
+
+    /// The name of whoever plays `player` at `table`, or `-` for an empty
+    /// seat
+    fn player_name(&self, table: &Table, player: PlayerKind) -> String {
+        match table.seat(player) {
+            Some(Seat::Human(client)) => self.clients.get(&client)
+                .map_or_else(|| format!("guest{}", client), |state| state.name.clone()),
+            Some(Seat::Bot) => format!("bot-{}", table.game.difficulty),
+            None => "-".to_string(),
+        }
+    }
+
+    fn send(&mut self, client: ClientId, line: String) {
+        self.outbox.push((client, line));
+    }
+}
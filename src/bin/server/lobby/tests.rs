@@ -0,0 +1,148 @@
+use super::*;
+
+/// A lobby with `count` clients connected, and their `NAME` lines cleared
+fn lobby_with(count: ClientId) -> (Lobby, Instant) {
+    let mut lobby = Lobby::new(Duration::from_secs(30));
+    for client in 1..=count {
+        lobby.connect(client);
+    }
+    lobby.take_outbox();
+    (lobby, Instant::now())
+}
+
+/// The lines sent to `client` since the last call, clearing the outbox
+fn lines_for(lobby: &mut Lobby, client: ClientId) -> Vec<String> {
+    lobby.take_outbox().into_iter()
+        .filter(|&(to, _)| to == client)
+        .map(|(_, line)| line)
+        .collect()
+}
+
+#[test]
+fn parse_request_test() {
+    assert_eq!("HELLO ann lee".parse(), Ok(Request::Hello("ann_lee".to_string())));
+    assert_eq!("join 3".parse(), Ok(Request::Join(3)));
+    assert_eq!("BOT".parse(), Ok(Request::Bot(Difficulty::Hard)));
+    assert_eq!("BOT easy".parse(), Ok(Request::Bot(Difficulty::Easy)));
+    assert_eq!("move 2 3".parse(), Ok(Request::Move(Coord::new(1, 2))));
+
+    for bad in &["", "HELLO", "JOIN", "JOIN x", "BOT expert", "MOVE 4 1", "LIST all", "FOO"] {
+        assert!(bad.parse::<Request>().is_err(), "{:?} parsed", bad);
+    }
+}
+
+#[test]
+fn connect_test() {
+    let mut lobby = Lobby::new(Duration::from_secs(30));
+    lobby.connect(4);
+    assert_eq!(lines_for(&mut lobby, 4), ["NAME guest4"]);
+}
+
+#[test]
+fn pair_players_and_play_test() {
+    let (mut lobby, now) = lobby_with(2);
+    lobby.handle(1, "HELLO ann", now);
+    lobby.handle(2, "HELLO bob", now);
+
+    lobby.handle(1, "PLAY", now);
+    assert_eq!(lines_for(&mut lobby, 1), ["WAITING 1"]);
+    lobby.handle(2, "LIST", now);
+    assert_eq!(lines_for(&mut lobby, 2), ["GAME 1 ann - open", "END"]);
+
+    lobby.handle(2, "PLAY", now);
+    let outbox = lobby.take_outbox();
+    assert!(outbox.contains(&(1, "START 1 X bob".to_string())));
+    assert!(outbox.contains(&(2, "START 1 O ann".to_string())));
+    assert!(outbox.contains(&(2, "STATE .../.../... X -".to_string())));
+
+    // turn order is enforced by the server's game
+    lobby.handle(2, "MOVE 1 1", now);
+    assert_eq!(lines_for(&mut lobby, 2), ["ERROR It's X's turn"]);
+
+    for &(client, line) in &[(1, "MOVE 1 1"), (2, "MOVE 2 1"), (1, "MOVE 1 2"), (2, "MOVE 2 2"), (1, "MOVE 1 3")] {
+        lobby.handle(client, line, now);
+    }
+    let lines = lines_for(&mut lobby, 2);
+    assert_eq!(&lines[lines.len() - 2..], ["STATE XXX/OO./... O X", "OVER 1 X board"]);
+    assert_eq!(lobby.take_results(), ["game=1 x=ann o=bob result=X reason=board board=XXX/OO./..."]);
+
+    // both players are back in the lobby, and the game is gone
+    lobby.handle(1, "LIST", now);
+    assert_eq!(lines_for(&mut lobby, 1), ["END"]);
+    lobby.handle(2, "MOVE 3 3", now);
+    assert_eq!(lines_for(&mut lobby, 2), ["ERROR You're not playing a game"]);
+}
+
+#[test]
+fn bot_replies_test() {
+    let (mut lobby, now) = lobby_with(1);
+    lobby.handle(1, "BOT", now);
+    assert_eq!(lines_for(&mut lobby, 1), ["START 1 X bot-hard", "STATE .../.../... X -"]);
+
+    lobby.handle(1, "MOVE 2 2", now);
+    let lines = lines_for(&mut lobby, 1);
+    assert_eq!(lines.len(), 1);
+    // the bot's reply comes back in the same state, so it's X's turn again
+    assert!(lines[0].starts_with("STATE ") && lines[0].ends_with(" X -"), "{}", lines[0]);
+    assert_eq!(lines[0].matches('O').count(), 1);
+}
+
+#[test]
+fn spectators_follow_the_game_test() {
+    let (mut lobby, now) = lobby_with(3);
+    lobby.handle(1, "CREATE", now);
+    lobby.handle(2, "JOIN 1", now);
+    lobby.handle(1, "MOVE 2 2", now);
+    lobby.take_outbox();
+
+    lobby.handle(3, "WATCH 1", now);
+    assert_eq!(lines_for(&mut lobby, 3), ["WATCHING 1 guest1 guest2", "STATE .../.X./... O -"]);
+
+    // spectators can't play
+    lobby.handle(3, "MOVE 1 1", now);
+    assert_eq!(lines_for(&mut lobby, 3), ["ERROR You're not playing a game"]);
+
+    lobby.handle(2, "RESIGN", now);
+    assert_eq!(lines_for(&mut lobby, 3), ["STATE .../.X./... O X", "OVER 1 X resigned"]);
+}
+
+#[test]
+fn turn_timeout_test() {
+    let (mut lobby, now) = lobby_with(2);
+    lobby.handle(1, "CREATE", now);
+    // an open game waits as long as it takes
+    lobby.tick(now + Duration::from_secs(100));
+    lobby.handle(2, "JOIN 1", now + Duration::from_secs(100));
+    lobby.handle(1, "MOVE 1 1", now + Duration::from_secs(110));
+    lobby.take_outbox();
+
+    lobby.tick(now + Duration::from_secs(139));
+    assert!(lobby.take_results().is_empty());
+    lobby.tick(now + Duration::from_secs(140));
+    assert_eq!(lobby.take_results(), ["game=1 x=guest1 o=guest2 result=X reason=timeout board=X../.../..."]);
+    assert!(lobby.take_outbox().contains(&(1, "OVER 1 X timeout".to_string())));
+}
+
+#[test]
+fn leaving_test() {
+    let (mut lobby, now) = lobby_with(3);
+    lobby.handle(1, "CREATE", now);
+    lobby.handle(2, "JOIN 1", now);
+    lobby.take_outbox();
+
+    // a game in progress has to be finished first
+    lobby.handle(1, "CREATE", now);
+    assert_eq!(lines_for(&mut lobby, 1), ["ERROR Finish or resign your game first"]);
+    lobby.handle(2, "JOIN 1", now);
+    assert_eq!(lines_for(&mut lobby, 2), ["ERROR Finish or resign your game first"]);
+
+    // losing the connection loses the game
+    lobby.disconnect(1);
+    assert_eq!(lines_for(&mut lobby, 2), ["STATE .../.../... X O", "OVER 1 O left"]);
+
+    // and leaving an open game closes it
+    lobby.handle(3, "CREATE", now);
+    lobby.handle(3, "LEAVE", now);
+    lobby.handle(2, "JOIN 2", now);
+    assert_eq!(lines_for(&mut lobby, 2), ["ERROR There's no game 2"]);
+}
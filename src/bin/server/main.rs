@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tungstenite::Message as Frame;
+
+use lobby::{ClientId, Lobby};
+
+mod lobby;
+
+const DEFAULT_PORT: u16 = 7879;
+const DEFAULT_WEBSOCKET_PORT: u16 = 7880;
+const DEFAULT_TURN_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_LOG: &str = "results.log";
+
+/// How often the lobby checks the clock when no one is sending anything,
+/// and how long a WebSocket client's thread waits for a frame before
+/// sending what's queued for it
+const TICK: Duration = Duration::from_millis(100);
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// What the connection threads tell the lobby
+enum Event {
+    /// A client connected, with the channel to send it lines on
+    Connected(ClientId, Sender<String>),
+    Line(ClientId, String),
+    Disconnected(ClientId),
+}
+
+struct Options {
+    port: u16,
+    websocket_port: u16,
+    turn_timeout: Duration,
+    log: String,
+}
+
+impl Options {
+    fn from_args(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            port: DEFAULT_PORT,
+            websocket_port: DEFAULT_WEBSOCKET_PORT,
+            turn_timeout: Duration::from_secs(DEFAULT_TURN_TIMEOUT_SECS),
+            log: DEFAULT_LOG.to_string(),
+        };
+
+        let mut i = 1;
+        while i < args.len() {
+            let value = args.get(i + 1).ok_or_else(|| format!("{} needs a value", args[i]))?;
+            let parse_number = |value: &str| {
+                value.parse::<u64>().map_err(|_| format!("Expected a number for {}, found \"{}\"", args[i], value))
+            };
+            let parse_port = |value: &str| {
+                value.parse::<u16>().map_err(|_| {
+                    format!("Expected a port number from 0 to {} for {}, found \"{}\"", u16::MAX, args[i], value)
+                })
+            };
+            match args[i].as_str() {
+                "--port" => options.port = parse_port(value)?,
+                "--ws-port" => options.websocket_port = parse_port(value)?,
+                "--turn-timeout" => options.turn_timeout = Duration::from_secs(parse_number(value)?),
+                "--log" => options.log = value.clone(),
+                arg => return Err(format!("Unknown option \"{}\"", arg)),
+            }
+            i += 2;
+        }
+        Ok(options)
+    }
+}
+
+/// Reads lines from a plain TCP client until it leaves, with a second
+/// thread writing the lines sent to it
+fn serve_tcp(stream: TcpStream, events: Sender<Event>) -> io::Result<()> {
+    let id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, outgoing) = mpsc::channel::<String>();
+    let mut writer = stream.try_clone()?;
+    thread::spawn(move || {
+        for line in outgoing {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let _ = events.send(Event::Connected(id, sender));
+    for line in BufReader::new(stream).lines() {
+        match line {
+            Ok(line) if line.trim().is_empty() => (),
+            Ok(line) => {
+                let _ = events.send(Event::Line(id, line));
+            },
+            Err(_) => break,
+        }
+    }
+    let _ = events.send(Event::Disconnected(id));
+    Ok(())
+}
+
+/// Serves a WebSocket client, where every text frame holds one or more
+/// lines of the same protocol. The socket can't be read and written from
+/// different threads, so reads time out to let queued lines go out.
+fn serve_websocket(stream: TcpStream, events: Sender<Event>) -> io::Result<()> {
+    let mut socket = tungstenite::accept(stream.try_clone()?)
+        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e.to_string()))?;
+    stream.set_read_timeout(Some(TICK))?;
+
+    let id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, outgoing) = mpsc::channel::<String>();
+    let _ = events.send(Event::Connected(id, sender));
+
+    'frames: loop {
+        match socket.read() {
+            Ok(Frame::Text(text)) => {
+                for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                    let _ = events.send(Event::Line(id, line.to_string()));
+                }
+            },
+            Ok(Frame::Close(_)) => break,
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => (),
+            Err(_) => break,
+        }
+        while let Ok(line) = outgoing.try_recv() {
+            if socket.send(Frame::Text(line)).is_err() {
+                break 'frames;
+            }
+        }
+    }
+    let _ = events.send(Event::Disconnected(id));
+    Ok(())
+}
+
+/// Accepts clients on `listener` for as long as the server runs, each on a
+/// thread of its own
+fn accept(listener: TcpListener, events: Sender<Event>, serve: fn(TcpStream, Sender<Event>) -> io::Result<()>) {
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let events = events.clone();
+            thread::spawn(move || serve(stream, events));
+        }
+    });
+}
+
+fn bind(port: u16) -> TcpListener {
+    match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Couldn't listen on port {}: {}", port, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Appends a finished game to the log, with the time it ended in seconds
+/// since the Unix epoch
+fn log_result(path: &str, result: &str) {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let line = format!("time={} {}", time, result);
+    println!("{}", line);
+    let written = OpenOptions::new().create(true).append(true).open(path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = written {
+        eprintln!("Couldn't write to {}: {}", path, e);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let options = match Options::from_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let (events, received) = mpsc::channel();
+    accept(bind(options.port), events.clone(), serve_tcp);
+    accept(bind(options.websocket_port), events, serve_websocket);
+    println!("Listening on port {} for TCP and {} for WebSocket clients", options.port, options.websocket_port);
+
+    // the lobby and every game in it are only touched on this thread
+    let mut lobby = Lobby::new(options.turn_timeout);
+    let mut clients: HashMap<ClientId, Sender<String>> = HashMap::new();
+    loop {
+        match received.recv_timeout(TICK) {
+            Ok(Event::Connected(id, sender)) => {
+                clients.insert(id, sender);
+                lobby.connect(id);
+            },
+            Ok(Event::Line(id, line)) => lobby.handle(id, &line, Instant::now()),
+            Ok(Event::Disconnected(id)) => {
+                lobby.disconnect(id);
+                clients.remove(&id);
+            },
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        lobby.tick(Instant::now());
+
+        for (id, line) in lobby.take_outbox() {
+            if let Some(sender) = clients.get(&id) {
+                let _ = sender.send(line);
+            }
+        }
+        for result in lobby.take_results() {
+            log_result(&options.log, &result);
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use std::env;
+use std::io::{self, Write};
+use std::process;
+use std::time::Duration;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor,
+                       SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use tic_tac_torture::*;
+use tic_tac_torture::PlayerKind::*;
+use tic_tac_torture::records::{self, RecordStore, Recorder};
+use tic_tac_torture::render::Palette;
+use tic_tac_torture::scheduler::BotScheduler;
+use tic_tac_torture::settings::Settings;
+
+const TICK_MILLIS: u64 = 20;
+
+// where the top left corner of the board is drawn
+const BOARD_LEFT: u16 = 2;
+const BOARD_TOP: u16 = 1;
+// each square is drawn as " X " between the grid lines
+const CELL_WIDTH: u16 = 4;
+const CELL_HEIGHT: u16 = 2;
+// the status and score panels go to the right of the board
+const PANEL_LEFT: u16 = BOARD_LEFT + 4 + CELL_WIDTH * BOARD_SIZE as u16 + 3;
+
+/// Puts the terminal into raw mode on an alternate screen, and puts it back
+/// when dropped, so the terminal is left usable even if the game panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// What a key press asks for
+enum Action {
+    MoveCursor(isize, isize),
+    Play,
+    Undo,
+    NewMatch,
+    Quit,
+    None,
+}
+
+fn key_action(key: KeyEvent) -> Action {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Action::Quit;
+    }
+
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => Action::MoveCursor(-1, 0),
+        KeyCode::Down | KeyCode::Char('j') => Action::MoveCursor(1, 0),
+        KeyCode::Left | KeyCode::Char('h') => Action::MoveCursor(0, -1),
+        KeyCode::Right | KeyCode::Char('l') => Action::MoveCursor(0, 1),
+        KeyCode::Enter | KeyCode::Char(' ') => Action::Play,
+        KeyCode::Char('u') => Action::Undo,
+        KeyCode::Char('n') => Action::NewMatch,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        _ => Action::None,
+    }
+}
+
+/// A colour of the theme as the terminal's, dropping the alpha, which
+/// terminals can't show
+fn color(rgba: [f32; 4]) -> Color {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::Rgb { r: channel(rgba[0]), g: channel(rgba[1]), b: channel(rgba[2]) }
+}
+
+fn player_color(player: PlayerKind, palette: &Palette) -> Color {
+    match player {
+        PlayerX => color(palette.x),
+        PlayerO => color(palette.o),
+    }
+}
+
+// draws the board with box characters, the files and ranks along the edges,
+// and the square under the cursor in reverse video, in the colours of the
+// theme picked in the settings
+fn draw_board(out: &mut impl Write, game: &Game, cursor: Coord, palette: &Palette) -> io::Result<()> {
+    let n = BOARD_SIZE as u16;
+    let left = BOARD_LEFT + 2;
+    let grid_color = color(palette.grid);
+    let loss_color = color(palette.loss);
+
+    let win_lines: &[Line] = match &game.status {
+        GameStatus::Win(_, lines) => lines,
+        _ => &[],
+    };
+
+    // file letters along the top
+    for col in 0..BOARD_SIZE {
+        queue!(out, MoveTo(left + 2 + col as u16 * CELL_WIDTH, BOARD_TOP), Print(file_char(col)))?;
+    }
+
+    for row in 0..=n {
+        let y = BOARD_TOP + 1 + row * CELL_HEIGHT;
+        let (start, middle, end) = match row {
+            0 => ('┌', '┬', '┐'),
+            r if r == n => ('└', '┴', '┘'),
+            _ => ('├', '┼', '┤'),
+        };
+        let mut line = start.to_string();
+        for col in 0..n {
+            line.push_str("───");
+            line.push(if col == n - 1 { end } else { middle });
+        }
+        queue!(out, SetForegroundColor(grid_color), MoveTo(left, y), Print(line), ResetColor)?;
+    }
+
+    for row in 0..BOARD_SIZE {
+        let y = BOARD_TOP + 2 + row as u16 * CELL_HEIGHT;
+        queue!(out, MoveTo(BOARD_LEFT, y), Print(row + 1))?;
+
+        for col in 0..BOARD_SIZE {
+            let x = left + col as u16 * CELL_WIDTH;
+            queue!(out, SetForegroundColor(grid_color), MoveTo(x, y), Print('│'), ResetColor)?;
+
+            let coord = Coord::new(row, col);
+            if win_lines.iter().any(|line| line.contains(coord)) {
+                queue!(out, SetBackgroundColor(color(palette.hover)), SetAttribute(Attribute::Bold))?;
+            }
+            if coord == cursor && game.status == GameStatus::InProgress {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+
+            let (symbol, color) = match game.board[row][col] {
+                // pieces of a loser, or of both players in a tie, are greyed out
+                Some(player) => match game.status {
+                    GameStatus::Win(winner, _) if winner != player => (player.to_char(), loss_color),
+                    GameStatus::Tie => (player.to_char(), loss_color),
+                    _ => (player.to_char(), player_color(player, palette)),
+                },
+                None => (' ', grid_color),
+            };
+            queue!(
+                out,
+                SetForegroundColor(color),
+                Print(format!(" {} ", symbol)),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+        queue!(
+            out,
+            SetForegroundColor(grid_color),
+            MoveTo(left + n * CELL_WIDTH, y),
+            Print('│'),
+            ResetColor
+        )?;
+    }
+    Ok(())
+}
+
+/// What's happening in `series`. `bot_failed` is whether the bot stopped
+/// without a move for this turn, which leaves undoing or starting over.
+fn status_text(series: &Match, bot_failed: bool, palette: &Palette) -> (String, Color) {
+    let game = &series.game;
+    match &game.status {
+        GameStatus::InProgress => {
+            let player = game.current_player;
+            let who = if bot_failed {
+                "the bot couldn't find a move"
+            } else if game.current_player_is_human() {
+                "your move"
+            } else {
+                "bot is thinking"
+            };
+            (format!("{} to play, {}", player.to_char(), who), player_color(player, palette))
+        },
+        GameStatus::Tie => ("Tie game".to_string(), color(palette.grid)),
+        GameStatus::Win(player, _) => (format!("{} has won!", player.to_char()), player_color(*player, palette)),
+    }
+}
+
+fn draw_panels(out: &mut impl Write, series: &Match, bot_failed: bool, palette: &Palette) -> io::Result<()> {
+    let grid_color = color(palette.grid);
+    let (status, status_color) = status_text(series, bot_failed, palette);
+    let match_result = match series.status() {
+        MatchStatus::Win(player) => (format!("{} wins the match", player.to_char()), player_color(player, palette)),
+        MatchStatus::Draw => ("The match is drawn".to_string(), grid_color),
+        MatchStatus::InProgress => (String::new(), grid_color),
+    };
+    let keys = if series.game.status == GameStatus::InProgress {
+        "arrows/hjkl move, enter play"
+    } else if series.status() == MatchStatus::InProgress {
+        "enter for the next game"
+    } else {
+        "n for a new match"
+    };
+
+    let lines = [
+        ("Status".to_string(), grid_color),
+        (status, status_color),
+        (String::new(), grid_color),
+        ("Score".to_string(), grid_color),
+        (series.to_string(), color(palette.text)),
+        match_result,
+        (String::new(), grid_color),
+        (keys.to_string(), grid_color),
+        ("u undo, n new match, q quit".to_string(), grid_color),
+    ];
+
+    for (i, (text, color)) in lines.iter().enumerate() {
+        queue!(
+            out,
+            MoveTo(PANEL_LEFT, BOARD_TOP + i as u16),
+            SetForegroundColor(*color),
+            Print(text),
+            ResetColor
+        )?;
+    }
+    Ok(())
+}
+
+fn draw(out: &mut impl Write, series: &Match, bot_failed: bool, cursor: Coord, palette: &Palette) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All))?;
+    draw_board(out, &series.game, cursor, palette)?;
+    draw_panels(out, series, bot_failed, palette)?;
+    out.flush()
+}
+
+/// Plays until the player quits, recording finished games in `recorder`.
+/// Nothing can be printed over the board, so a game that couldn't be
+/// recorded is reported once the terminal is restored. The bot's delay and
+/// the colours come from `settings`, shared with the GUI.
+fn run(
+    series: &mut Match,
+    settings: &Settings,
+    recorder: &mut Recorder,
+    record_error: &mut Option<String>
+) -> io::Result<()> {
+    let mut out = io::stdout();
+    let mut cursor = Coord::new(1, 1);
+    let palette = settings.theme.palette();
+    let mut bot = BotScheduler::new(Duration::from_millis(settings.bot_delay_millis));
+    let mut redraw = true;
+    // whether the board last drawn shows the bot failing to move
+    let mut failure_shown = false;
+
+    loop {
+        let (x, o) = records::local_names(&series.game);
+        if let Err(e) = recorder.update(&series.game, &x, &o) {
+            record_error.get_or_insert(e);
+        }
+
+        if redraw {
+            failure_shown = bot.has_failed(series.game.turn_id);
+            draw(&mut out, series, failure_shown, cursor, &palette)?;
+            redraw = false;
+        }
+
+        if let Some((row, col)) = bot.update(&mut series.game) {
+            series.make_move(row, col);
+            redraw = true;
+            continue;
+        }
+        if bot.has_failed(series.game.turn_id) != failure_shown {
+            redraw = true;
+            continue;
+        }
+
+        // keys are waited for a tick at a time, so the bot's move is picked
+        // up soon after it's ready
+        if !event::poll(Duration::from_millis(TICK_MILLIS))? {
+            continue;
+        }
+        redraw = true;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            // anything else, such as a resize, just redraws
+            _ => continue,
+        };
+
+        match key_action(key) {
+            Action::MoveCursor(rows, cols) => {
+                let step = |value: usize, by: isize| {
+                    (value as isize + by).rem_euclid(BOARD_SIZE as isize) as usize
+                };
+                cursor = Coord::new(step(cursor.row, rows), step(cursor.col, cols));
+            },
+            Action::Play => {
+                let game = &series.game;
+                if game.status != GameStatus::InProgress {
+                    series.next_game();
+                } else if game.current_player_is_human() && game.board[cursor.row][cursor.col].is_none() {
+                    series.make_move(cursor.row, cursor.col);
+                }
+            },
+            Action::Undo => {
+                series.undo_to_human();
+            },
+            Action::NewMatch => {
+                *series = Match::new(series.config.clone(), series.best_of);
+                recorder.restart();
+            },
+            Action::Quit => return Ok(()),
+            Action::None => (),
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut series = match Match::from_args(&args) {
+        Ok(series) => series,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let (settings, warnings) = Settings::load();
+    let mut recorder = Recorder::new(RecordStore::open_default());
+    let mut record_error = None;
+    let result = TerminalGuard::new()
+        .and_then(|_guard| run(&mut series, &settings, &mut recorder, &mut record_error));
+    // shown once the terminal is back, where they won't be drawn over
+    for warning in warnings {
+        eprintln!("{}", warning);
+    }
+    if let Some(e) = record_error {
+        eprintln!("{}", e);
+    }
+    if let Err(e) = result {
+        eprintln!("Terminal error: {}", e);
+        process::exit(1);
+    }
+}
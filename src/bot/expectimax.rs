@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use super::super::{has_won, is_full, Board, PlayerKind, Ruleset};
+
+/// Chooses the move with the best expected result for `player` under
+/// `ruleset`. Unlike `bot::get_move` this doesn't assume the players
+/// alternate: positions where chance decides who moves next are scored by
+/// averaging over the possible movers (expectimax), so it also plays
+/// Random-Turn and handicap games.
+pub fn get_move(player: PlayerKind, board: &Board, ruleset: &Ruleset) -> (usize, usize) {
+    let mut best_move: Option<(usize, usize)> = None;
+    let mut best_value = f64::NEG_INFINITY;
+    for (square, value) in move_values(player, board, ruleset) {
+        if value > best_value {
+            best_move = Some(square);
+            best_value = value;
+        }
+    }
+
+    match best_move {
+        Some(m) => m,
+        None => panic!("No move was found for the bot."),
+    }
+}
+
+/// Every empty square as (row, col) with the value of `player` playing
+/// there, on the same scale as `evaluate`
+pub fn move_values(player: PlayerKind, board: &Board, ruleset: &Ruleset) -> Vec<((usize, usize), f64)> {
+    let mut search = Search {
+        player,
+        ruleset,
+        values: HashMap::new(),
+    };
+
+    empty_squares(board).into_iter()
+        .map(|(row, col)| {
+            let mut board_copy = *board;
+            board_copy[row][col] = Some(player);
+            ((row, col), search.after_move(player, &board_copy))
+        })
+        .collect()
+}
+
+/// Scores `board` with `player` to move, from `player`'s point of view:
+/// positive when `player` wins with best play, negative when they lose and
+/// zero for a draw. Wins are worth more the sooner they happen, one plus
+/// the number of squares left empty.
+pub fn evaluate(player: PlayerKind, board: &Board, ruleset: &Ruleset) -> f64 {
+    let mut search = Search {
+        player,
+        ruleset,
+        values: HashMap::new(),
+    };
+    search.before_move(player, board)
+}
+
+struct Search<'a> {
+    /// The player the search is choosing moves for
+    player: PlayerKind,
+    ruleset: &'a Ruleset,
+    /// The value of each board with a given player to move
+    values: HashMap<(Board, PlayerKind), f64>,
+}
+
+impl<'a> Search<'a> {
+    /// Scores `board` right after `mover` played on it, from the point of
+    /// view of `self.player`. Wins are worth more the sooner they happen.
+    fn after_move(&mut self, mover: PlayerKind, board: &Board) -> f64 {
+        if has_won(mover, board) {
+            let score = 1.0 + empty_squares(board).len() as f64;
+            return if mover == self.player { score } else { -score };
+        }
+
+        if is_full(board) {
+            return 0.0;
+        }
+
+        self.ruleset.next_player_odds(mover, board)
+            .into_iter()
+            .map(|(next, chance)| chance * self.before_move(next, board))
+            .sum()
+    }
+
+    /// Scores `board` with `mover` about to play, who picks the best move
+    /// for themselves
+    fn before_move(&mut self, mover: PlayerKind, board: &Board) -> f64 {
+        if let Some(&value) = self.values.get(&(*board, mover)) {
+            return value;
+        }
+
+        let values: Vec<f64> = empty_squares(board).into_iter()
+            .map(|(row, col)| {
+                let mut board_copy = *board;
+                board_copy[row][col] = Some(mover);
+                self.after_move(mover, &board_copy)
+            })
+            .collect();
+
+        let value = if mover == self.player {
+            values.into_iter().fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            values.into_iter().fold(f64::INFINITY, f64::min)
+        };
+
+        self.values.insert((*board, mover), value);
+        value
+    }
+}
+
+fn empty_squares(board: &Board) -> Vec<(usize, usize)> {
+    let mut squares = Vec::new();
+    for (i, row) in board.iter().enumerate() {
+        for (j, square) in row.iter().enumerate() {
+            if square.is_none() {
+                squares.push((i, j));
+            }
+        }
+    }
+    squares
+}
@@ -1,7 +1,10 @@
-use super::{Board, PlayerKind};
+use std::fmt;
+
+use super::{Board, Difficulty, Game, PlayerKind};
 
 #[cfg(test)]
 mod tests;
+pub mod expectimax;
 
 #[derive(Debug, PartialEq)]
 struct Position {
@@ -9,25 +12,112 @@ struct Position {
     col: usize,
 }
 
+/// A rule of the perfect strategy, which finds a move if the rule applies
+type Rule = fn(PlayerKind, &Board) -> Option<Position>;
+
+/// The rule of the perfect strategy that picked a move, in the order the
+/// rules are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MoveReason {
+    Win,
+    Block,
+    Fork,
+    BlockFork,
+    Center,
+    OppositeCorner,
+    EmptyCorner,
+    EmptySide,
+}
+
+impl MoveReason {
+    /// A sentence explaining the rule
+    pub fn description(&self) -> &'static str {
+        match self {
+            MoveReason::Win => "Completes three in a row.",
+            MoveReason::Block => "Blocks the opponent's three in a row.",
+            MoveReason::Fork => "Makes two threats at once.",
+            MoveReason::BlockFork => "Stops the opponent from making two threats at once.",
+            MoveReason::Center => "Takes the center.",
+            MoveReason::OppositeCorner => "Takes the corner opposite the opponent's.",
+            MoveReason::EmptyCorner => "Takes an empty corner.",
+            MoveReason::EmptySide => "Takes an empty side.",
+        }
+    }
+}
+
+impl fmt::Display for MoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MoveReason::Win => "win",
+            MoveReason::Block => "block",
+            MoveReason::Fork => "fork",
+            MoveReason::BlockFork => "block_fork",
+            MoveReason::Center => "center",
+            MoveReason::OppositeCorner => "opposite_corner",
+            MoveReason::EmptyCorner => "empty_corner",
+            MoveReason::EmptySide => "empty_side",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 // chooses a move based on the perfect strategy detailed at:
 // https://en.wikipedia.org/wiki/Tic-tac-toe#Strategy
 pub fn get_move(player: PlayerKind, board: &Board) -> (usize, usize) {
+    explain_move(player, board).0
+}
+
+/// The move `get_move` plays, along with the rule that picked it
+pub fn explain_move(player: PlayerKind, board: &Board) -> ((usize, usize), MoveReason) {
 
     // array of functions to check for a matching move
     // the order is very important to the correctness of the algorithm
-    let functions = 
-        [win, block, fork, block_fork, center, opposite_corner,
-         empty_corner, empty_side];
+    let functions: [(Rule, MoveReason); 8] =
+        [(win, MoveReason::Win), (block, MoveReason::Block), (fork, MoveReason::Fork),
+         (block_fork, MoveReason::BlockFork), (center, MoveReason::Center),
+         (opposite_corner, MoveReason::OppositeCorner), (empty_corner, MoveReason::EmptyCorner),
+         (empty_side, MoveReason::EmptySide)];
 
-    for func in functions.iter() {
+    for (func, reason) in functions.iter() {
         if let Some(p) = func(player, board) {
-            return (p.row, p.col);
+            return ((p.row, p.col), *reason);
         }
     }
 
     panic!("No move was found for the bot.");
 }
 
+/// Chooses a move for the current player of `game`. Standard games use the
+/// perfect strategy in `get_move`, which assumes the players alternate from
+/// an empty board, and any other ruleset falls back to expectimax search.
+pub fn get_game_move(game: &Game) -> (usize, usize) {
+    if game.ruleset.is_standard() {
+        get_move(game.current_player, &game.board)
+    } else {
+        expectimax::get_move(game.current_player, &game.board, &game.ruleset)
+    }
+}
+
+/// Chooses the bot's move for the current player of `game` at the game's
+/// difficulty. This is `get_game_move`, except that below `Hard` the bot
+/// sometimes plays a random empty square, using the game's random number
+/// generator.
+pub fn get_bot_move(game: &mut Game) -> (usize, usize) {
+    let blunders = game.difficulty != Difficulty::Hard
+        && game.rng.below(100) < game.difficulty.blunder_percent();
+    if !blunders {
+        return get_game_move(game);
+    }
+
+    let empty: Vec<(usize, usize)> = (0..game.board.len())
+        .flat_map(|row| (0..game.board.len()).map(move |col| (row, col)))
+        .filter(|&(row, col)| game.board[row][col].is_none())
+        .collect();
+    empty[game.rng.below(empty.len())]
+}
+
 fn opposite_player(player: PlayerKind) -> PlayerKind {
     use PlayerKind::*;
     match player {
@@ -84,25 +174,25 @@ fn win(player: PlayerKind, board: &Board) -> Option<Position> {
     for i in 0..board.len() {
         // check horizontal
         position = check_two_in_a_row(player, board, i, 0, 0, 1);
-        if let Some(_) = position {
+        if position.is_some() {
             return position;
         }
 
         // check vertical
         position = check_two_in_a_row(player, board, 0, i, 1, 0);
-        if let Some(_) = position {
+        if position.is_some() {
             return position;
         }
     }
 
     // check diagonals
     position = check_two_in_a_row(player, board, 0, 0, 1, 1);
-    if let Some(_) = position {
+    if position.is_some() {
         return position;
     }
 
     position = check_two_in_a_row(player, board, 0, 2, 1, -1);
-    if let Some(_) = position {
+    if position.is_some() {
         return position;
     }
     None
@@ -112,7 +202,7 @@ fn win(player: PlayerKind, board: &Board) -> Option<Position> {
 /// `player`'s opponent
 fn block(player: PlayerKind, board: &Board) -> Option<Position> {
     // returning win() for the opposite player
-    return win(opposite_player(player), board);
+    win(opposite_player(player), board)
 }
 
 /// Higher order function that tests how many valid moves exist for
@@ -120,19 +210,19 @@ fn block(player: PlayerKind, board: &Board) -> Option<Position> {
 /// `fork` and `block_fork` functions.
 fn valid_move_count<F>(player: PlayerKind, board: &Board, func: F) -> u32
                     where F: Fn(PlayerKind, &Board) -> Option<Position>  {
-    let mut board_copy = board.clone();
+    let mut board_copy = *board;
     let mut move_position = func(player, &board_copy);
 
     match move_position {
-        None => return 0,
+        None => 0,
         Some(p) => {
             // place the opposite player at move_position, and check
             // if another valid move exists
             board_copy[p.row][p.col] = Some(opposite_player(player));
             move_position = func(player, &board_copy);
             match move_position {
-                None => return 1,
-                Some(_) => return 2,
+                None => 1,
+                Some(_) => 2,
             }
         }
     }
@@ -142,11 +232,11 @@ fn valid_move_count<F>(player: PlayerKind, board: &Board, func: F) -> u32
 fn fork(player: PlayerKind, board: &Board) -> Option<Position> {
     for i in 0..board.len() {
         for j in 0..board.len() {
-            if let None = board[i][j] {
-                let mut board_copy = board.clone();
+            if board[i][j].is_none() {
+                let mut board_copy = *board;
                 board_copy[i][j] = Some(player);
                 if valid_move_count(player, &board_copy, win) == 2 {
-                    return Some(Position {row: i as usize, col: j as usize});
+                    return Some(Position {row: i, col: j});
                 }
             }
         }
@@ -170,7 +260,10 @@ fn block_double_fork(player: PlayerKind, board: &Board) -> Option<Position> {
     // create two in a row, unless blocking it causes the opponent to fork
     for i in 0..board.len() {
         for j in 0..board.len() {
-            let mut board_copy = board.clone();
+            if board[i][j].is_some() {
+                continue;
+            }
+            let mut board_copy = *board;
             board_copy[i][j] = Some(player);
             // if playing in this square results in a possible win
             if valid_move_count(player, &board_copy, win) > 0 {
@@ -189,7 +282,7 @@ fn block_double_fork(player: PlayerKind, board: &Board) -> Option<Position> {
 }
 
 fn center(_player: PlayerKind, board: &Board) -> Option<Position> {
-    if let None = board[1][1] {
+    if board[1][1].is_none() {
         return Some(Position {row: 1, col: 1});
     }
     None
@@ -201,7 +294,7 @@ fn opposite_corner(player: PlayerKind, board: &Board) -> Option<Position> {
 
     for corner in corners.iter() {
         if board[corner[0]][corner[1]] == Some(opposite_player) &&
-           board[2 - corner[0]][2 - corner[1]] == None {
+           board[2 - corner[0]][2 - corner[1]].is_none() {
                return Some(Position {row: 2 - corner[0], col: 2 - corner[1]});
         }
     }
@@ -212,7 +305,7 @@ fn empty_corner(_player: PlayerKind, board: &Board) -> Option<Position> {
     let corners = [[0, 0], [2, 0], [2, 2], [0, 2]];
 
     for corner in corners.iter() {
-        if let None = board[corner[0]][corner[1]] {
+        if board[corner[0]][corner[1]].is_none() {
             return Some(Position {row: corner[0], col: corner[1]});
         }
     }
@@ -223,7 +316,7 @@ fn empty_side(_player: PlayerKind, board: &Board) -> Option<Position> {
     let sides = [[1, 0], [2, 1], [1, 2], [0, 1]];
 
     for side in sides.iter() {
-        if let None = board[side[0]][side[1]] {
+        if board[side[0]][side[1]].is_none() {
             return Some(Position {row: side[0], col: side[1]});
         }
     }
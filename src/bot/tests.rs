@@ -12,10 +12,10 @@ static P_O: Option<PlayerKind> = Some(PlayerO);
 /// * `name` - the identifier of the test function being made
 /// * `func` - the name of the function being tested
 /// * `p` - a variable of type char, indicating the player we are 
-/// passing to the check function
+///   passing to the check function
 /// * `board` - a variable of type Board, used as the board's state
 /// * `expected` - a variable of type Position, the expected 
-/// return value of `func`
+///   return value of `func`
 macro_rules! board_test_some {
     ($name:ident, $func:ident, $p:expr, $board:expr, $expected:expr) => {
         #[test]
@@ -213,7 +213,7 @@ fn brute_force_helper(current_player: PlayerKind, bot_player: PlayerKind,
         if opposite_player(current_player) != bot_player {
             failed_games.insert(GameState {
                 last_player: opposite_player(current_player),
-                board: board,
+                board,
             });
         }
         return;
@@ -221,7 +221,7 @@ fn brute_force_helper(current_player: PlayerKind, bot_player: PlayerKind,
 
     // if it's the bot's turn use it's function to make a move
     if current_player == bot_player {
-        let (row, col) = get_move(current_player, &mut board);
+        let (row, col) = get_move(current_player, &board);
         board[row][col] = Some(current_player);
         brute_force_helper(opposite_player(current_player), bot_player, 
                            board, failed_games);
@@ -229,8 +229,8 @@ fn brute_force_helper(current_player: PlayerKind, bot_player: PlayerKind,
     } else {
         for i in 0..board.len() {
             for j in 0..board[0].len() {
-                if let None = board[i][j] {
-                    let mut board_copy = board.clone();
+                if board[i][j].is_none() {
+                    let mut board_copy = board;
                     board_copy[i][j] = Some(current_player);
                     brute_force_helper(opposite_player(current_player), bot_player, 
                                        board_copy, failed_games);
@@ -254,3 +254,177 @@ fn brute_force_everything() {
     brute_force_helper(PlayerX, PlayerO, [[None; 3]; 3], &mut failed_games);
     assert!(failed_games.is_empty(), "Failed game states were:\n {:?}", failed_games);
 }
+
+#[test]
+fn expectimax_takes_win_test() {
+    let board: Board =
+        [[P_X, P_O, EMP],
+         [EMP, P_X, P_O],
+         [EMP, EMP, EMP]];
+    let ruleset = Ruleset {
+        turn_order: TurnOrder::Random,
+        handicap: Handicap::None,
+    };
+    assert_eq!(expectimax::get_move(PlayerX, &board, &ruleset), (2, 2));
+}
+
+#[test]
+fn expectimax_double_move_wins_test() {
+    // X moves twice on their first turn, so taking a corner next to the
+    // handicapped first stone sets up an unstoppable pair of threats
+    let board: Board =
+        [[EMP, EMP, EMP],
+         [EMP, P_X, EMP],
+         [EMP, EMP, EMP]];
+    let ruleset = Ruleset {
+        turn_order: TurnOrder::Alternating,
+        handicap: Handicap::DoubleFirstMove(PlayerX),
+    };
+
+    let mut game = Game::with_ruleset(ruleset);
+    game.make_move(1, 1);
+    assert_eq!(game.board, board);
+
+    // O can only block one of X's lines after the second stone
+    while let GameStatus::InProgress = game.status {
+        let (row, col) = get_game_move(&game);
+        game.make_move(row, col);
+    }
+    assert_eq!(game.status.winner(), Some(PlayerX));
+}
+
+/// Plays every reply against the expectimax bot with `ruleset`, and checks
+/// that the bot never loses from an empty board
+fn expectimax_never_loses_helper(game: &Game, bot_player: PlayerKind) {
+    match game.status {
+        GameStatus::Win(player, _) => {
+            assert_eq!(player, bot_player, "Bot lost on {:?}", game.board);
+            return;
+        },
+        GameStatus::Tie => return,
+        GameStatus::InProgress => (),
+    }
+
+    let moves = if game.current_player == bot_player {
+        vec![expectimax::get_move(bot_player, &game.board, &game.ruleset)]
+    } else {
+        (0..9).map(|i| (i / 3, i % 3))
+            .filter(|&(row, col)| game.board[row][col].is_none())
+            .collect()
+    };
+
+    for (row, col) in moves {
+        let mut next = Game::with_ruleset(game.ruleset.clone());
+        next.board = game.board;
+        next.current_player = game.current_player;
+        next.make_move(row, col);
+        expectimax_never_loses_helper(&next, bot_player);
+    }
+}
+
+#[test]
+fn expectimax_never_loses_standard_game_test() {
+    let game = Game::new();
+    expectimax_never_loses_helper(&game, PlayerX);
+    expectimax_never_loses_helper(&game, PlayerO);
+}
+
+#[test]
+fn hard_bot_never_blunders_test() {
+    // X can win on the top row, and the hard bot always takes it
+    let mut game = GameConfig::new().human(PlayerX, false).build();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+        game.make_move(row, col);
+    }
+    for seed in 1..50 {
+        game.rng = Rng::from_seed(seed);
+        assert_eq!(get_bot_move(&mut game), (0, 2));
+    }
+}
+
+#[test]
+fn easy_bot_blunders_test() {
+    let mut game = GameConfig::new().human(PlayerX, false).difficulty(Difficulty::Easy).build();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+        game.make_move(row, col);
+    }
+
+    // the easy bot still only plays empty squares, but misses the win
+    // some of the time
+    let mut missed_wins = 0;
+    for seed in 1..50 {
+        game.rng = Rng::from_seed(seed);
+        let (row, col) = get_bot_move(&mut game);
+        assert!(game.board[row][col].is_none());
+        if (row, col) != (0, 2) {
+            missed_wins += 1;
+        }
+    }
+    assert!(missed_wins > 0);
+}
+
+/// Checks the strategy's move in every position reachable from `board`
+/// with `player` to move, perfect play or not
+fn plays_empty_square_helper(player: PlayerKind, board: Board, seen: &mut HashSet<Board>) {
+    if has_won(PlayerX, &board) || has_won(PlayerO, &board) || is_full(&board) || !seen.insert(board) {
+        return;
+    }
+    let (row, col) = get_move(player, &board);
+    assert!(board[row][col].is_none(), "{} played a taken square on {}", player.to_char(), board_string(&board));
+
+    for i in 0..board.len() {
+        for j in 0..board.len() {
+            if board[i][j].is_none() {
+                let mut board_copy = board;
+                board_copy[i][j] = Some(player);
+                plays_empty_square_helper(opposite_player(player), board_copy, seen);
+            }
+        }
+    }
+}
+
+#[test]
+fn plays_empty_square_everywhere_test() {
+    // the easy and medium bots' blunders can lead anywhere, so the strategy
+    // can't rely on having played perfectly so far
+    plays_empty_square_helper(PlayerX, [[None; BOARD_SIZE]; BOARD_SIZE], &mut HashSet::new());
+}
+
+#[test]
+fn explain_move_test() {
+    let board: Board =
+        [[P_X, P_X, EMP],
+         [P_O, P_O, EMP],
+         [EMP, EMP, EMP]];
+    assert_eq!(explain_move(PlayerX, &board), ((0, 2), MoveReason::Win));
+
+    let board: Board =
+        [[P_X, P_X, EMP],
+         [EMP, P_O, EMP],
+         [EMP, EMP, EMP]];
+    assert_eq!(explain_move(PlayerO, &board), ((0, 2), MoveReason::Block));
+
+    let board = [[EMP; 3]; 3];
+    assert_eq!(explain_move(PlayerX, &board), ((1, 1), MoveReason::Center));
+    assert_eq!(MoveReason::BlockFork.to_string(), "block_fork");
+}
+
+#[test]
+fn evaluate_test() {
+    let ruleset = Ruleset::default();
+    // a win now, with four squares left, against a draw from the start
+    let board: Board =
+        [[P_X, P_X, EMP],
+         [P_O, P_O, EMP],
+         [EMP, EMP, EMP]];
+    assert_eq!(expectimax::evaluate(PlayerX, &board, &ruleset), 5.0);
+    assert_eq!(expectimax::evaluate(PlayerO, &board, &ruleset), 5.0);
+    assert_eq!(expectimax::evaluate(PlayerX, &[[EMP; 3]; 3], &ruleset), 0.0);
+
+    // O failed to take the center or a corner, so X wins
+    let board: Board =
+        [[P_X, P_O, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+    assert!(expectimax::evaluate(PlayerX, &board, &ruleset) > 0.0);
+}
@@ -0,0 +1,127 @@
+use super::{parse_player_arg, Difficulty, Game, PlayerKind, Ruleset};
+
+/// Builds a `Game` with a choice of who moves first and which sides are
+/// played by humans and bots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    pub first_player: PlayerKind,
+    pub x_is_human: bool,
+    pub o_is_human: bool,
+    /// Swap who moves first every time the game is reset
+    pub alternate_starts: bool,
+    pub ruleset: Ruleset,
+    pub difficulty: Difficulty,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameConfig {
+    /// The classic setup: a human X against a bot O, with X moving first
+    pub fn new() -> GameConfig {
+        GameConfig {
+            first_player: PlayerKind::PlayerX,
+            x_is_human: true,
+            o_is_human: false,
+            alternate_starts: false,
+            ruleset: Ruleset::default(),
+            difficulty: Difficulty::default(),
+        }
+    }
+
+    pub fn first_player(mut self, player: PlayerKind) -> GameConfig {
+        self.first_player = player;
+        self
+    }
+
+    /// A single human plays `player` against the bot
+    pub fn human_side(self, player: PlayerKind) -> GameConfig {
+        self.human(player, true).human(player.opposite(), false)
+    }
+
+    /// Sets whether `player` is played by a human or by the bot
+    pub fn human(mut self, player: PlayerKind, is_human: bool) -> GameConfig {
+        match player {
+            PlayerKind::PlayerX => self.x_is_human = is_human,
+            PlayerKind::PlayerO => self.o_is_human = is_human,
+        }
+        self
+    }
+
+    pub fn alternate_starts(mut self, alternate: bool) -> GameConfig {
+        self.alternate_starts = alternate;
+        self
+    }
+
+    pub fn ruleset(mut self, ruleset: Ruleset) -> GameConfig {
+        self.ruleset = ruleset;
+        self
+    }
+
+    pub fn difficulty(mut self, difficulty: Difficulty) -> GameConfig {
+        self.difficulty = difficulty;
+        self
+    }
+
+    pub fn build(self) -> Game {
+        Game::from_config(self)
+    }
+
+    pub fn is_human(&self, player: PlayerKind) -> bool {
+        match player {
+            PlayerKind::PlayerX => self.x_is_human,
+            PlayerKind::PlayerO => self.o_is_human,
+        }
+    }
+
+    /// Parses a config from command line arguments, ignoring any arguments
+    /// it doesn't recognise. Along with the rule options of
+    /// `Ruleset::from_args` it takes:
+    ///
+    /// * `--first <X|O>` - the player who moves first
+    /// * `--play-as <X|O|both|none>` - the side played by a human, where
+    ///   `both` is two humans and `none` has the bot play itself
+    /// * `--alternate-starts` - swap who moves first after every game
+    /// * `--difficulty <easy|medium|hard>` - how well the bot plays
+    pub fn from_args(args: &[String]) -> Result<GameConfig, String> {
+        let mut config = GameConfig::new().ruleset(Ruleset::from_args(args)?);
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--first" => {
+                    i += 1;
+                    config = config.first_player(parse_player_arg(args.get(i))?);
+                },
+                "--play-as" => {
+                    i += 1;
+                    config = match args.get(i).map(|s| s.to_lowercase()).as_deref() {
+                        Some("both") => config.human(PlayerKind::PlayerX, true)
+                                              .human(PlayerKind::PlayerO, true),
+                        Some("none") => config.human(PlayerKind::PlayerX, false)
+                                              .human(PlayerKind::PlayerO, false),
+                        _ => config.human_side(parse_player_arg(args.get(i))
+                            .map_err(|_| "Expected a side, X, O, both or none".to_string())?),
+                    };
+                },
+                "--alternate-starts" => config = config.alternate_starts(true),
+                "--difficulty" => {
+                    i += 1;
+                    let difficulty = match args.get(i) {
+                        Some(arg) => arg.parse()?,
+                        None => return Err("--difficulty needs a level, easy, medium or hard".to_string()),
+                    };
+                    config = config.difficulty(difficulty);
+                },
+                _ => (),
+            }
+            i += 1;
+        }
+
+        Ok(config)
+    }
+}
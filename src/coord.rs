@@ -0,0 +1,98 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::BOARD_SIZE;
+
+/// A square on the board. `row` counts down from the top and `col` counts
+/// across from the left, both from zero, matching `board[row][col]`.
+///
+/// Written out, a square is named by its file and rank: the file is a letter
+/// for the column, `a` on the left, and the rank is a number for the row, `1`
+/// at the top. So `a1` is the top left corner and `c3` the bottom right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, col: usize) -> Coord {
+        Coord { row, col }
+    }
+
+    /// The letter naming the column, `a` for the leftmost
+    pub fn file(&self) -> char {
+        file_char(self.col)
+    }
+
+    /// The number naming the row, 1 for the top
+    pub fn rank(&self) -> usize {
+        self.row + 1
+    }
+}
+
+/// The letter labelling column `col`, counting from zero
+pub fn file_char(col: usize) -> char {
+    (b'a' + col as u8) as char
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Coord {
+        Coord::new(row, col)
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> (usize, usize) {
+        (coord.row, coord.col)
+    }
+}
+
+/// Formats the square by file and rank, e.g. `b2`
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+/// Parses a square either by file and rank, `b2`, or as a row and column
+/// number counted from 1, `2,2` or `2 2`
+impl FromStr for Coord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Coord, String> {
+        let error = || format!(
+            "\"{}\" isn't a square, try a file and rank like b2 or a row and column like 2,2",
+            s.trim()
+        );
+        let in_range = |n: usize| (1..=BOARD_SIZE).contains(&n);
+
+        let parts: Vec<&str> = s.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        match parts.as_slice() {
+            [row, col] => match (row.parse::<usize>(), col.parse::<usize>()) {
+                (Ok(row), Ok(col)) if in_range(row) && in_range(col) => Ok(Coord::new(row - 1, col - 1)),
+                _ => Err(error()),
+            },
+            [square] => {
+                let mut chars = square.chars();
+                let file = chars.next().map(|c| c.to_ascii_lowercase());
+                match (file, chars.as_str().parse::<usize>()) {
+                    (Some(file @ 'a'..='z'), Ok(rank)) if in_range(rank) => {
+                        let col = file as usize - 'a' as usize;
+                        if col < BOARD_SIZE {
+                            Ok(Coord::new(rank - 1, col))
+                        } else {
+                            Err(error())
+                        }
+                    },
+                    _ => Err(error()),
+                }
+            },
+            _ => Err(error()),
+        }
+    }
+}
@@ -1,49 +1,466 @@
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[cfg(test)]
 mod tests;
 pub mod bot;
+pub mod mnk;
+pub mod net;
+pub mod notakto;
+pub mod order_chaos;
+pub mod records;
+pub mod render;
+pub mod replay;
+#[cfg(feature = "serde")]
+pub mod save;
+pub mod scheduler;
+pub mod settings;
+mod config;
+mod coord;
+mod rng;
+mod series;
+
+pub use config::GameConfig;
+pub use coord::{file_char, Coord};
+pub use rng::Rng;
+pub use series::{Match, MatchStatus};
 
 pub const EMPTY_SQUARE: char = '*';
 pub const BOARD_SIZE: usize = 3;
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum GameStatus {
     InProgress,
     Tie,
-    Win(PlayerKind),
+    /// The winner along with every line they completed, which is more than
+    /// one when the last move finished two lines at once, and none when the
+    /// other player resigned
+    Win(PlayerKind, Vec<Line>),
+}
+
+impl GameStatus {
+    pub fn winner(&self) -> Option<PlayerKind> {
+        match self {
+            GameStatus::Win(player, _) => Some(*player),
+            _ => None,
+        }
+    }
+}
+
+/// The way a line runs across the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LineDirection {
+    /// Along a row
+    Horizontal,
+    /// Down a column
+    Vertical,
+    /// From the top left corner to the bottom right
+    Diagonal,
+    /// From the top right corner to the bottom left
+    AntiDiagonal,
+}
+
+/// A line of squares across the board, listed from one end to the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line {
+    pub direction: LineDirection,
+    pub cells: [Coord; BOARD_SIZE],
+}
+
+impl Line {
+    /// Every row, column and diagonal of the board
+    pub fn all() -> Vec<Line> {
+        let line = |direction, cell: &dyn Fn(usize) -> Coord| Line {
+            direction,
+            cells: std::array::from_fn(cell),
+        };
+
+        let mut lines = Vec::new();
+        for i in 0..BOARD_SIZE {
+            lines.push(line(LineDirection::Horizontal, &|j| Coord::new(i, j)));
+            lines.push(line(LineDirection::Vertical, &|j| Coord::new(j, i)));
+        }
+        lines.push(line(LineDirection::Diagonal, &|j| Coord::new(j, j)));
+        lines.push(line(LineDirection::AntiDiagonal, &|j| Coord::new(j, BOARD_SIZE - 1 - j)));
+        lines
+    }
+
+    pub fn start(&self) -> Coord {
+        self.cells[0]
+    }
+
+    pub fn end(&self) -> Coord {
+        self.cells[BOARD_SIZE - 1]
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.cells.contains(&coord)
+    }
+}
+
+/// How well the bot plays. Below `Hard` it sometimes plays a random square
+/// instead of its best move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    #[default]
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    /// The chance out of 100 that the bot plays a random square
+    pub fn blunder_percent(&self) -> usize {
+        match self {
+            Difficulty::Easy => 70,
+            Difficulty::Medium => 30,
+            Difficulty::Hard => 0,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        Difficulty::ALL.iter()
+            .find(|difficulty| difficulty.to_string() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("Expected a difficulty, easy, medium or hard, found \"{}\"", s))
+    }
+}
+
+/// Decides who moves after each turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TurnOrder {
+    /// Players take turns, as in standard Tic-Tac-Toe
+    Alternating,
+    /// A coin flip decides who moves next each turn (Random-Turn Tic-Tac-Toe)
+    Random,
+}
+
+/// Gives one side an advantage at the start of the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Handicap {
+    None,
+    /// Stones of `PlayerKind` placed at (row, col) before the first move
+    Stones(PlayerKind, Vec<(usize, usize)>),
+    /// `PlayerKind` places two stones on their first turn
+    DoubleFirstMove(PlayerKind),
+}
+
+/// `alternating` or `random`
+impl fmt::Display for TurnOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TurnOrder::Alternating => write!(f, "alternating"),
+            TurnOrder::Random => write!(f, "random"),
+        }
+    }
+}
+
+impl std::str::FromStr for TurnOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<TurnOrder, String> {
+        match s {
+            "alternating" => Ok(TurnOrder::Alternating),
+            "random" => Ok(TurnOrder::Random),
+            _ => Err(format!("Expected turns, alternating or random, found \"{}\"", s)),
+        }
+    }
+}
+
+/// `none`, `double:X` or `stones:X:a1,c3`
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Handicap::None => write!(f, "none"),
+            Handicap::DoubleFirstMove(player) => write!(f, "double:{}", player.to_char()),
+            Handicap::Stones(player, stones) => {
+                let squares: Vec<String> = stones.iter().map(|&(row, col)| Coord::new(row, col).to_string()).collect();
+                write!(f, "stones:{}:{}", player.to_char(), squares.join(","))
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for Handicap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Handicap, String> {
+        let error = || format!("Expected a handicap, none, double:<X|O> or stones:<X|O>:<squares>, found \"{}\"", s);
+        let player = |s: &str| match s {
+            "X" => Ok(PlayerKind::PlayerX),
+            "O" => Ok(PlayerKind::PlayerO),
+            _ => Err(error()),
+        };
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts.as_slice() {
+            ["none"] => Ok(Handicap::None),
+            ["double", side] => player(side).map(Handicap::DoubleFirstMove),
+            ["stones", side, squares] => {
+                let player = player(side)?;
+                let stones = squares.split(',')
+                    .map(|square| square.parse::<Coord>().map(<(usize, usize)>::from))
+                    .collect::<Result<_, _>>()?;
+                Ok(Handicap::Stones(player, stones))
+            },
+            _ => Err(error()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ruleset {
+    pub turn_order: TurnOrder,
+    pub handicap: Handicap,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset {
+            turn_order: TurnOrder::Alternating,
+            handicap: Handicap::None,
+        }
+    }
 }
 
+impl Ruleset {
+    /// Returns true for the standard rules, which the perfect strategy in
+    /// `bot::get_move` is written for
+    pub fn is_standard(&self) -> bool {
+        *self == Ruleset::default()
+    }
+
+    /// Returns who may move after `mover` has played on `board`, along with
+    /// the chance of each player getting the turn.
+    pub fn next_player_odds(&self, mover: PlayerKind, board: &Board) -> Vec<(PlayerKind, f64)> {
+        use PlayerKind::*;
+
+        if let TurnOrder::Random = self.turn_order {
+            return vec![(PlayerX, 0.5), (PlayerO, 0.5)];
+        }
+
+        if let Handicap::DoubleFirstMove(player) = self.handicap {
+            // the player's only stone on the board is from their first turn
+            if player == mover && count_stones(player, board) == 1 {
+                return vec![(mover, 1.0)];
+            }
+        }
+
+        vec![(mover.opposite(), 1.0)]
+    }
+
+    /// Parses rule options from command line arguments, ignoring any
+    /// arguments that aren't rule options:
+    ///
+    /// * `--random-turn` - a coin flip decides who moves each turn
+    /// * `--double-first-move <X|O>` - the player moves twice on their first turn
+    /// * `--stones <X|O> <row,col>...` - the player starts with stones placed
+    ///   at the given squares, as row,col counted from 1,1 in the top left or
+    ///   by file and rank like `b2`
+    pub fn from_args(args: &[String]) -> Result<Ruleset, String> {
+        let mut ruleset = Ruleset::default();
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--random-turn" => ruleset.turn_order = TurnOrder::Random,
+                "--double-first-move" => {
+                    i += 1;
+                    let player = parse_player_arg(args.get(i))?;
+                    ruleset.handicap = Handicap::DoubleFirstMove(player);
+                },
+                "--stones" => {
+                    i += 1;
+                    let player = parse_player_arg(args.get(i))?;
+
+                    let mut stones = Vec::new();
+                    while let Some(arg) = args.get(i + 1) {
+                        if arg.starts_with("--") {
+                            break;
+                        }
+                        stones.push(parse_square_arg(arg)?);
+                        i += 1;
+                    }
+
+                    if stones.is_empty() {
+                        return Err("--stones needs at least one square".to_string());
+                    }
+                    check_stones(player, &stones)?;
+                    ruleset.handicap = Handicap::Stones(player, stones);
+                },
+                _ => (),
+            }
+            i += 1;
+        }
+
+        Ok(ruleset)
+    }
+}
+
+pub(crate) fn parse_player_arg(arg: Option<&String>) -> Result<PlayerKind, String> {
+    match arg.map(|s| s.to_uppercase()).as_deref() {
+        Some("X") => Ok(PlayerKind::PlayerX),
+        Some("O") => Ok(PlayerKind::PlayerO),
+        _ => Err("Expected a player, X or O".to_string()),
+    }
+}
+
+/// Checks that handicap stones are each on a different square and leave a
+/// game to play, without three in a row already
+fn check_stones(player: PlayerKind, stones: &[(usize, usize)]) -> Result<(), String> {
+    let mut board = [[None; BOARD_SIZE]; BOARD_SIZE];
+    for &(row, col) in stones {
+        if board[row][col].is_some() {
+            return Err(format!("--stones has {} more than once", Coord::new(row, col)));
+        }
+        board[row][col] = Some(player);
+    }
+    match board_status(&board) {
+        GameStatus::InProgress => Ok(()),
+        _ => Err("--stones can't already make three in a row".to_string()),
+    }
+}
+
+// expects a square as "row,col" in range [1, 3] or by file and rank, e.g.
+// "b2", returns as (row, col) in range [0, 2]
+fn parse_square_arg(arg: &str) -> Result<(usize, usize), String> {
+    arg.parse::<Coord>().map(|coord| coord.into())
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub player1: Player,
     pub player2: Player,
     pub current_player: PlayerKind,
     pub board: Board,
     pub status: GameStatus,
+    pub ruleset: Ruleset,
+    pub difficulty: Difficulty,
+    pub rng: Rng,
+    /// The player who moves first in the current game
+    pub starting_player: PlayerKind,
+    /// Swap `starting_player` every time the game is reset
+    pub alternate_starts: bool,
+    /// Every move of the current game in order, as (row, col, player)
+    pub history: Vec<(usize, usize, PlayerKind)>,
+    /// Identifies the position. It changes with every move, undo and reset
+    /// and is never reused, so work started for one position, like a bot's
+    /// move, can tell when it has gone stale. A loaded game gets a new one.
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_turn_id"))]
+    pub turn_id: u64,
+}
+
+/// Hands out turn ids, never the same one twice in a run
+pub(crate) fn next_turn_id() -> u64 {
+    static NEXT_TURN_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_TURN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
     pub fn new() -> Game {
+        Game::from_config(GameConfig::new())
+    }
+
+    pub fn with_ruleset(ruleset: Ruleset) -> Game {
+        Game::from_config(GameConfig::new().ruleset(ruleset))
+    }
+
+    pub fn from_config(config: GameConfig) -> Game {
         use PlayerKind::*;
-        Game {
+        let mut game = Game {
             player1: Player {
                 kind: PlayerX,
-                is_human: true,
+                is_human: config.x_is_human,
             },
             player2: Player {
                 kind: PlayerO,
-                is_human: false,
+                is_human: config.o_is_human,
             },
-            current_player: PlayerX,
+            current_player: config.first_player,
             board: [[None; BOARD_SIZE]; BOARD_SIZE],
             status: GameStatus::InProgress,
-        }
+            ruleset: config.ruleset,
+            difficulty: config.difficulty,
+            rng: Rng::new(),
+            starting_player: config.first_player,
+            alternate_starts: config.alternate_starts,
+            history: Vec::new(),
+            turn_id: next_turn_id(),
+        };
+        game.start();
+        game
     }
 
+    /// Clears the board for a new game, handing the first move to the other
+    /// player if starts alternate
     pub fn reset(&mut self) {
+        if self.alternate_starts {
+            self.starting_player = self.starting_player.opposite();
+        }
+        self.start();
+    }
+
+    fn start(&mut self) {
         self.board = [[None; BOARD_SIZE]; BOARD_SIZE];
         self.status = GameStatus::InProgress;
-        self.current_player = PlayerKind::PlayerX;
+        self.current_player = self.starting_player;
+        self.history.clear();
+        self.turn_id = next_turn_id();
+
+        if let Handicap::Stones(player, ref stones) = self.ruleset.handicap {
+            for &(row, col) in stones.iter() {
+                self.board[row][col] = Some(player);
+            }
+            // stones `from_args` would refuse can still be set up by hand
+            self.status = board_status(&self.board);
+        }
+
+        if let TurnOrder::Random = self.ruleset.turn_order {
+            self.current_player = self.flip_for_player();
+        }
+    }
+
+    fn flip_for_player(&mut self) -> PlayerKind {
+        if self.rng.coin_flip() {
+            PlayerKind::PlayerX
+        } else {
+            PlayerKind::PlayerO
+        }
     }
 
     // @Hack this doesn't smell very good
@@ -56,32 +473,90 @@ impl Game {
             return self.player2.is_human;
         }
 
-        return false;
+        false
     }
 
-    pub fn make_move(&mut self, row: usize, col: usize) {
-        use PlayerKind::*;
+    /// Plays the current player's piece at `row`, `col`. Returns false, and
+    /// leaves the game as it was, if the square is already taken or the game
+    /// is over.
+    pub fn make_move(&mut self, row: usize, col: usize) -> bool {
+        if !self.place(row, col, self.current_player) {
+            return false;
+        }
 
-        self.board[row][col] = Some(self.current_player);
+        let odds = self.ruleset.next_player_odds(self.current_player, &self.board);
+        self.current_player = if odds.len() > 1 {
+            self.flip_for_player()
+        } else {
+            odds[0].0
+        };
+        true
+    }
 
-        if has_won(self.current_player, &self.board) {
-            self.status = GameStatus::Win(self.current_player);
+    /// Plays `player`'s piece at `row`, `col` without deciding who moves
+    /// next, for replaying a game where that's already known. Returns false,
+    /// and leaves the game as it was, if the square is already taken or the
+    /// game is over.
+    pub fn place(&mut self, row: usize, col: usize, player: PlayerKind) -> bool {
+        if self.status != GameStatus::InProgress || self.board[row][col].is_some() {
+            return false;
         }
+        self.current_player = player;
+        self.board[row][col] = Some(player);
+        self.history.push((row, col, player));
+        self.turn_id = next_turn_id();
 
-        if is_full(&self.board) {
+        let lines = winning_lines(player, &self.board);
+        if !lines.is_empty() {
+            self.status = GameStatus::Win(player, lines);
+        } else if is_full(&self.board) {
             self.status = GameStatus::Tie;
         }
+        true
+    }
 
-        self.current_player = match self.current_player {
-            PlayerX => PlayerO,
-            PlayerO => PlayerX,
-        };
+    /// Ends the game with a win for the other player
+    pub fn resign(&mut self, player: PlayerKind) {
+        if self.status == GameStatus::InProgress {
+            self.status = GameStatus::Win(player.opposite(), Vec::new());
+            self.turn_id = next_turn_id();
+        }
+    }
+
+    /// Sets up `board` with `player` to move, as if the game had reached it
+    /// with no moves to undo. The game is over if someone has three in a
+    /// row or the board is full.
+    pub fn set_position(&mut self, board: Board, player: PlayerKind) {
+        self.board = board;
+        self.current_player = player;
+        self.history.clear();
+        self.turn_id = next_turn_id();
+
+        self.status = board_status(&board);
+    }
+
+    /// Takes back the last move, giving the turn back to the player who
+    /// made it. Returns false if no moves have been made.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((row, col, player)) => {
+                self.board[row][col] = None;
+                self.current_player = player;
+                self.status = GameStatus::InProgress;
+                self.turn_id = next_turn_id();
+                true
+            },
+            None => false,
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerKind {
+    #[cfg_attr(feature = "serde", serde(rename = "X"))]
     PlayerX,
+    #[cfg_attr(feature = "serde", serde(rename = "O"))]
     PlayerO,
 }
 
@@ -93,9 +568,18 @@ impl PlayerKind {
             PlayerO => 'O',
         }
     }
+
+    pub fn opposite(&self) -> PlayerKind {
+        use PlayerKind::*;
+        match self {
+            PlayerX => PlayerO,
+            PlayerO => PlayerX,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub kind: PlayerKind,
     pub is_human: bool,
@@ -109,6 +593,7 @@ impl fmt::Display for Player {
 
 pub type Board = [[Option<PlayerKind>; BOARD_SIZE]; BOARD_SIZE];
 
+#[allow(clippy::needless_range_loop)]
 pub fn has_won(player: PlayerKind, board: &Board) -> bool {
     let n = board.len();
 
@@ -152,13 +637,80 @@ pub fn has_won(player: PlayerKind, board: &Board) -> bool {
         }
     }
 
-    return diagonal_1_count == n || diagonal_2_count == n;
+    diagonal_1_count == n || diagonal_2_count == n
+}
+
+/// Every line `player` has filled, empty if they haven't won
+pub fn winning_lines(player: PlayerKind, board: &Board) -> Vec<Line> {
+    Line::all().into_iter()
+        .filter(|line| line.cells.iter().all(|coord| board[coord.row][coord.col] == Some(player)))
+        .collect()
+}
+
+pub fn count_stones(player: PlayerKind, board: &Board) -> usize {
+    board.iter()
+        .flat_map(|row| row.iter())
+        .filter(|&&square| square == Some(player))
+        .count()
+}
+
+/// The board on one line, rows from the top separated by `/` and empty
+/// squares as `.`, e.g. `X.O/.X./...`
+pub fn board_string(board: &Board) -> String {
+    let rows: Vec<String> = board.iter()
+        .map(|row| row.iter()
+            .map(|square| square.map_or('.', |p| p.to_char()))
+            .collect())
+        .collect();
+    rows.join("/")
+}
+
+/// Parses a board written by `board_string`
+pub fn parse_board_string(s: &str) -> Result<Board, String> {
+    let error = || format!("Expected a board like X.O/.X./..., found \"{}\"", s);
+    let rows: Vec<&str> = s.split('/').collect();
+    if rows.len() != BOARD_SIZE {
+        return Err(error());
+    }
+
+    let mut board = [[None; BOARD_SIZE]; BOARD_SIZE];
+    for (i, row) in rows.iter().enumerate() {
+        let squares: Vec<char> = row.chars().collect();
+        if squares.len() != BOARD_SIZE {
+            return Err(error());
+        }
+        for (j, square) in squares.into_iter().enumerate() {
+            board[i][j] = match square.to_ascii_uppercase() {
+                'X' => Some(PlayerKind::PlayerX),
+                'O' => Some(PlayerKind::PlayerO),
+                '.' => None,
+                _ => return Err(error()),
+            };
+        }
+    }
+    Ok(board)
+}
+
+/// The result a board shows on its own: a win for whoever has three in a
+/// row, a tie once it's full, or a game still being played
+pub fn board_status(board: &Board) -> GameStatus {
+    for &player in &[PlayerKind::PlayerX, PlayerKind::PlayerO] {
+        let lines = winning_lines(player, board);
+        if !lines.is_empty() {
+            return GameStatus::Win(player, lines);
+        }
+    }
+    if is_full(board) {
+        GameStatus::Tie
+    } else {
+        GameStatus::InProgress
+    }
 }
 
 pub fn is_full(board: &Board) -> bool {
     for row in board.iter() {
         for square in row.iter() {
-            if let None = *square {
+            if square.is_none() {
                 return false;
             }
         }
@@ -0,0 +1,242 @@
+use super::{next_turn_id, Coord, Difficulty, PlayerKind, Rng, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+/// The smallest and largest boards that can be played, as squares a side
+pub const MIN_BOARD_SIZE: usize = BOARD_SIZE;
+pub const MAX_BOARD_SIZE: usize = 9;
+
+/// Score given to a board the bot has already won, above any other
+const WIN_SCORE: i64 = i64::MAX;
+
+/// The directions a line can run in, as (row, col) steps: along a row, down
+/// a column, and down either diagonal
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+pub type MnkBoard = Vec<Vec<Option<PlayerKind>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MnkStatus {
+    InProgress,
+    Tie,
+    /// The winner along with the first and last square of the line that
+    /// won
+    Win(PlayerKind, (Coord, Coord)),
+}
+
+/// Tic-Tac-Toe on a square board of any size from 3 to 9, won by the first
+/// player with `win_length` in a row along a row, column or diagonal. The
+/// classic game's rules and bots are written for the 3x3 board, so this is
+/// a game of its own, played with the standard rules.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MnkGame {
+    pub size: usize,
+    pub win_length: usize,
+    pub x_is_human: bool,
+    pub o_is_human: bool,
+    pub difficulty: Difficulty,
+    /// The player who moves first in every game
+    pub starting_player: PlayerKind,
+    pub current_player: PlayerKind,
+    pub board: MnkBoard,
+    pub status: MnkStatus,
+    pub rng: Rng,
+    /// Changes with every move and reset, as `Game::turn_id` does
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_turn_id"))]
+    pub turn_id: u64,
+}
+
+impl MnkGame {
+    /// A game on a `size` by `size` board, won with `win_length` in a row,
+    /// which must fit on it
+    pub fn new(size: usize, win_length: usize) -> MnkGame {
+        assert!((MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size), "The board must be 3 to 9 squares a side.");
+        assert!((MIN_BOARD_SIZE..=size).contains(&win_length), "The win length must be from 3 to the board's size.");
+        MnkGame {
+            size,
+            win_length,
+            x_is_human: true,
+            o_is_human: false,
+            difficulty: Difficulty::default(),
+            starting_player: PlayerKind::PlayerX,
+            current_player: PlayerKind::PlayerX,
+            board: vec![vec![None; size]; size],
+            status: MnkStatus::InProgress,
+            rng: Rng::new(),
+            turn_id: next_turn_id(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.board = vec![vec![None; self.size]; self.size];
+        self.status = MnkStatus::InProgress;
+        self.current_player = self.starting_player;
+        self.turn_id = next_turn_id();
+    }
+
+    pub fn current_player_is_human(&self) -> bool {
+        match self.current_player {
+            PlayerKind::PlayerX => self.x_is_human,
+            PlayerKind::PlayerO => self.o_is_human,
+        }
+    }
+
+    /// Plays the current player's piece at `row`, `col`. Returns false, and
+    /// leaves the game as it was, if the square is off the board or taken,
+    /// or the game is over.
+    pub fn make_move(&mut self, row: usize, col: usize) -> bool {
+        if self.status != MnkStatus::InProgress || row >= self.size || col >= self.size
+            || self.board[row][col].is_some() {
+            return false;
+        }
+        let player = self.current_player;
+        self.board[row][col] = Some(player);
+        self.turn_id = next_turn_id();
+
+        if let Some(ends) = winning_line(&self.board, self.win_length, Coord::new(row, col)) {
+            self.status = MnkStatus::Win(player, ends);
+        } else if is_full(&self.board) {
+            self.status = MnkStatus::Tie;
+        }
+        self.current_player = player.opposite();
+        true
+    }
+}
+
+/// The first and last square of a line of at least `win_length` through
+/// `at`, all held by the player on `at`, if there is one
+pub fn winning_line(board: &MnkBoard, win_length: usize, at: Coord) -> Option<(Coord, Coord)> {
+    let player = board[at.row][at.col]?;
+    let holds = |row: i32, col: i32| {
+        let size = board.len() as i32;
+        (0..size).contains(&row) && (0..size).contains(&col) && board[row as usize][col as usize] == Some(player)
+    };
+
+    for &(d_row, d_col) in DIRECTIONS.iter() {
+        let (row, col) = (at.row as i32, at.col as i32);
+        // how far the player's pieces run from `at` each way
+        let run = |sign: i32| (1..).take_while(|&i| holds(row + sign * i * d_row, col + sign * i * d_col)).count() as i32;
+        let (back, forward) = (run(-1), run(1));
+        if (back + forward + 1) as usize >= win_length {
+            let start = Coord::new((row - back * d_row) as usize, (col - back * d_col) as usize);
+            let end = Coord::new((row + forward * d_row) as usize, (col + forward * d_col) as usize);
+            return Some((start, end));
+        }
+    }
+    None
+}
+
+pub fn is_full(board: &MnkBoard) -> bool {
+    board.iter().all(|row| row.iter().all(|square| square.is_some()))
+}
+
+/// Calls `func` with the squares of every line of `win_length` squares on
+/// a board `size` squares a side, in all four directions
+pub fn for_each_window<F>(size: usize, win_length: usize, mut func: F)
+                      where F: FnMut(&[Coord]) {
+    let (n, len) = (size as i32, win_length as i32);
+    let mut window = Vec::with_capacity(win_length);
+    for &(d_row, d_col) in DIRECTIONS.iter() {
+        for row in 0..n {
+            for col in 0..n {
+                let end_row = row + d_row * (len - 1);
+                let end_col = col + d_col * (len - 1);
+                if end_row < 0 || end_row >= n || end_col < 0 || end_col >= n {
+                    continue;
+                }
+
+                window.clear();
+                window.extend((0..len).map(|k| Coord::new((row + d_row * k) as usize, (col + d_col * k) as usize)));
+                func(&window);
+            }
+        }
+    }
+}
+
+/// Returns how promising `board` is for `player`. Every line of
+/// `win_length` squares holding only one player's pieces can still be
+/// completed, and counts for that player, ten times more for each piece in
+/// it. The other player's lines count a little more against, so the bot
+/// blocks a line as full as one of its own rather than extending its own.
+pub fn evaluate(board: &MnkBoard, win_length: usize, player: PlayerKind) -> i64 {
+    let mut score = 0;
+    for_each_window(board.len(), win_length, |window| {
+        let (mut own, mut other) = (0, 0);
+        for coord in window {
+            match board[coord.row][coord.col] {
+                Some(piece) if piece == player => own += 1,
+                Some(_) => other += 1,
+                None => (),
+            }
+        }
+        if other == 0 && own > 0 {
+            score += 10_i64.pow(own);
+        } else if own == 0 && other > 0 {
+            score -= 10_i64.pow(other) * 2;
+        }
+    });
+    score
+}
+
+/// Chooses a move for `player` by trying every empty square and picking
+/// the one that leaves the best board for them: a win if there is one,
+/// otherwise the best score from `evaluate`. Ties go to the square nearest
+/// the center.
+pub fn get_move(player: PlayerKind, board: &MnkBoard, win_length: usize) -> (usize, usize) {
+    let size = board.len();
+    let center = (size as i32 - 1) as f64 / 2.0;
+    let mut best: Option<((usize, usize), i64, f64)> = None;
+
+    for row in 0..size {
+        for col in 0..size {
+            if board[row][col].is_some() {
+                continue;
+            }
+
+            let mut board_copy = board.clone();
+            board_copy[row][col] = Some(player);
+            let score = if winning_line(&board_copy, win_length, Coord::new(row, col)).is_some() {
+                WIN_SCORE
+            } else {
+                evaluate(&board_copy, win_length, player)
+            };
+            let distance = (row as f64 - center).abs() + (col as f64 - center).abs();
+
+            let is_better = match best {
+                None => true,
+                Some((_, best_score, best_distance)) => {
+                    score > best_score || (score == best_score && distance < best_distance)
+                },
+            };
+            if is_better {
+                best = Some(((row, col), score, distance));
+            }
+        }
+    }
+
+    match best {
+        Some((m, _, _)) => m,
+        None => panic!("No move was found for the bot."),
+    }
+}
+
+/// Chooses the bot's move for the current player of `game` at the game's
+/// difficulty. Below `Hard` it sometimes plays a random empty square
+/// instead, as `bot::get_bot_move` does for the classic game.
+pub fn get_bot_move(game: &mut MnkGame) -> (usize, usize) {
+    let blunders = game.difficulty != Difficulty::Hard
+        && game.rng.below(100) < game.difficulty.blunder_percent();
+    if !blunders {
+        return get_move(game.current_player, &game.board, game.win_length);
+    }
+
+    let empty: Vec<(usize, usize)> = (0..game.size)
+        .flat_map(|row| (0..game.size).map(move |col| (row, col)))
+        .filter(|&(row, col)| game.board[row][col].is_none())
+        .collect();
+    empty[game.rng.below(empty.len())]
+}
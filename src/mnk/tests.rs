@@ -0,0 +1,105 @@
+use super::*;
+
+use PlayerKind::*;
+
+/// A board from rows of `X`, `O` and `.`, top to bottom
+fn board(rows: &[&str]) -> MnkBoard {
+    rows.iter()
+        .map(|row| row.chars()
+            .map(|square| match square {
+                'X' => Some(PlayerX),
+                'O' => Some(PlayerO),
+                _ => None,
+            })
+            .collect())
+        .collect()
+}
+
+#[test]
+fn winning_line_test() {
+    let board = board(&[
+        "X....",
+        ".X..O",
+        "..X.O",
+        "...XO",
+        "....O",
+    ]);
+    assert_eq!(winning_line(&board, 4, Coord::new(2, 2)), Some((Coord::new(0, 0), Coord::new(3, 3))));
+    assert_eq!(winning_line(&board, 4, Coord::new(1, 4)), Some((Coord::new(1, 4), Coord::new(4, 4))));
+    assert_eq!(winning_line(&board, 5, Coord::new(2, 2)), None);
+    assert_eq!(winning_line(&board, 4, Coord::new(0, 1)), None);
+}
+
+#[test]
+fn make_move_test() {
+    let mut game = MnkGame::new(4, 3);
+    game.x_is_human = true;
+    game.o_is_human = true;
+    for &(row, col) in &[(0, 3), (0, 0), (1, 2), (1, 1)] {
+        assert!(game.make_move(row, col));
+    }
+    assert!(!game.make_move(1, 1));
+    assert!(!game.make_move(4, 0));
+    assert_eq!(game.current_player, PlayerX);
+
+    // three along the anti-diagonal wins on a 4x4 board
+    assert!(game.make_move(2, 1));
+    assert_eq!(game.status, MnkStatus::Win(PlayerX, (Coord::new(0, 3), Coord::new(2, 1))));
+    assert!(!game.make_move(3, 3));
+
+    game.reset();
+    assert_eq!(game.board, vec![vec![None; 4]; 4]);
+    assert_eq!(game.status, MnkStatus::InProgress);
+}
+
+#[test]
+fn full_board_is_a_tie_test() {
+    let mut game = MnkGame::new(3, 3);
+    for &(row, col) in &[(0, 0), (1, 1), (2, 2), (0, 1), (2, 1), (2, 0), (0, 2), (1, 2), (1, 0)] {
+        game.make_move(row, col);
+    }
+    assert_eq!(game.status, MnkStatus::Tie);
+}
+
+#[test]
+fn bot_wins_or_blocks_test() {
+    // X can win at once, which comes before blocking O
+    let position = board(&[
+        "XXX..",
+        "OOO..",
+        ".....",
+        ".....",
+        ".....",
+    ]);
+    assert_eq!(get_move(PlayerX, &position, 4), (0, 3));
+
+    // otherwise O's four is blocked, at the end that's left open
+    let position = board(&[
+        "..X..",
+        "..O..",
+        "..O..",
+        "..O..",
+        ".....",
+    ]);
+    assert_eq!(get_move(PlayerX, &position, 4), (4, 2));
+
+    // an empty board is opened in the center
+    assert_eq!(get_move(PlayerO, &board(&[".......", ".......", ".......", ".......", ".......", ".......", "......."]), 5), (3, 3));
+}
+
+#[test]
+fn bot_plays_itself_to_the_end_test() {
+    for &(size, win_length) in &[(3, 3), (4, 3), (5, 4), (7, 5)] {
+        let mut game = MnkGame::new(size, win_length);
+        game.x_is_human = false;
+        game.rng = Rng::from_seed(size as u64);
+        while game.status == MnkStatus::InProgress {
+            let (row, col) = get_bot_move(&mut game);
+            assert!(game.make_move(row, col));
+        }
+        // the bot draws against itself on the classic board
+        if size == 3 {
+            assert_eq!(game.status, MnkStatus::Tie);
+        }
+    }
+}
@@ -0,0 +1,582 @@
+use std::env;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use super::{board_string, parse_board_string, winning_lines, Board, Coord, Game, GameConfig,
+            GameStatus, Handicap, PlayerKind, Ruleset, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// The game as the host sends it in a `STATE` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    pub board: Board,
+    pub current_player: PlayerKind,
+    /// `None` while the game is on, then `Some` of the winner, or of `None`
+    /// for a tie
+    pub result: Option<Option<PlayerKind>>,
+}
+
+impl Snapshot {
+    pub fn of(game: &Game) -> Snapshot {
+        Snapshot {
+            board: game.board,
+            current_player: game.current_player,
+            result: match &game.status {
+                GameStatus::InProgress => None,
+                GameStatus::Tie => Some(None),
+                GameStatus::Win(player, _) => Some(Some(*player)),
+            },
+        }
+    }
+
+    /// Makes `game` match the snapshot. Winning lines are worked out from
+    /// the board, so a win by resignation has none, as on the host. Pieces
+    /// new since the last snapshot are added to the history; a board that
+    /// lost pieces is a new game, so the history starts over from the
+    /// stones the game's rules start it with, which aren't moves.
+    pub fn apply(&self, game: &mut Game) {
+        let squares = (0..BOARD_SIZE).flat_map(|row| (0..BOARD_SIZE).map(move |col| (row, col)));
+        if squares.clone().any(|(row, col)| {
+            game.board[row][col].is_some() && game.board[row][col] != self.board[row][col]
+        }) {
+            game.board = [[None; BOARD_SIZE]; BOARD_SIZE];
+            if let Handicap::Stones(player, ref stones) = game.ruleset.handicap {
+                for &(row, col) in stones {
+                    game.board[row][col] = Some(player);
+                }
+            }
+            game.history.clear();
+        }
+        for (row, col) in squares {
+            if let (None, Some(player)) = (game.board[row][col], self.board[row][col]) {
+                game.history.push((row, col, player));
+            }
+        }
+        game.board = self.board;
+        game.current_player = self.current_player;
+        game.status = match self.result {
+            None => GameStatus::InProgress,
+            Some(None) => GameStatus::Tie,
+            Some(Some(player)) => GameStatus::Win(player, winning_lines(player, &self.board)),
+        };
+        game.turn_id = super::next_turn_id();
+    }
+}
+
+/// A line of the protocol two players on a network talk in over TCP
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Message {
+    /// `HELLO <name>`, sent by the guest on joining
+    Hello(String),
+    /// `WELCOME <side> <turns> <handicap> <name>`, the host's answer to
+    /// `HELLO`, with the side the guest plays, the rules of the game and the
+    /// host's name. The rules are written as in the record store, like
+    /// `alternating none` or `random stones:X:b2`.
+    Welcome { side: PlayerKind, ruleset: Ruleset, name: String },
+    /// `MOVE <row> <col>`, the guest's move, from 1 to 3 from the top left
+    Move(Coord),
+    /// `STATE <board> <next> <result>`, the game as it stands, sent by the
+    /// host after every change. The board is written as by `board_string`,
+    /// `next` is the player to move and `result` is `-` while the game is
+    /// on, `tie`, or the winner.
+    State(Snapshot),
+    /// `RESIGN`, the sender gives up the game
+    Resign,
+    /// `REMATCH`, the sender wants another game, which starts once both
+    /// players have asked
+    Rematch,
+    /// `ERROR <message>`, the last message was refused
+    Error(String),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Hello(name) => write!(f, "HELLO {}", name),
+            Message::Welcome { side, ruleset, name } => {
+                write!(f, "WELCOME {} {} {} {}", side.to_char(), ruleset.turn_order, ruleset.handicap, name)
+            },
+            Message::Move(coord) => write!(f, "MOVE {} {}", coord.row + 1, coord.col + 1),
+            Message::State(snapshot) => {
+                let result = match snapshot.result {
+                    None => "-".to_string(),
+                    Some(None) => "tie".to_string(),
+                    Some(Some(player)) => player.to_char().to_string(),
+                };
+                write!(f, "STATE {} {} {}", board_string(&snapshot.board),
+                       snapshot.current_player.to_char(), result)
+            },
+            Message::Resign => write!(f, "RESIGN"),
+            Message::Rematch => write!(f, "REMATCH"),
+            Message::Error(message) => write!(f, "ERROR {}", message),
+        }
+    }
+}
+
+fn parse_player(s: &str) -> Result<PlayerKind, String> {
+    match s {
+        "X" => Ok(PlayerKind::PlayerX),
+        "O" => Ok(PlayerKind::PlayerO),
+        _ => Err(format!("Expected a player, X or O, found \"{}\"", s)),
+    }
+}
+
+/// `s` without its first `count` words
+fn skip_words(s: &str, count: usize) -> &str {
+    let mut rest = s.trim_start();
+    for _ in 0..count {
+        rest = rest.find(char::is_whitespace).map_or("", |end| rest[end..].trim_start());
+    }
+    rest
+}
+
+fn parse_index(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if (1..=BOARD_SIZE).contains(&n) => Ok(n - 1),
+        _ => Err(format!("Expected a number from 1 to {}, found \"{}\"", BOARD_SIZE, s)),
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Message, String> {
+        let line = line.trim();
+        let (command, rest) = match line.split_once(' ') {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+        let args: Vec<&str> = rest.split_whitespace().collect();
+
+        match (command, args.as_slice()) {
+            ("HELLO", [_, ..]) => Ok(Message::Hello(rest.to_string())),
+            ("WELCOME", [side, turns, handicap, _, ..]) => Ok(Message::Welcome {
+                side: parse_player(side)?,
+                ruleset: Ruleset { turn_order: turns.parse()?, handicap: handicap.parse()? },
+                name: skip_words(rest, 3).to_string(),
+            }),
+            ("MOVE", [row, col]) => Ok(Message::Move(Coord::new(parse_index(row)?, parse_index(col)?))),
+            ("STATE", [board, next, result]) => Ok(Message::State(Snapshot {
+                board: parse_board_string(board)?,
+                current_player: parse_player(next)?,
+                result: match *result {
+                    "-" => None,
+                    "tie" => Some(None),
+                    winner => Some(Some(parse_player(winner)?)),
+                },
+            })),
+            ("RESIGN", []) => Ok(Message::Resign),
+            ("REMATCH", []) => Ok(Message::Rematch),
+            ("ERROR", _) => Ok(Message::Error(rest.to_string())),
+            _ => Err(format!("Unknown message \"{}\"", line)),
+        }
+    }
+}
+
+/// What a `Peer` has received
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incoming {
+    Message(Message),
+    /// A line that couldn't be parsed, with the reason
+    Invalid(String),
+    /// The other end hung up
+    Closed,
+}
+
+/// The other end of a connection. Lines are read on their own thread, so
+/// checking for messages never blocks a frontend's loop.
+pub struct Peer {
+    writer: TcpStream,
+    incoming: Receiver<Incoming>,
+}
+
+impl Peer {
+    pub fn new(stream: TcpStream) -> io::Result<Peer> {
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let received = match line.parse() {
+                    Ok(message) => Incoming::Message(message),
+                    Err(e) => Incoming::Invalid(e),
+                };
+                if sender.send(received).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(Incoming::Closed);
+        });
+        Ok(Peer { writer: stream, incoming })
+    }
+
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        writeln!(self.writer, "{}", message)?;
+        self.writer.flush()
+    }
+
+    /// The next thing received, if there's anything waiting
+    pub fn try_receive(&self) -> Option<Incoming> {
+        match self.incoming.try_recv() {
+            Ok(received) => Some(received),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Incoming::Closed),
+        }
+    }
+
+    /// Waits for the next thing received
+    pub fn receive(&self) -> Incoming {
+        self.incoming.recv().unwrap_or(Incoming::Closed)
+    }
+}
+
+impl Drop for Peer {
+    // the reading thread holds its own handle on the socket, so it has to
+    // be shut down for the other end to see it close
+    fn drop(&mut self) {
+        let _ = self.writer.shutdown(Shutdown::Both);
+    }
+}
+
+/// Which end of a network game to play, from the command line
+#[derive(Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Wait for a player to join on this port
+    Host(u16),
+    /// Join the game hosted at this address
+    Join(String),
+}
+
+impl Role {
+    /// Reads the role from command line arguments, returning `None` for a
+    /// game on this computer:
+    ///
+    /// * `--host [port]` - host a game, on port 7878 by default
+    /// * `--join <address>` - join a game, given as `host` or `host:port`
+    pub fn from_args(args: &[String]) -> Result<Option<Role>, String> {
+        if let Some(i) = args.iter().position(|arg| arg == "--host") {
+            let port = match args.get(i + 1).filter(|arg| !arg.starts_with("--")) {
+                Some(port) => port.parse().map_err(|_| format!("Expected a port number, found \"{}\"", port))?,
+                None => DEFAULT_PORT,
+            };
+            Ok(Some(Role::Host(port)))
+        } else if let Some(i) = args.iter().position(|arg| arg == "--join") {
+            match args.get(i + 1) {
+                Some(address) if address.contains(':') => Ok(Some(Role::Join(address.clone()))),
+                Some(address) => Ok(Some(Role::Join(format!("{}:{}", address, DEFAULT_PORT)))),
+                None => Err("--join needs the address of the host, e.g. --join 192.168.1.20".to_string()),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Starts the session, waiting for a guest to join on any network
+    /// interface when hosting. `config` is only used by the host.
+    pub fn connect(&self, name: &str, config: GameConfig) -> Result<Session, String> {
+        match self {
+            Role::Host(port) => {
+                let listener = TcpListener::bind(("0.0.0.0", *port))
+                    .map_err(|e| format!("Couldn't listen on port {}: {}", port, e))?;
+                Session::host(&listener, name, config).map_err(|e| format!("Couldn't start the game: {}", e))
+            },
+            Role::Join(address) => {
+                Session::join(address.as_str(), name).map_err(|e| format!("Couldn't join {}: {}", address, e))
+            },
+        }
+    }
+}
+
+/// The name to introduce this end with, from `--name <name>`, or else the
+/// user's login name
+pub fn player_name(args: &[String]) -> String {
+    let name = args.iter().position(|arg| arg == "--name")
+        .and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| env::var("USER").or_else(|_| env::var("USERNAME")).ok())
+        .unwrap_or_default();
+    clean_name(&name)
+}
+
+/// `name` as it can be sent in a message: without control characters like
+/// line breaks, which would end the line early, or spaces at either end.
+/// A name with nothing left is `Player`.
+pub fn clean_name(name: &str) -> String {
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    match name.trim() {
+        "" => "Player".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Checks that `player` may play at `coord` in `game`
+pub fn check_move(game: &Game, player: PlayerKind, coord: Coord) -> Result<(), String> {
+    if game.status != GameStatus::InProgress {
+        Err("The game is over".to_string())
+    } else if game.current_player != player {
+        Err(format!("It's {}'s turn", game.current_player.to_char()))
+    } else if coord.row >= BOARD_SIZE || coord.col >= BOARD_SIZE {
+        Err(format!("{} is off the board", coord))
+    } else if game.board[coord.row][coord.col].is_some() {
+        Err(format!("{} is taken", coord))
+    } else {
+        Ok(())
+    }
+}
+
+/// Something that happened in a `Session`, for the frontend to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// The game changed, by a move, a resignation or a new game
+    Updated,
+    /// The other player wants a rematch
+    RematchOffered,
+    /// The host refused something this end sent
+    Refused(String),
+    /// The other player left
+    Closed,
+}
+
+/// One end of a game between two players on a network. The host keeps the
+/// real game and checks every move the guest sends before playing it, so
+/// the guest only ever shows what the host sends back.
+pub struct Session {
+    /// The game as this end knows it. On the host it's the real game, and
+    /// on the guest it's a copy kept up to date by the host.
+    pub game: Game,
+    /// The side played at this end
+    pub side: PlayerKind,
+    pub is_host: bool,
+    pub opponent_name: String,
+    peer: Peer,
+    /// Whether this end and the other have asked for a rematch
+    rematch: (bool, bool),
+}
+
+impl Session {
+    /// Waits on `listener` for a guest to join, then starts a game set up
+    /// by `config`, in which the host plays X. Both sides are played by
+    /// people, whatever `config` says, and who starts swaps every game.
+    pub fn host(listener: &TcpListener, name: &str, config: GameConfig) -> io::Result<Session> {
+        let (stream, _) = listener.accept()?;
+        let mut peer = Peer::new(stream)?;
+
+        let opponent_name = loop {
+            match peer.receive() {
+                Incoming::Message(Message::Hello(name)) => break clean_name(&name),
+                Incoming::Closed => {
+                    return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "The guest left before saying hello"));
+                },
+                _ => peer.send(&Message::Error("Expected HELLO".to_string()))?,
+            }
+        };
+
+        let game = config.human(PlayerKind::PlayerX, true)
+            .human(PlayerKind::PlayerO, true)
+            .alternate_starts(true)
+            .build();
+        let mut session = Session {
+            game,
+            side: PlayerKind::PlayerX,
+            is_host: true,
+            opponent_name,
+            peer,
+            rematch: (false, false),
+        };
+        let welcome = Message::Welcome {
+            side: PlayerKind::PlayerO,
+            ruleset: session.game.ruleset.clone(),
+            name: clean_name(name),
+        };
+        session.peer.send(&welcome)?;
+        session.send_state().map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))?;
+        Ok(session)
+    }
+
+    /// Joins the game hosted at `address`
+    pub fn join<A: ToSocketAddrs>(address: A, name: &str) -> io::Result<Session> {
+        let mut peer = Peer::new(TcpStream::connect(address)?)?;
+        peer.send(&Message::Hello(clean_name(name)))?;
+
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let (side, ruleset, opponent_name) = match peer.receive() {
+            Incoming::Message(Message::Welcome { side, ruleset, name }) => (side, ruleset, name),
+            Incoming::Message(message) => return Err(invalid(format!("Expected WELCOME, got {}", message))),
+            Incoming::Invalid(e) => return Err(invalid(e)),
+            Incoming::Closed => {
+                return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "The host closed the connection"));
+            },
+        };
+
+        // the guest's game only follows the host's, but is played under the
+        // same rules, so it starts with the same stones and is recorded as
+        // it was played
+        let mut game = GameConfig::new().ruleset(ruleset).human(PlayerKind::PlayerO, true).build();
+        match peer.receive() {
+            Incoming::Message(Message::State(snapshot)) => snapshot.apply(&mut game),
+            _ => return Err(invalid("Expected the game's STATE".to_string())),
+        }
+
+        Ok(Session {
+            game,
+            side,
+            is_host: false,
+            opponent_name,
+            peer,
+            rematch: (false, false),
+        })
+    }
+
+    pub fn is_my_turn(&self) -> bool {
+        self.game.status == GameStatus::InProgress && self.game.current_player == self.side
+    }
+
+    /// The names of X and O, with `name` for this end's player
+    pub fn player_names<'a>(&'a self, name: &'a str) -> (&'a str, &'a str) {
+        match self.side {
+            PlayerKind::PlayerX => (name, &self.opponent_name),
+            PlayerKind::PlayerO => (&self.opponent_name, name),
+        }
+    }
+
+    /// Whether this end has asked for a rematch that hasn't started yet
+    pub fn rematch_requested(&self) -> bool {
+        self.rematch.0
+    }
+
+    /// Plays a move at this end. On the guest the board changes once the
+    /// host has checked the move and sent it back.
+    pub fn play(&mut self, coord: Coord) -> Result<(), String> {
+        check_move(&self.game, self.side, coord)?;
+        if self.is_host {
+            self.game.make_move(coord.row, coord.col);
+            self.send_state()
+        } else {
+            self.send(&Message::Move(coord))
+        }
+    }
+
+    pub fn resign(&mut self) -> Result<(), String> {
+        if self.game.status != GameStatus::InProgress {
+            return Err("The game is over".to_string());
+        }
+        if self.is_host {
+            self.game.resign(self.side);
+            self.send_state()
+        } else {
+            self.send(&Message::Resign)
+        }
+    }
+
+    /// Asks for another game once this one is over
+    pub fn rematch(&mut self) -> Result<(), String> {
+        if self.game.status == GameStatus::InProgress {
+            return Err("The game isn't over".to_string());
+        }
+        self.rematch.0 = true;
+        if self.is_host && self.rematch.1 {
+            self.start_rematch()
+        } else {
+            self.send(&Message::Rematch)
+        }
+    }
+
+    /// Handles anything received since the last call, returning the first
+    /// thing the frontend should know about, without waiting
+    pub fn poll(&mut self) -> Option<SessionEvent> {
+        while let Some(received) = self.peer.try_receive() {
+            if let Some(event) = self.handle(received) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Waits until there's something for the frontend to know about
+    pub fn wait(&mut self) -> SessionEvent {
+        loop {
+            let received = self.peer.receive();
+            if let Some(event) = self.handle(received) {
+                return event;
+            }
+        }
+    }
+
+    fn handle(&mut self, received: Incoming) -> Option<SessionEvent> {
+        let message = match received {
+            Incoming::Message(message) => message,
+            Incoming::Invalid(e) => {
+                let _ = self.send(&Message::Error(e));
+                return None;
+            },
+            Incoming::Closed => return Some(SessionEvent::Closed),
+        };
+
+        let result = match (self.is_host, message) {
+            (_, Message::Error(e)) => return Some(SessionEvent::Refused(e)),
+            (true, Message::Move(coord)) => {
+                check_move(&self.game, self.side.opposite(), coord).and_then(|_| {
+                    self.game.make_move(coord.row, coord.col);
+                    self.send_state()
+                })
+            },
+            (true, Message::Resign) if self.game.status == GameStatus::InProgress => {
+                self.game.resign(self.side.opposite());
+                self.send_state()
+            },
+            (_, Message::Rematch) if self.game.status != GameStatus::InProgress => {
+                self.rematch.1 = true;
+                if !self.is_host || !self.rematch.0 {
+                    return Some(SessionEvent::RematchOffered);
+                }
+                self.start_rematch()
+            },
+            (false, Message::State(snapshot)) => {
+                snapshot.apply(&mut self.game);
+                if self.game.status == GameStatus::InProgress {
+                    self.rematch = (false, false);
+                }
+                Ok(())
+            },
+            (_, message) => Err(format!("Didn't expect {}", message)),
+        };
+
+        match result {
+            Ok(()) => Some(SessionEvent::Updated),
+            Err(e) => {
+                let _ = self.send(&Message::Error(e));
+                None
+            },
+        }
+    }
+
+    fn start_rematch(&mut self) -> Result<(), String> {
+        self.rematch = (false, false);
+        self.game.reset();
+        self.send_state()
+    }
+
+    fn send_state(&mut self) -> Result<(), String> {
+        self.send(&Message::State(Snapshot::of(&self.game)))
+    }
+
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        self.peer.send(message).map_err(|e| format!("Lost the connection: {}", e))
+    }
+}
@@ -0,0 +1,244 @@
+use super::*;
+
+use std::time::{Duration, Instant};
+
+use crate::TurnOrder;
+use PlayerKind::*;
+
+/// A host and a guest connected on localhost
+fn connect() -> (Session, Session) {
+    connect_with(GameConfig::new())
+}
+
+/// A host and a guest connected on localhost, in a game set up by `config`
+fn connect_with(config: GameConfig) -> (Session, Session) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let host = thread::spawn(move || Session::host(&listener, "ann", config));
+    let guest = Session::join(address, "bob").unwrap();
+    (host.join().unwrap().unwrap(), guest)
+}
+
+/// Lines sent by the host, read straight off the socket
+fn read_line(reader: &mut BufReader<TcpStream>) -> String {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    line.trim().to_string()
+}
+
+#[test]
+fn message_round_trip_test() {
+    let mut board = [[None; BOARD_SIZE]; BOARD_SIZE];
+    board[0][0] = Some(PlayerX);
+    board[1][1] = Some(PlayerO);
+    let messages = [
+        Message::Hello("bob".to_string()),
+        Message::Welcome { side: PlayerO, ruleset: Ruleset::default(), name: "ann lee".to_string() },
+        Message::Welcome {
+            side: PlayerX,
+            ruleset: Ruleset { turn_order: TurnOrder::Random, handicap: Handicap::Stones(PlayerO, vec![(0, 0), (2, 1)]) },
+            name: "bob".to_string(),
+        },
+        Message::Move(Coord::new(2, 0)),
+        Message::State(Snapshot { board, current_player: PlayerX, result: None }),
+        Message::State(Snapshot { board, current_player: PlayerO, result: Some(None) }),
+        Message::State(Snapshot { board, current_player: PlayerO, result: Some(Some(PlayerX)) }),
+        Message::Resign,
+        Message::Rematch,
+        Message::Error("c3 is taken".to_string()),
+    ];
+    for message in messages.iter() {
+        assert_eq!(message.to_string().parse::<Message>().as_ref(), Ok(message));
+    }
+
+    assert_eq!(Message::Move(Coord::new(2, 0)).to_string(), "MOVE 3 1");
+    assert_eq!(Message::Welcome { side: PlayerO, ruleset: Ruleset::default(), name: "ann lee".to_string() }
+                   .to_string(),
+               "WELCOME O alternating none ann lee");
+    // a guest's name is all of HELLO, even when it starts like a side
+    assert_eq!("HELLO X Ray".parse(), Ok(Message::Hello("X Ray".to_string())));
+    assert_eq!(Message::State(Snapshot { board, current_player: PlayerO, result: Some(Some(PlayerX)) })
+                   .to_string(),
+               "STATE X../.O./... O X");
+
+    for bad in &["", "HELLO", "WELCOME O", "WELCOME O alternating none", "WELCOME Z alternating none ann",
+                 "WELCOME O sideways none ann", "WELCOME O alternating double:Z ann", "MOVE 1", "MOVE 0 1", "MOVE 4 1", "STATE X../.O./... O",
+                 "STATE X../.O/... O -", "STATE X../.O./... Z -", "RESIGN now", "FOO"] {
+        assert!(bad.parse::<Message>().is_err(), "{:?} parsed", bad);
+    }
+}
+
+#[test]
+fn play_a_game_test() {
+    let (mut host, mut guest) = connect();
+    assert_eq!((host.side, guest.side), (PlayerX, PlayerO));
+    assert_eq!((host.opponent_name.as_str(), guest.opponent_name.as_str()), ("bob", "ann"));
+    assert_eq!(guest.player_names("bob"), ("ann", "bob"));
+    assert!(host.is_my_turn() && !guest.is_my_turn());
+
+    // each end only moves on its own turn and on an empty square
+    assert_eq!(guest.play(Coord::new(0, 0)), Err("It's X's turn".to_string()));
+
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+        if host.is_my_turn() {
+            host.play(Coord::new(row, col)).unwrap();
+            assert_eq!(guest.wait(), SessionEvent::Updated);
+        } else {
+            guest.play(Coord::new(row, col)).unwrap();
+            assert_eq!(host.wait(), SessionEvent::Updated);
+            assert_eq!(guest.wait(), SessionEvent::Updated);
+        }
+        assert_eq!(guest.game.board, host.game.board);
+        assert_eq!(guest.game.history, host.game.history);
+    }
+    assert_eq!(host.play(Coord::new(1, 1)), Err("b2 is taken".to_string()));
+
+    host.play(Coord::new(0, 2)).unwrap();
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.status, host.game.status);
+    assert_eq!(guest.game.status.winner(), Some(PlayerX));
+
+    // the rematch starts once both have asked, with O starting
+    guest.rematch().unwrap();
+    assert_eq!(host.wait(), SessionEvent::RematchOffered);
+    host.rematch().unwrap();
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.status, GameStatus::InProgress);
+    assert!(guest.is_my_turn());
+    assert_eq!(guest.game.board, [[None; BOARD_SIZE]; BOARD_SIZE]);
+    assert!(guest.game.history.is_empty());
+
+    guest.play(Coord::new(2, 2)).unwrap();
+    assert_eq!(host.wait(), SessionEvent::Updated);
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.history, host.game.history);
+}
+
+#[test]
+fn handicap_stones_test() {
+    let ruleset = Ruleset { turn_order: TurnOrder::Alternating, handicap: Handicap::Stones(PlayerO, vec![(1, 1)]) };
+    let (mut host, mut guest) = connect_with(GameConfig::new().ruleset(ruleset.clone()));
+
+    // the guest plays under the host's rules, and the stones it starts
+    // with aren't moves
+    assert_eq!(guest.game.ruleset, ruleset);
+    assert_eq!(guest.game.board, host.game.board);
+    assert_eq!(guest.game.board[1][1], Some(PlayerO));
+    assert!(guest.game.history.is_empty());
+
+    host.play(Coord::new(0, 0)).unwrap();
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.history, host.game.history);
+    assert_eq!(guest.game.history, vec![(0, 0, PlayerX)]);
+
+    // nor are they in a rematch
+    host.resign().unwrap();
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    guest.rematch().unwrap();
+    assert_eq!(host.wait(), SessionEvent::RematchOffered);
+    host.rematch().unwrap();
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.board, host.game.board);
+    assert_eq!(guest.game.board[1][1], Some(PlayerO));
+    assert!(guest.game.history.is_empty());
+}
+
+#[test]
+fn resign_test() {
+    let (mut host, mut guest) = connect();
+    guest.resign().unwrap();
+    assert_eq!(host.wait(), SessionEvent::Updated);
+    assert_eq!(host.game.status, GameStatus::Win(PlayerX, Vec::new()));
+    assert_eq!(guest.wait(), SessionEvent::Updated);
+    assert_eq!(guest.game.status, GameStatus::Win(PlayerX, Vec::new()));
+
+    assert!(guest.resign().is_err());
+    assert!(host.play(Coord::new(0, 0)).is_err());
+}
+
+#[test]
+fn host_checks_moves_test() {
+    // a guest that sends whatever it likes, straight over the socket
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let mut host = Session::host(&listener, "ann", GameConfig::new()).unwrap();
+        let mut events = Vec::new();
+        loop {
+            match host.wait() {
+                SessionEvent::Closed => return (host, events),
+                event => events.push(event),
+            }
+        }
+    });
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    // the name loses the control characters a guest of its own could send
+    writeln!(stream, "HELLO mal\x07lory\x1b").unwrap();
+    assert_eq!(read_line(&mut reader), "WELCOME O alternating none ann");
+    assert_eq!(read_line(&mut reader), "STATE .../.../... X -");
+
+    // out of turn, nonsense, and a rematch before the game's over
+    writeln!(stream, "MOVE 2 2").unwrap();
+    assert_eq!(read_line(&mut reader), "ERROR It's X's turn");
+    writeln!(stream, "MOVE 2").unwrap();
+    assert!(read_line(&mut reader).starts_with("ERROR Unknown message"));
+    writeln!(stream, "REMATCH").unwrap();
+    assert_eq!(read_line(&mut reader), "ERROR Didn't expect REMATCH");
+    writeln!(stream, "STATE XXX/.../... O X").unwrap();
+    assert!(read_line(&mut reader).starts_with("ERROR Didn't expect STATE"));
+    drop(reader);
+    drop(stream);
+
+    // none of it reached the game
+    let (host, events) = host.join().unwrap();
+    assert_eq!(host.opponent_name, "mallory");
+    assert!(events.is_empty());
+    assert_eq!(host.game.board, [[None; BOARD_SIZE]; BOARD_SIZE]);
+}
+
+#[test]
+fn poll_test() {
+    let (mut host, mut guest) = connect();
+    assert_eq!(guest.poll(), None);
+
+    host.play(Coord::new(1, 1)).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let event = loop {
+        if let Some(event) = guest.poll() {
+            break event;
+        }
+        assert!(Instant::now() < deadline, "The move never arrived.");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(event, SessionEvent::Updated);
+    assert_eq!(guest.game.board[1][1], Some(PlayerX));
+
+    drop(host);
+    assert_eq!(guest.wait(), SessionEvent::Closed);
+}
+
+#[test]
+fn role_from_args_test() {
+    let args = |s: &str| -> Vec<String> { s.split_whitespace().map(String::from).collect() };
+    assert_eq!(Role::from_args(&args("cli")), Ok(None));
+    assert_eq!(Role::from_args(&args("cli --host")), Ok(Some(Role::Host(DEFAULT_PORT))));
+    assert_eq!(Role::from_args(&args("cli --host 9000 --name ann")), Ok(Some(Role::Host(9000))));
+    assert_eq!(Role::from_args(&args("cli --host --name ann")), Ok(Some(Role::Host(DEFAULT_PORT))));
+    assert_eq!(Role::from_args(&args("cli --join 10.0.0.2")), Ok(Some(Role::Join("10.0.0.2:7878".to_string()))));
+    assert_eq!(Role::from_args(&args("cli --join localhost:9000")), Ok(Some(Role::Join("localhost:9000".to_string()))));
+    assert!(Role::from_args(&args("cli --join")).is_err());
+    assert!(Role::from_args(&args("cli --host abc")).is_err());
+}
+
+#[test]
+fn clean_name_test() {
+    assert_eq!(clean_name("ann"), "ann");
+    assert_eq!(clean_name("ann\nMOVE 1 1"), "annMOVE 1 1");
+    assert_eq!(clean_name(" bob\t\r\n"), "bob");
+    assert_eq!(clean_name("\n"), "Player");
+
+    let args: Vec<String> = vec!["cli".to_string(), "--name".to_string(), "eve\nRESIGN".to_string()];
+    assert_eq!(player_name(&args), "eveRESIGN");
+}
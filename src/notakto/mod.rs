@@ -0,0 +1,301 @@
+use super::{has_won, next_turn_id, Board, PlayerKind, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+pub const DEFAULT_BOARD_COUNT: usize = 3;
+
+/// Both players place X in Notakto, so players are told apart by the
+/// order in which they move rather than by their symbol.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Seat {
+    First,
+    Second,
+}
+
+impl Seat {
+    pub fn opposite(&self) -> Seat {
+        match self {
+            Seat::First => Seat::Second,
+            Seat::Second => Seat::First,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NotaktoStatus {
+    InProgress,
+    Win(Seat),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotaktoGame {
+    pub first_is_human: bool,
+    pub second_is_human: bool,
+    pub current_seat: Seat,
+    pub boards: Vec<Board>,
+    pub status: NotaktoStatus,
+    /// Changes with every move and reset, as `Game::turn_id` does
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_turn_id"))]
+    pub turn_id: u64,
+}
+
+impl NotaktoGame {
+    pub fn new(board_count: usize) -> NotaktoGame {
+        assert!(board_count > 0, "Notakto needs at least one board.");
+        NotaktoGame {
+            first_is_human: true,
+            second_is_human: false,
+            current_seat: Seat::First,
+            boards: vec![[[None; BOARD_SIZE]; BOARD_SIZE]; board_count],
+            status: NotaktoStatus::InProgress,
+            turn_id: next_turn_id(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for board in self.boards.iter_mut() {
+            *board = [[None; BOARD_SIZE]; BOARD_SIZE];
+        }
+        self.status = NotaktoStatus::InProgress;
+        self.current_seat = Seat::First;
+        self.turn_id = next_turn_id();
+    }
+
+    pub fn current_seat_is_human(&self) -> bool {
+        match self.current_seat {
+            Seat::First => self.first_is_human,
+            Seat::Second => self.second_is_human,
+        }
+    }
+
+    /// A board is dead once it has three in a row, and can't be played on
+    pub fn is_dead(&self, board: usize) -> bool {
+        is_dead(&self.boards[board])
+    }
+
+    /// Places an X on `board` at `row`, `col`. The player who kills the
+    /// last live board loses. Returns false, and leaves the game as it was,
+    /// if the square is taken, the board is dead or the game is over.
+    pub fn make_move(&mut self, board: usize, row: usize, col: usize) -> bool {
+        if self.status != NotaktoStatus::InProgress || self.is_dead(board) || self.boards[board][row][col].is_some() {
+            return false;
+        }
+        self.boards[board][row][col] = Some(PlayerKind::PlayerX);
+
+        if self.boards.iter().all(is_dead) {
+            self.status = NotaktoStatus::Win(self.current_seat.opposite());
+        }
+
+        self.current_seat = self.current_seat.opposite();
+        self.turn_id = next_turn_id();
+        true
+    }
+}
+
+pub fn is_dead(board: &Board) -> bool {
+    has_won(PlayerKind::PlayerX, board)
+}
+
+/// Chooses a move for the player to move, returning the board index, row
+/// and column. Moves to a losing position for the opponent are preferred;
+/// with none available the bot stalls by avoiding killing boards.
+pub fn get_move(boards: &[Board]) -> (usize, usize, usize) {
+    let mut fallback: Option<(usize, usize, usize)> = None;
+    let mut fallback_kills_board = false;
+
+    for (index, board) in boards.iter().enumerate() {
+        if is_dead(board) {
+            continue;
+        }
+
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if board[row][col].is_some() {
+                    continue;
+                }
+
+                let mut boards_copy = boards.to_vec();
+                boards_copy[index][row][col] = Some(PlayerKind::PlayerX);
+
+                if !is_next_player_win(&boards_copy) {
+                    return (index, row, col);
+                }
+
+                // with no winning move, prefer one that leaves the board alive
+                let kills_board = is_dead(&boards_copy[index]);
+                if fallback.is_none() || (fallback_kills_board && !kills_board) {
+                    fallback = Some((index, row, col));
+                    fallback_kills_board = kills_board;
+                }
+            }
+        }
+    }
+
+    match fallback {
+        Some(m) => m,
+        None => panic!("No move was found for the bot."),
+    }
+}
+
+/// Returns true if the player about to move in `boards` can force a win
+pub fn is_next_player_win(boards: &[Board]) -> bool {
+    let value = boards.iter()
+        .map(board_value)
+        .fold(QuotientElement::IDENTITY, |product, value| product * value);
+    !value.is_p_position()
+}
+
+/// An element of the misère quotient of Notakto on 3x3 boards, as found by
+/// Plambeck and Whitehead in "The Secrets of Notakto". The quotient is the
+/// commutative monoid
+///
+///   < a, b, c, d | a² = 1, b³ = b, b²c = c, c³ = ac², b²d = d, cd = ad, d² = c² >
+///
+/// with 18 elements. Every board has a value in it, the value of a sum of
+/// boards is the product of their values, and the player to move loses
+/// exactly when that product is one of a, b², bc or c².
+///
+/// Elements are stored in the normal form a^a b^b c^c d^d.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuotientElement {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+}
+
+impl QuotientElement {
+    pub const IDENTITY: QuotientElement = QuotientElement::new(0, 0, 0, 0);
+
+    const fn new(a: u8, b: u8, c: u8, d: u8) -> QuotientElement {
+        QuotientElement { a, b, c, d }
+    }
+
+    pub fn is_p_position(&self) -> bool {
+        let p_positions = [
+            QuotientElement::new(1, 0, 0, 0),
+            QuotientElement::new(0, 2, 0, 0),
+            QuotientElement::new(0, 1, 1, 0),
+            QuotientElement::new(0, 0, 2, 0),
+        ];
+        p_positions.contains(self)
+    }
+
+    /// Applies the relations of the quotient until the element is in
+    /// normal form
+    fn reduce(mut self) -> QuotientElement {
+        loop {
+            let before = self;
+
+            // a² = 1
+            self.a %= 2;
+            // b³ = b
+            if self.b >= 3 {
+                self.b -= 2;
+            }
+            // b²c = c and b²d = d
+            if self.b == 2 && (self.c > 0 || self.d > 0) {
+                self.b = 0;
+            }
+            // c³ = ac²
+            if self.c >= 3 {
+                self.c -= 1;
+                self.a += 1;
+            }
+            // cd = ad
+            if self.c > 0 && self.d > 0 {
+                self.c -= 1;
+                self.a += 1;
+            }
+            // d² = c²
+            if self.d >= 2 {
+                self.d -= 2;
+                self.c += 2;
+            }
+            self.a %= 2;
+
+            if self == before {
+                return self;
+            }
+        }
+    }
+}
+
+impl std::ops::Mul for QuotientElement {
+    type Output = QuotientElement;
+
+    fn mul(self, other: QuotientElement) -> QuotientElement {
+        QuotientElement::new(self.a + other.a, self.b + other.b,
+                             self.c + other.c, self.d + other.d).reduce()
+    }
+}
+
+/// Returns the value of a single board in the misère quotient. Dead boards
+/// offer no moves and are worth the identity.
+pub fn board_value(board: &Board) -> QuotientElement {
+    if is_dead(board) {
+        return QuotientElement::IDENTITY;
+    }
+
+    let a = QuotientElement::new(1, 0, 0, 0);
+    let b = QuotientElement::new(0, 1, 0, 0);
+    let c = QuotientElement::new(0, 0, 1, 0);
+    let d = QuotientElement::new(0, 0, 0, 1);
+
+    // the table of values is keyed by the canonical orientation of each of
+    // the 46 distinct live boards
+    match canonical_mask(to_mask(board)) {
+        0 => c,
+        16 => c * c,
+        3 => d,
+        14 | 41 | 70 => a * d,
+        19 | 26 | 69 | 78 | 106 => a * b,
+        5 | 11 | 12 | 17 | 18 | 29 | 30 | 42 | 45 | 99 | 101 | 110 | 113 | 114
+            | 171 => b,
+        10 | 13 | 21 | 27 | 28 | 40 | 43 | 68 | 97 | 102 | 108 | 115 | 170
+            | 173 | 229 | 238 | 325 => a,
+        1 | 2 | 98 => QuotientElement::IDENTITY,
+        mask => panic!("Board {:#011b} has no value in the misère quotient.", mask),
+    }
+}
+
+fn to_mask(board: &Board) -> u16 {
+    let mut mask = 0;
+    for (i, row) in board.iter().enumerate() {
+        for (j, square) in row.iter().enumerate() {
+            if square.is_some() {
+                mask |= 1 << (i * BOARD_SIZE + j);
+            }
+        }
+    }
+    mask
+}
+
+/// Returns the smallest mask among the eight symmetries of the board
+fn canonical_mask(mask: u16) -> u16 {
+    let n = BOARD_SIZE;
+    let mut best = mask;
+    let mut current = mask;
+    for _ in 0..4 {
+        // rotate 90 degrees clockwise: (i, j) -> (j, n - 1 - i)
+        let mut rotated = 0;
+        let mut mirrored = 0;
+        for i in 0..n {
+            for j in 0..n {
+                if current & (1 << (i * n + j)) != 0 {
+                    rotated |= 1 << (j * n + (n - 1 - i));
+                    mirrored |= 1 << (i * n + (n - 1 - j));
+                }
+            }
+        }
+        best = best.min(rotated).min(mirrored);
+        current = rotated;
+    }
+    best
+}
@@ -0,0 +1,280 @@
+use super::*;
+use std::collections::HashMap;
+
+static EMP: Option<PlayerKind> = None;
+static P_X: Option<PlayerKind> = Some(PlayerKind::PlayerX);
+
+const EMPTY_BOARD: Board = [[None; BOARD_SIZE]; BOARD_SIZE];
+
+#[test]
+fn dead_board_test() {
+    let board: Board =
+        [[P_X, EMP, EMP],
+         [EMP, P_X, EMP],
+         [EMP, EMP, P_X]];
+    assert!(is_dead(&board));
+
+    let board: Board =
+        [[P_X, P_X, EMP],
+         [EMP, EMP, P_X],
+         [P_X, EMP, P_X]];
+    assert!(!is_dead(&board));
+}
+
+#[test]
+fn killing_last_board_loses_test() {
+    let mut game = NotaktoGame::new(2);
+    game.boards[0] =
+        [[P_X, P_X, P_X],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+    game.boards[1] =
+        [[P_X, P_X, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+
+    assert!(game.make_move(1, 0, 2));
+    assert_eq!(game.status, NotaktoStatus::Win(Seat::Second));
+    assert!(game.is_dead(1));
+
+    // nothing can be played once the game is over
+    assert!(!game.make_move(1, 2, 2));
+    assert_eq!(game.boards[1][2][2], EMP);
+}
+
+#[test]
+fn killing_one_of_several_boards_continues_test() {
+    let mut game = NotaktoGame::new(2);
+    game.boards[0] =
+        [[P_X, P_X, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+
+    assert!(game.make_move(0, 0, 2));
+    assert_eq!(game.status, NotaktoStatus::InProgress);
+    assert_eq!(game.current_seat, Seat::Second);
+}
+
+#[test]
+fn make_move_refuses_taken_square_and_dead_board_test() {
+    let mut game = NotaktoGame::new(2);
+    game.boards[0] =
+        [[P_X, P_X, P_X],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+
+    assert!(!game.make_move(0, 1, 1));
+    assert_eq!(game.boards[0][1][1], EMP);
+
+    assert!(game.make_move(1, 1, 1));
+    assert!(!game.make_move(1, 1, 1));
+    assert_eq!(game.current_seat, Seat::Second);
+}
+
+/// Exhaustive search used to check the misère quotient. A position is the
+/// sorted list of the canonical masks of its live boards.
+struct Analysis {
+    results: HashMap<Vec<u16>, bool>,
+}
+
+impl Analysis {
+    fn new() -> Analysis {
+        Analysis {
+            results: HashMap::new(),
+        }
+    }
+
+    fn is_next_player_win(&mut self, boards: &[Board]) -> bool {
+        let mut position: Vec<u16> = boards.iter()
+            .filter(|board| !is_dead(board))
+            .map(|board| canonical_mask(to_mask(board)))
+            .collect();
+        position.sort_unstable();
+        self.solve(position)
+    }
+
+    fn solve(&mut self, position: Vec<u16>) -> bool {
+        // misère play: with no live boards left the previous player killed
+        // the last one, so the player to move has won
+        if position.is_empty() {
+            return true;
+        }
+
+        if let Some(&result) = self.results.get(&position) {
+            return result;
+        }
+
+        let mut result = false;
+        'search: for (index, &mask) in position.iter().enumerate() {
+            for square in 0..BOARD_SIZE * BOARD_SIZE {
+                if mask & (1 << square) != 0 {
+                    continue;
+                }
+
+                let mut next = position.clone();
+                let board = to_board(mask | (1 << square));
+                if is_dead(&board) {
+                    next.remove(index);
+                } else {
+                    next[index] = canonical_mask(to_mask(&board));
+                    next.sort_unstable();
+                }
+
+                if !self.solve(next) {
+                    result = true;
+                    break 'search;
+                }
+            }
+        }
+
+        self.results.insert(position, result);
+        result
+    }
+}
+
+fn to_board(mask: u16) -> Board {
+    let mut board = EMPTY_BOARD;
+    for (i, row) in board.iter_mut().enumerate() {
+        for (j, square) in row.iter_mut().enumerate() {
+            if mask & (1 << (i * BOARD_SIZE + j)) != 0 {
+                *square = P_X;
+            }
+        }
+    }
+    board
+}
+
+/// Every live board, up to rotation and reflection
+fn canonical_live_boards() -> Vec<Board> {
+    let mut masks: Vec<u16> = (0..1 << (BOARD_SIZE * BOARD_SIZE))
+        .filter(|&mask| !is_dead(&to_board(mask)))
+        .map(canonical_mask)
+        .collect();
+    masks.sort_unstable();
+    masks.dedup();
+    masks.into_iter().map(to_board).collect()
+}
+
+// the known results for games started on empty boards: the first player
+// wins on one or three boards and the second player wins on two
+#[test]
+fn empty_board_outcomes_test() {
+    assert!(is_next_player_win(&[EMPTY_BOARD]));
+    assert!(!is_next_player_win(&[EMPTY_BOARD; 2]));
+    assert!(is_next_player_win(&[EMPTY_BOARD; 3]));
+
+    let mut analysis = Analysis::new();
+    assert!(analysis.is_next_player_win(&[EMPTY_BOARD]));
+    assert!(!analysis.is_next_player_win(&[EMPTY_BOARD; 2]));
+    assert!(analysis.is_next_player_win(&[EMPTY_BOARD; 3]));
+}
+
+#[test]
+fn quotient_element_test() {
+    let a = QuotientElement::new(1, 0, 0, 0);
+    let b = QuotientElement::new(0, 1, 0, 0);
+    let c = QuotientElement::new(0, 0, 1, 0);
+    let d = QuotientElement::new(0, 0, 0, 1);
+
+    assert_eq!(a * a, QuotientElement::IDENTITY);
+    assert_eq!(b * b * b, b);
+    assert_eq!(b * b * c, c);
+    assert_eq!(c * c * c, a * c * c);
+    assert_eq!(c * d, a * d);
+    assert_eq!(d * d, c * c);
+    assert!((b * c).is_p_position());
+    assert!(!c.is_p_position());
+}
+
+/// Checks the quotient against exhaustive search for every position of up
+/// to three boards
+#[test]
+fn quotient_matches_exhaustive_search_test() {
+    let boards = canonical_live_boards();
+    assert_eq!(boards.len(), 46);
+
+    let mut analysis = Analysis::new();
+    for i in 0..boards.len() {
+        for j in i..boards.len() {
+            for k in j..boards.len() {
+                let position = [boards[i], boards[j], boards[k]];
+                assert_eq!(is_next_player_win(&position),
+                           analysis.is_next_player_win(&position));
+                assert_eq!(is_next_player_win(&position[..2]),
+                           analysis.is_next_player_win(&position[..2]));
+            }
+            assert_eq!(is_next_player_win(&[boards[i]]),
+                       analysis.is_next_player_win(&[boards[i]]));
+        }
+    }
+}
+
+#[test]
+fn symmetric_boards_are_equivalent_test() {
+    let board: Board =
+        [[P_X, EMP, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+    let rotated: Board =
+        [[EMP, EMP, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, P_X]];
+    assert_eq!(canonical_mask(to_mask(&board)), canonical_mask(to_mask(&rotated)));
+}
+
+#[test]
+fn bot_wins_single_board_from_start_test() {
+    // the only winning opening on a single board is the center
+    assert_eq!(get_move(&[EMPTY_BOARD]), (0, 1, 1));
+}
+
+#[test]
+fn bot_avoids_killing_last_board_test() {
+    let board: Board =
+        [[P_X, P_X, EMP],
+         [EMP, EMP, EMP],
+         [EMP, EMP, EMP]];
+    let (index, row, col) = get_move(&[board]);
+    let mut board_copy = board;
+    board_copy[row][col] = Some(PlayerKind::PlayerX);
+    assert_eq!(index, 0);
+    assert!(!is_dead(&board_copy));
+}
+
+/// Plays every reply of a human opponent against the bot, and checks that
+/// the bot never loses a game it was in a winning position to start.
+fn bot_never_loses_helper(boards: Vec<Board>, bot_to_move: bool) {
+    if boards.iter().all(is_dead) {
+        // whoever moved last killed the last board and lost
+        assert!(bot_to_move, "Bot killed the last board in a won position.");
+        return;
+    }
+
+    if bot_to_move {
+        let (index, row, col) = get_move(&boards);
+        let mut next = boards.clone();
+        next[index][row][col] = Some(PlayerKind::PlayerX);
+        assert!(!is_next_player_win(&next));
+        bot_never_loses_helper(next, false);
+    } else {
+        for (index, board) in boards.iter().enumerate() {
+            if is_dead(board) {
+                continue;
+            }
+            for row in 0..BOARD_SIZE {
+                for col in 0..BOARD_SIZE {
+                    if board[row][col].is_none() {
+                        let mut next = boards.clone();
+                        next[index][row][col] = Some(PlayerKind::PlayerX);
+                        bot_never_loses_helper(next, true);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn bot_never_loses_single_board_test() {
+    bot_never_loses_helper(vec![EMPTY_BOARD], true);
+}
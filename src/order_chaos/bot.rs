@@ -0,0 +1,76 @@
+use super::*;
+
+/// Score given to a board on which Order has already won
+const ORDER_WIN_SCORE: i64 = 1_000_000_000;
+
+/// Score of an open window indexed by how many matching symbols it holds.
+/// Each step is worth ten times the one below, a hundred times for the
+/// last, so fuller windows weigh far more. With 32 windows on the board,
+/// several smaller ones can still add up to more than a fuller one.
+const WINDOW_SCORES: [i64; ORDER_CHAOS_WIN_LENGTH] = [1, 10, 100, 1_000, 100_000];
+
+// chooses a move by trying every symbol in every empty square and picking
+// the resulting board that is best for `role`. Order maximises the board
+// score and Chaos minimises it.
+pub fn get_move(role: Role, board: &OrderChaosBoard) -> (usize, usize, PlayerKind) {
+    use PlayerKind::*;
+
+    let mut best_move: Option<(usize, usize, PlayerKind)> = None;
+    let mut best_score = 0;
+
+    for row in 0..ORDER_CHAOS_BOARD_SIZE {
+        for col in 0..ORDER_CHAOS_BOARD_SIZE {
+            if board[row][col].is_some() {
+                continue;
+            }
+
+            for &symbol in [PlayerX, PlayerO].iter() {
+                let mut board_copy = *board;
+                board_copy[row][col] = Some(symbol);
+                let score = evaluate(&board_copy);
+
+                let is_better = match role {
+                    Role::Order => score > best_score,
+                    Role::Chaos => score < best_score,
+                };
+
+                if best_move.is_none() || is_better {
+                    best_move = Some((row, col, symbol));
+                    best_score = score;
+                }
+            }
+        }
+    }
+
+    match best_move {
+        Some(m) => m,
+        None => panic!("No move was found for the bot."),
+    }
+}
+
+/// Returns how promising `board` is for Order. Every line of five that
+/// contains only one kind of symbol can still be completed and adds to the
+/// score; lines holding both symbols are dead and count for nothing.
+pub fn evaluate(board: &OrderChaosBoard) -> i64 {
+    if has_five_in_a_row(board) {
+        return ORDER_WIN_SCORE;
+    }
+
+    let mut score = 0;
+    for_each_window(|window| {
+        let mut x_count = 0;
+        let mut o_count = 0;
+        for &(row, col) in window.iter() {
+            match board[row][col] {
+                Some(PlayerKind::PlayerX) => x_count += 1,
+                Some(PlayerKind::PlayerO) => o_count += 1,
+                None => (),
+            }
+        }
+
+        if x_count == 0 || o_count == 0 {
+            score += WINDOW_SCORES[x_count + o_count];
+        }
+    });
+    score
+}
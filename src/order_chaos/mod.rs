@@ -0,0 +1,145 @@
+use super::PlayerKind;
+
+#[cfg(test)]
+mod tests;
+pub mod bot;
+
+pub const ORDER_CHAOS_BOARD_SIZE: usize = 6;
+pub const ORDER_CHAOS_WIN_LENGTH: usize = 5;
+
+pub type OrderChaosBoard =
+    [[Option<PlayerKind>; ORDER_CHAOS_BOARD_SIZE]; ORDER_CHAOS_BOARD_SIZE];
+
+/// The two sides of Order and Chaos. Both roles may place either symbol,
+/// so the outcome of a game is decided by role rather than by symbol.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Role {
+    /// Wants five in a row of either symbol
+    Order,
+    /// Wants to fill the board without five in a row
+    Chaos,
+}
+
+impl Role {
+    pub fn opposite(&self) -> Role {
+        match self {
+            Role::Order => Role::Chaos,
+            Role::Chaos => Role::Order,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OrderChaosStatus {
+    InProgress,
+    Win(Role),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderChaosGame {
+    pub order_is_human: bool,
+    pub chaos_is_human: bool,
+    pub current_role: Role,
+    pub board: OrderChaosBoard,
+    pub status: OrderChaosStatus,
+}
+
+impl Default for OrderChaosGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderChaosGame {
+    pub fn new() -> OrderChaosGame {
+        OrderChaosGame {
+            order_is_human: true,
+            chaos_is_human: false,
+            current_role: Role::Order,
+            board: [[None; ORDER_CHAOS_BOARD_SIZE]; ORDER_CHAOS_BOARD_SIZE],
+            status: OrderChaosStatus::InProgress,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.board = [[None; ORDER_CHAOS_BOARD_SIZE]; ORDER_CHAOS_BOARD_SIZE];
+        self.status = OrderChaosStatus::InProgress;
+        self.current_role = Role::Order;
+    }
+
+    pub fn current_role_is_human(&self) -> bool {
+        match self.current_role {
+            Role::Order => self.order_is_human,
+            Role::Chaos => self.chaos_is_human,
+        }
+    }
+
+    /// Places `symbol` at `row`, `col` for the current role. Order wins as
+    /// soon as five of one symbol line up, Chaos wins if the board fills
+    /// up without that happening. Returns false, and leaves the game as it
+    /// was, if the square is already taken or the game is over.
+    pub fn make_move(&mut self, row: usize, col: usize, symbol: PlayerKind) -> bool {
+        if self.status != OrderChaosStatus::InProgress || self.board[row][col].is_some() {
+            return false;
+        }
+        self.board[row][col] = Some(symbol);
+
+        if has_five_in_a_row(&self.board) {
+            self.status = OrderChaosStatus::Win(Role::Order);
+        } else if is_full(&self.board) {
+            self.status = OrderChaosStatus::Win(Role::Chaos);
+        }
+
+        self.current_role = self.current_role.opposite();
+        true
+    }
+}
+
+/// Calls `func` with the cells of every line of `ORDER_CHAOS_WIN_LENGTH`
+/// squares on the board, in all four directions.
+pub fn for_each_window<F>(mut func: F)
+                      where F: FnMut(&[(usize, usize); ORDER_CHAOS_WIN_LENGTH]) {
+    let n = ORDER_CHAOS_BOARD_SIZE as i32;
+    let len = ORDER_CHAOS_WIN_LENGTH as i32;
+    let directions = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+    for &(d_row, d_col) in directions.iter() {
+        for row in 0..n {
+            for col in 0..n {
+                let end_row = row + d_row * (len - 1);
+                let end_col = col + d_col * (len - 1);
+                if end_row < 0 || end_row >= n || end_col < 0 || end_col >= n {
+                    continue;
+                }
+
+                let mut window = [(0, 0); ORDER_CHAOS_WIN_LENGTH];
+                for (k, cell) in window.iter_mut().enumerate() {
+                    let k = k as i32;
+                    *cell = ((row + d_row * k) as usize, (col + d_col * k) as usize);
+                }
+                func(&window);
+            }
+        }
+    }
+}
+
+pub fn has_five_in_a_row(board: &OrderChaosBoard) -> bool {
+    let mut found = false;
+    for_each_window(|window| {
+        let (first_row, first_col) = window[0];
+        if let Some(symbol) = board[first_row][first_col] {
+            if window.iter().all(|&(row, col)| board[row][col] == Some(symbol)) {
+                found = true;
+            }
+        }
+    });
+    found
+}
+
+pub fn is_full(board: &OrderChaosBoard) -> bool {
+    board.iter().all(|row| row.iter().all(|square| square.is_some()))
+}
@@ -0,0 +1,130 @@
+use super::*;
+use super::bot::get_move;
+
+static EMP: Option<PlayerKind> = None;
+static P_X: Option<PlayerKind> = Some(PlayerKind::PlayerX);
+static P_O: Option<PlayerKind> = Some(PlayerKind::PlayerO);
+
+use PlayerKind::*;
+
+#[test]
+fn five_in_a_row_test() {
+    let mut board: OrderChaosBoard =
+        [[EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, P_O, P_O, P_O, P_O, P_O],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP]];
+    assert!(has_five_in_a_row(&board));
+
+    board =
+        [[EMP, EMP, EMP, EMP, EMP, P_X],
+         [EMP, EMP, EMP, EMP, P_X, EMP],
+         [EMP, EMP, EMP, P_X, EMP, EMP],
+         [EMP, EMP, P_X, EMP, EMP, EMP],
+         [EMP, P_X, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP]];
+    assert!(has_five_in_a_row(&board));
+
+    board =
+        [[EMP, EMP, EMP, EMP, EMP, EMP],
+         [P_X, EMP, EMP, EMP, EMP, EMP],
+         [EMP, P_X, EMP, EMP, EMP, EMP],
+         [EMP, EMP, P_X, EMP, EMP, EMP],
+         [EMP, EMP, EMP, P_X, EMP, EMP],
+         [EMP, EMP, EMP, EMP, P_X, EMP]];
+    assert!(has_five_in_a_row(&board));
+}
+
+#[test]
+fn mixed_symbols_are_not_five_in_a_row_test() {
+    let board: OrderChaosBoard =
+        [[P_X, P_X, P_O, P_X, P_X, P_X],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP]];
+    assert!(!has_five_in_a_row(&board));
+}
+
+#[test]
+fn order_wins_with_either_symbol_test() {
+    let mut game = OrderChaosGame::new();
+    for col in 0..4 {
+        game.make_move(0, col, PlayerO);
+        game.make_move(5, col, PlayerX);
+    }
+    assert_eq!(game.status, OrderChaosStatus::InProgress);
+    assert_eq!(game.current_role, Role::Order);
+
+    assert!(game.make_move(0, 4, PlayerO));
+    assert_eq!(game.status, OrderChaosStatus::Win(Role::Order));
+
+    // nothing can be played once the game is over
+    assert!(!game.make_move(3, 3, PlayerX));
+    assert_eq!(game.board[3][3], EMP);
+}
+
+#[test]
+fn make_move_refuses_taken_square_test() {
+    let mut game = OrderChaosGame::new();
+    assert!(game.make_move(2, 2, PlayerX));
+    assert!(!game.make_move(2, 2, PlayerO));
+    assert_eq!(game.board[2][2], P_X);
+    assert_eq!(game.current_role, Role::Chaos);
+}
+
+#[test]
+fn chaos_wins_on_full_board_test() {
+    let board: OrderChaosBoard =
+        [[P_X, P_X, P_O, P_O, P_X, P_X],
+         [P_X, P_X, P_O, P_O, P_X, P_X],
+         [P_X, P_X, P_O, P_O, P_X, P_X],
+         [P_X, P_X, P_O, P_O, P_X, P_X],
+         [P_O, P_O, P_X, P_X, P_O, P_O],
+         [P_X, P_X, P_O, P_O, P_X, P_X]];
+
+    let mut game = OrderChaosGame::new();
+    for (row, cells) in board.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            game.make_move(row, col, cell.unwrap());
+        }
+    }
+    assert!(is_full(&game.board));
+    assert_eq!(game.status, OrderChaosStatus::Win(Role::Chaos));
+}
+
+#[test]
+fn order_bot_completes_five_test() {
+    let board: OrderChaosBoard =
+        [[EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, P_O, EMP, EMP, EMP],
+         [EMP, EMP, P_O, P_X, EMP, EMP],
+         [EMP, EMP, P_O, EMP, P_X, EMP],
+         [EMP, EMP, P_O, EMP, EMP, EMP],
+         [EMP, EMP, P_X, EMP, EMP, EMP]];
+    assert_eq!(get_move(Role::Order, &board), (0, 2, PlayerO));
+}
+
+#[test]
+fn chaos_bot_blocks_four_test() {
+    let board: OrderChaosBoard =
+        [[EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [P_O, P_X, P_X, P_X, P_X, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP],
+         [EMP, EMP, EMP, EMP, EMP, EMP]];
+    assert_eq!(get_move(Role::Chaos, &board), (2, 5, PlayerO));
+}
+
+#[test]
+fn bots_finish_a_game_test() {
+    let mut game = OrderChaosGame::new();
+    while let OrderChaosStatus::InProgress = game.status {
+        let (row, col, symbol) = get_move(game.current_role, &game.board);
+        assert!(game.make_move(row, col, symbol));
+    }
+}
@@ -0,0 +1,520 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redb::{Database, ReadableTable, TableDefinition, TableError};
+
+use super::{Coord, Difficulty, Game, GameStatus, Handicap, PlayerKind, Ruleset, TurnOrder};
+
+#[cfg(test)]
+mod tests;
+
+const FILE_NAME: &str = "records.redb";
+/// The file older versions kept games in, one record to a line
+const LEGACY_FILE_NAME: &str = "games.log";
+
+/// A finished game, as kept in the record store
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameRecord {
+    /// When the game started, in seconds since the Unix epoch
+    pub started: u64,
+    pub duration: Duration,
+    pub x: String,
+    pub o: String,
+    /// The bot that played, `perfect` for the perfect strategy or
+    /// `expectimax` for other rules, or `none` in a game between people
+    pub engine: String,
+    pub difficulty: Difficulty,
+    /// Who made the first move
+    pub first: PlayerKind,
+    /// The winner, or `None` for a tie
+    pub winner: Option<PlayerKind>,
+    /// Whether the loser resigned
+    pub resigned: bool,
+    /// The rules the game was played under
+    pub ruleset: Ruleset,
+    /// Each move with the player who made it, as the rules don't always
+    /// have the players take turns
+    pub moves: Vec<(Coord, PlayerKind)>,
+}
+
+impl GameRecord {
+    /// Records `game` between the players named `x` and `o`, or returns
+    /// `None` while it's still being played
+    pub fn new(game: &Game, x: &str, o: &str, started: SystemTime) -> Option<GameRecord> {
+        let (winner, resigned) = match &game.status {
+            GameStatus::InProgress => return None,
+            GameStatus::Win(player, lines) => (Some(*player), lines.is_empty()),
+            GameStatus::Tie => (None, false),
+        };
+        let engine = if game.player1.is_human && game.player2.is_human {
+            "none"
+        } else if game.ruleset.is_standard() {
+            "perfect"
+        } else {
+            "expectimax"
+        };
+
+        Some(GameRecord {
+            started: started.duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+            duration: started.elapsed().unwrap_or_default(),
+            x: record_name(x),
+            o: record_name(o),
+            engine: engine.to_string(),
+            difficulty: game.difficulty,
+            first: game.history.first().map_or(game.current_player, |&(_, _, player)| player),
+            winner,
+            resigned,
+            ruleset: game.ruleset.clone(),
+            moves: game.history.iter().map(|&(row, col, player)| (Coord::new(row, col), player)).collect(),
+        })
+    }
+
+    pub fn name(&self, player: PlayerKind) -> &str {
+        match player {
+            PlayerKind::PlayerX => &self.x,
+            PlayerKind::PlayerO => &self.o,
+        }
+    }
+
+    /// The squares played, in order
+    pub fn squares(&self) -> Vec<Coord> {
+        self.moves.iter().map(|&(square, _)| square).collect()
+    }
+}
+
+/// Names are written between spaces, so they can't have any
+fn record_name(name: &str) -> String {
+    let name = name.split_whitespace().collect::<Vec<_>>().join("_");
+    if name.is_empty() {
+        "-".to_string()
+    } else {
+        name
+    }
+}
+
+/// The name a person playing on this computer is recorded under: their
+/// login name
+pub fn local_name() -> String {
+    env::var("USER").or_else(|_| env::var("USERNAME"))
+        .map(|name| record_name(&name))
+        .unwrap_or_else(|_| "player".to_string())
+}
+
+/// The names of the two sides of a game on this computer. A lone person is
+/// recorded by their login name and the bot as `bot`, while two people
+/// sharing the computer can't be told apart, so they're `player-x` and
+/// `player-o`.
+pub fn local_names(game: &Game) -> (String, String) {
+    match (game.player1.is_human, game.player2.is_human) {
+        (true, true) => ("player-x".to_string(), "player-o".to_string()),
+        (true, false) => (local_name(), "bot".to_string()),
+        (false, true) => ("bot".to_string(), local_name()),
+        (false, false) => ("bot".to_string(), "bot".to_string()),
+    }
+}
+
+fn parse_player(s: &str) -> Option<PlayerKind> {
+    match s {
+        "X" => Some(PlayerKind::PlayerX),
+        "O" => Some(PlayerKind::PlayerO),
+        _ => None,
+    }
+}
+
+/// One line of `key=value` pairs, like
+/// `started=1700000000 duration_ms=5300 x=ann o=bot engine=perfect
+/// difficulty=hard first=X result=X resigned=false turns=alternating
+/// handicap=none moves=Xb2,Oa1,Xc3`
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let result = match self.winner {
+            Some(player) => player.to_char().to_string(),
+            None => "tie".to_string(),
+        };
+        let moves: Vec<String> = self.moves.iter()
+            .map(|(square, player)| format!("{}{}", player.to_char(), square))
+            .collect();
+        write!(f, "started={} duration_ms={} x={} o={} engine={} difficulty={} first={} result={} resigned={} \
+                   turns={} handicap={} moves={}",
+               self.started, self.duration.as_millis(), self.x, self.o, self.engine, self.difficulty,
+               self.first.to_char(), result, self.resigned, self.ruleset.turn_order, self.ruleset.handicap,
+               moves.join(","))
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<GameRecord, String> {
+        let fields: HashMap<&str, &str> = line.split_whitespace()
+            .map(|pair| pair.split_once('=').ok_or_else(|| format!("Expected key=value, found \"{}\"", pair)))
+            .collect::<Result<_, _>>()?;
+        let field = |key: &str| fields.get(key).copied().ok_or_else(|| format!("Missing {}", key));
+        let number = |key: &str| field(key)?.parse::<u64>().map_err(|_| format!("Expected a number for {}", key));
+
+        // a record without a first mover is taken to start with X, as
+        // most games do
+        let first = match fields.get("first").copied() {
+            None => PlayerKind::PlayerX,
+            Some(first) => parse_player(first)
+                .ok_or_else(|| format!("Expected X or O to move first, found \"{}\"", first))?,
+        };
+        // records from before the rules were kept are of standard games
+        let ruleset = Ruleset {
+            turn_order: fields.get("turns").map_or(Ok(TurnOrder::Alternating), |turns| turns.parse())?,
+            handicap: fields.get("handicap").map_or(Ok(Handicap::None), |handicap| handicap.parse())?,
+        };
+        // as are moves without the player who made them, who then take turns
+        let mut mover = first;
+        let mut moves = Vec::new();
+        for square in field("moves")?.split(',').filter(|square| !square.is_empty()) {
+            let (player, square) = match square.get(..1).and_then(parse_player) {
+                Some(player) => (player, &square[1..]),
+                None => (mover, square),
+            };
+            moves.push((square.parse()?, player));
+            mover = player.opposite();
+        }
+
+        Ok(GameRecord {
+            started: number("started")?,
+            duration: Duration::from_millis(number("duration_ms")?),
+            x: field("x")?.to_string(),
+            o: field("o")?.to_string(),
+            engine: field("engine")?.to_string(),
+            difficulty: field("difficulty")?.parse()?,
+            first,
+            winner: match field("result")? {
+                "X" => Some(PlayerKind::PlayerX),
+                "O" => Some(PlayerKind::PlayerO),
+                "tie" => None,
+                result => return Err(format!("Expected a result, X, O or tie, found \"{}\"", result)),
+            },
+            resigned: field("resigned")? == "true",
+            ruleset,
+            moves,
+        })
+    }
+}
+
+/// Where finished games are kept: an embedded key-value database (redb) in
+/// one file. Games are stored under increasing ids, each as the line
+/// `GameRecord` is written as, and every player's lifetime record is kept up
+/// to date alongside them, so it can be looked up without reading every
+/// game. Each game is written in a single transaction, so a crash part way
+/// through leaves no half-written game behind.
+pub struct RecordStore {
+    pub path: PathBuf,
+}
+
+/// Each game, by an id counting up from 0 in the order they were stored
+const GAMES: TableDefinition<u64, &str> = TableDefinition::new("games");
+/// Each player's games, wins, losses and draws, by name
+const PLAYERS: TableDefinition<&str, (u64, u64, u64, u64)> = TableDefinition::new("players");
+
+// redb's errors are large, but there's at most one for each time the store is
+// used, and each is turned into a message straight away
+#[allow(clippy::result_large_err)]
+impl RecordStore {
+    pub fn new(path: PathBuf) -> RecordStore {
+        RecordStore { path }
+    }
+
+    /// The store shared by every frontend: `tic-tac-torture/records.redb`
+    /// in `$XDG_DATA_HOME`, `~/.local/share` or, on Windows, `%APPDATA%`
+    pub fn open_default() -> Option<RecordStore> {
+        let dir = env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(RecordStore::new(dir.join("tic-tac-torture").join(FILE_NAME)))
+    }
+
+    /// Opens the database, creating it if it's not there yet. A new
+    /// database next to a `games.log` from older versions, which kept a
+    /// record to a line, starts with every game that log holds.
+    fn open(&self) -> Result<Database, redb::Error> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(redb::StorageError::from)?;
+        }
+        let is_new = !self.path.exists();
+        let database = Database::create(&self.path)?;
+        if is_new {
+            let legacy = self.path.with_file_name(LEGACY_FILE_NAME);
+            if let Ok(text) = fs::read_to_string(legacy) {
+                let records: Vec<GameRecord> = text.lines().filter_map(|line| line.parse().ok()).collect();
+                insert(&database, &records)?;
+            }
+        }
+        Ok(database)
+    }
+
+    /// Whether there's anything to read yet, in the database or in a log
+    /// left by older versions
+    fn exists(&self) -> bool {
+        self.path.exists() || self.path.with_file_name(LEGACY_FILE_NAME).exists()
+    }
+
+    pub fn append(&self, record: &GameRecord) -> Result<(), String> {
+        self.open()
+            .and_then(|database| insert(&database, std::slice::from_ref(record)))
+            .map_err(|e| format!("Couldn't save the game to {}: {}", self.path.display(), e))
+    }
+
+    /// Every record in the store, oldest first. There are none before the
+    /// first game is stored. Records that can't be read, like one written by
+    /// a newer version, are skipped and counted.
+    pub fn load(&self) -> Result<LoadedRecords, String> {
+        let read = || -> Result<LoadedRecords, redb::Error> {
+            let mut loaded = LoadedRecords::default();
+            let transaction = self.open()?.begin_read()?;
+            let games = match transaction.open_table(GAMES) {
+                Ok(games) => games,
+                Err(TableError::TableDoesNotExist(_)) => return Ok(loaded),
+                Err(e) => return Err(e.into()),
+            };
+            for entry in games.iter()? {
+                let (_, line) = entry?;
+                match line.value().parse() {
+                    Ok(record) => loaded.records.push(record),
+                    Err(_) => loaded.skipped += 1,
+                }
+            }
+            Ok(loaded)
+        };
+        if !self.exists() {
+            return Ok(LoadedRecords::default());
+        }
+        read().map_err(|e| format!("Couldn't read {}: {}", self.path.display(), e))
+    }
+
+    /// The lifetime record of `name`, looked up without reading every game
+    pub fn stats_for(&self, name: &str) -> Result<PlayerStats, String> {
+        let read = || -> Result<PlayerStats, redb::Error> {
+            let mut stats = PlayerStats::new(name);
+            let transaction = self.open()?.begin_read()?;
+            let players = match transaction.open_table(PLAYERS) {
+                Ok(players) => players,
+                Err(TableError::TableDoesNotExist(_)) => return Ok(stats),
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(entry) = players.get(name)? {
+                let (games, wins, losses, draws) = entry.value();
+                stats.games = games as usize;
+                stats.wins = wins as usize;
+                stats.losses = losses as usize;
+                stats.draws = draws as usize;
+            }
+            Ok(stats)
+        };
+        if !self.exists() {
+            return Ok(PlayerStats::new(name));
+        }
+        read().map_err(|e| format!("Couldn't read {}: {}", self.path.display(), e))
+    }
+}
+
+/// Stores `records` after the games already in `database`, adding them to
+/// their players' records, all in one transaction
+#[allow(clippy::result_large_err)]
+fn insert(database: &Database, records: &[GameRecord]) -> Result<(), redb::Error> {
+    let transaction = database.begin_write()?;
+    {
+        let mut games = transaction.open_table(GAMES)?;
+        let mut players = transaction.open_table(PLAYERS)?;
+        let first_id = games.last()?.map_or(0, |(id, _)| id.value() + 1);
+        for (id, record) in (first_id..).zip(records) {
+            games.insert(id, record.to_string().as_str())?;
+
+            for (name, result) in results(record) {
+                let (mut games, mut wins, mut losses, mut draws) = players.get(name)?
+                    .map_or((0, 0, 0, 0), |entry| entry.value());
+                games += 1;
+                match result {
+                    Some(true) => wins += 1,
+                    Some(false) => losses += 1,
+                    None => draws += 1,
+                }
+                players.insert(name, (games, wins, losses, draws))?;
+            }
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// The records read from a store
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadedRecords {
+    pub records: Vec<GameRecord>,
+    /// How many records couldn't be read
+    pub skipped: usize,
+}
+
+impl LoadedRecords {
+    /// A note on the records skipped from `store`, if there were any
+    pub fn warning(&self, store: &RecordStore) -> Option<String> {
+        match self.skipped {
+            0 => None,
+            1 => Some(format!("Skipped 1 unreadable game in {}", store.path.display())),
+            n => Some(format!("Skipped {} unreadable games in {}", n, store.path.display())),
+        }
+    }
+}
+
+/// Watches the game being played, timing it and storing it in the record
+/// store once it's finished
+pub struct Recorder {
+    /// Where games are stored, if anywhere
+    pub store: Option<RecordStore>,
+    started: SystemTime,
+    over: bool,
+}
+
+impl Recorder {
+    pub fn new(store: Option<RecordStore>) -> Recorder {
+        Recorder {
+            store,
+            started: SystemTime::now(),
+            over: false,
+        }
+    }
+
+    /// Starts timing a new game
+    pub fn restart(&mut self) {
+        self.started = SystemTime::now();
+        self.over = false;
+    }
+
+    /// Called after anything that may have changed `game`, played between
+    /// `x` and `o`. Stores the game the first time it's seen finished, and
+    /// starts timing again once a new game is under way.
+    pub fn update(&mut self, game: &Game, x: &str, o: &str) -> Result<(), String> {
+        match (&game.status, self.over) {
+            (GameStatus::InProgress, true) => self.restart(),
+            (GameStatus::InProgress, false) | (_, true) => (),
+            (_, false) => {
+                self.over = true;
+                if let (Some(store), Some(record)) = (&self.store, GameRecord::new(game, x, o, self.started)) {
+                    store.append(&record)?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// A player's lifetime record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub name: String,
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+}
+
+impl PlayerStats {
+    fn new(name: &str) -> PlayerStats {
+        PlayerStats {
+            name: name.to_string(),
+            games: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+        }
+    }
+
+    /// The share of games won, from 0 to 1
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+impl fmt::Display for PlayerStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} games, {} won, {} lost, {} drawn", self.games, self.wins, self.losses, self.draws)
+    }
+}
+
+/// The record of `name` over `records`
+pub fn stats_for(records: &[GameRecord], name: &str) -> PlayerStats {
+    player_stats(records).into_iter()
+        .find(|stats| stats.name == name)
+        .unwrap_or_else(|| PlayerStats::new(name))
+}
+
+/// Every player's record, most games first. A game someone played against
+/// themselves under one name, like the bot against the bot, counts once for
+/// them, as a draw, since they neither won nor lost against anyone else.
+pub fn player_stats(records: &[GameRecord]) -> Vec<PlayerStats> {
+    let mut stats: Vec<PlayerStats> = Vec::new();
+    for record in records {
+        for (name, result) in results(record) {
+            let index = match stats.iter().position(|stats| stats.name == name) {
+                Some(index) => index,
+                None => {
+                    stats.push(PlayerStats::new(name));
+                    stats.len() - 1
+                },
+            };
+            let entry = &mut stats[index];
+            entry.games += 1;
+            match result {
+                Some(true) => entry.wins += 1,
+                Some(false) => entry.losses += 1,
+                None => entry.draws += 1,
+            }
+        }
+    }
+    // stable, so players with as many games stay in the order they first
+    // played
+    stats.sort_by_key(|stats| Reverse(stats.games));
+    stats
+}
+
+/// Each player of `record` once, with whether they won, or `None` for a
+/// draw. Someone playing themselves is listed once, with a draw.
+fn results(record: &GameRecord) -> Vec<(&str, Option<bool>)> {
+    if record.x == record.o {
+        return vec![(&record.x, None)];
+    }
+    [PlayerKind::PlayerX, PlayerKind::PlayerO].iter()
+        .map(|&player| (record.name(player), record.winner.map(|winner| winner == player)))
+        .collect()
+}
+
+/// The most played openings, as their first `length` moves with how often
+/// each was played, at most `limit` of them, most played first
+pub fn common_openings(records: &[GameRecord], length: usize, limit: usize) -> Vec<(Vec<Coord>, usize)> {
+    let mut openings: Vec<(Vec<Coord>, usize)> = Vec::new();
+    for record in records.iter().filter(|record| record.moves.len() >= length) {
+        let squares = record.squares();
+        let opening = &squares[..length];
+        match openings.iter_mut().find(|(moves, _)| moves.as_slice() == opening) {
+            Some((_, count)) => *count += 1,
+            None => openings.push((opening.to_vec(), 1)),
+        }
+    }
+    openings.sort_by_key(|&(_, count)| Reverse(count));
+    openings.truncate(limit);
+    openings
+}
+
+/// The `limit` games that took longest, longest first
+pub fn longest_games(records: &[GameRecord], limit: usize) -> Vec<&GameRecord> {
+    let mut games: Vec<&GameRecord> = records.iter().collect();
+    games.sort_by_key(|record| Reverse(record.duration));
+    games.truncate(limit);
+    games
+}
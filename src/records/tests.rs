@@ -0,0 +1,188 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use super::*;
+use crate::{GameConfig, Handicap, Ruleset, TurnOrder};
+
+fn record(x: &str, o: &str, winner: Option<PlayerKind>, duration: u64, moves: &str) -> GameRecord {
+    GameRecord {
+        started: 1_700_000_000,
+        duration: Duration::from_secs(duration),
+        x: x.to_string(),
+        o: o.to_string(),
+        engine: "perfect".to_string(),
+        difficulty: Difficulty::Hard,
+        first: PlayerKind::PlayerX,
+        winner,
+        resigned: false,
+        ruleset: Ruleset::default(),
+        moves: moves.split(',').zip([PlayerKind::PlayerX, PlayerKind::PlayerO].iter().cycle())
+            .map(|(square, &player)| (square.parse().unwrap(), player))
+            .collect(),
+    }
+}
+
+#[test]
+fn record_from_game_test() {
+    let mut game = GameConfig::new().human(PlayerKind::PlayerO, true).build();
+    assert_eq!(GameRecord::new(&game, "ann", "bob", SystemTime::now()), None);
+
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        game.make_move(row, col);
+    }
+    let record = GameRecord::new(&game, "ann", "bob smith", SystemTime::now()).unwrap();
+    assert_eq!(record.winner, Some(PlayerKind::PlayerX));
+    assert_eq!(record.o, "bob_smith");
+    assert_eq!(record.engine, "none");
+    assert!(!record.resigned);
+    assert_eq!(record.squares().iter().map(Coord::to_string).collect::<Vec<_>>(), ["a1", "a2", "b1", "b2", "c1"]);
+    assert_eq!(record.moves[1].1, PlayerKind::PlayerO);
+    assert!(record.ruleset.is_standard());
+
+    // the rules are kept, along with who made each move
+    let ruleset = Ruleset { turn_order: TurnOrder::Random, handicap: Handicap::DoubleFirstMove(PlayerKind::PlayerO) };
+    let mut game = GameConfig::new().ruleset(ruleset.clone()).human(PlayerKind::PlayerO, true).build();
+    game.current_player = PlayerKind::PlayerO;
+    game.make_move(1, 1);
+    game.current_player = PlayerKind::PlayerO;
+    game.make_move(0, 0);
+    game.resign(PlayerKind::PlayerX);
+    let record = GameRecord::new(&game, "ann", "bob", SystemTime::now()).unwrap();
+    assert_eq!(record.ruleset, ruleset);
+    assert_eq!(record.moves, [(Coord::new(1, 1), PlayerKind::PlayerO), (Coord::new(0, 0), PlayerKind::PlayerO)]);
+}
+
+#[test]
+fn line_round_trip_test() {
+    let mut original = record("ann", "bot", None, 5, "b2,a1,c3");
+    original.duration = Duration::from_millis(5300);
+    let line = original.to_string();
+    assert_eq!(line, "started=1700000000 duration_ms=5300 x=ann o=bot engine=perfect difficulty=hard \
+                      first=X result=tie resigned=false turns=alternating handicap=none moves=Xb2,Oa1,Xc3");
+    assert_eq!(line.parse(), Ok(original.clone()));
+
+    // lines written before the rules and movers were kept
+    let old = "started=1700000000 duration_ms=5300 x=ann o=bot engine=perfect difficulty=hard \
+               result=tie resigned=false moves=b2,a1,c3";
+    assert_eq!(old.parse(), Ok(original.clone()));
+
+    for handicap in &[Handicap::DoubleFirstMove(PlayerKind::PlayerO),
+                      Handicap::Stones(PlayerKind::PlayerX, vec![(0, 0), (2, 2)])] {
+        original.ruleset = Ruleset { turn_order: TurnOrder::Random, handicap: handicap.clone() };
+        original.moves = vec![(Coord::new(1, 1), PlayerKind::PlayerO), (Coord::new(0, 1), PlayerKind::PlayerO)];
+        assert_eq!(original.to_string().parse(), Ok(original.clone()));
+    }
+    assert!(original.to_string().contains("turns=random handicap=stones:X:a1,c3 moves=Ob2,Ob1"));
+
+    assert!("started=1 duration_ms=2".parse::<GameRecord>().is_err());
+    assert!(line.replace("result=tie", "result=draw").parse::<GameRecord>().is_err());
+}
+
+#[test]
+fn store_test() {
+    let path = env::temp_dir().join(format!("tic-tac-torture-records-{}", process::id())).join(FILE_NAME);
+    let store = RecordStore::new(path.clone());
+    assert_eq!(store.load(), Ok(LoadedRecords::default()));
+
+    let first = record("ann", "bot", Some(PlayerKind::PlayerX), 10, "b2,a1");
+    let second = record("bot", "ann", None, 20, "a1,b2");
+    store.append(&first).unwrap();
+    store.append(&second).unwrap();
+    assert_eq!(store.load().map(|loaded| loaded.records), Ok(vec![first.clone(), second.clone()]));
+
+    // each player's record is kept as games are stored
+    assert_eq!(store.stats_for("ann"), Ok(PlayerStats { name: "ann".to_string(), games: 2, wins: 1, losses: 0, draws: 1 }));
+    assert_eq!(store.stats_for("nobody").map(|stats| stats.games), Ok(0));
+
+    // a game that can't be read, like one from a newer version, is
+    // skipped, and the games after it still load
+    {
+        let database = Database::create(&path).unwrap();
+        let transaction = database.begin_write().unwrap();
+        transaction.open_table(GAMES).unwrap().insert(2, "started=1700000000 duration_ms=53").unwrap();
+        transaction.commit().unwrap();
+    }
+    store.append(&first).unwrap();
+    let loaded = store.load().unwrap();
+    assert_eq!(loaded.records, vec![first.clone(), second, first]);
+    assert_eq!(loaded.skipped, 1);
+    assert!(loaded.warning(&store).unwrap().starts_with("Skipped 1 unreadable game"));
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn legacy_log_test() {
+    let dir = env::temp_dir().join(format!("tic-tac-torture-legacy-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let first = record("ann", "bot", Some(PlayerKind::PlayerX), 10, "b2,a1");
+    let second = record("bot", "ann", None, 20, "a1,b2");
+    fs::write(dir.join(LEGACY_FILE_NAME), format!("{}\nstarted=1700000000 duration_ms=53\n{}\n", first, second)).unwrap();
+
+    // a new store starts with the games in the log older versions kept
+    let store = RecordStore::new(dir.join(FILE_NAME));
+    assert_eq!(store.load().map(|loaded| loaded.records), Ok(vec![first.clone(), second.clone()]));
+    assert_eq!(store.stats_for("ann").map(|stats| stats.games), Ok(2));
+    // and only the once
+    store.append(&first).unwrap();
+    assert_eq!(store.load().map(|loaded| loaded.records), Ok(vec![first.clone(), second, first]));
+    assert!(dir.join(LEGACY_FILE_NAME).exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn queries_test() {
+    let records = vec![
+        record("ann", "bot", Some(PlayerKind::PlayerX), 30, "b2,a1,c3"),
+        record("ann", "bot", Some(PlayerKind::PlayerO), 10, "b2,a1,a3"),
+        record("bot", "ann", None, 50, "a1,b2"),
+        record("bob", "ann", Some(PlayerKind::PlayerX), 20, "b2,c3"),
+    ];
+
+    let stats = player_stats(&records);
+    assert_eq!(stats.iter().map(|stats| stats.name.as_str()).collect::<Vec<_>>(), ["ann", "bot", "bob"]);
+    assert_eq!(stats[0], PlayerStats { name: "ann".to_string(), games: 4, wins: 1, losses: 2, draws: 1 });
+    assert_eq!(stats[0].win_rate(), 0.25);
+    assert_eq!(stats_for(&records, "bob").wins, 1);
+    assert_eq!(stats_for(&records, "nobody").games, 0);
+
+    let openings = common_openings(&records, 2, 2);
+    let squares = |moves: &[Coord]| moves.iter().map(Coord::to_string).collect::<Vec<_>>().join(",");
+    assert_eq!(openings.iter().map(|(moves, count)| (squares(moves), *count)).collect::<Vec<_>>(),
+               [("b2,a1".to_string(), 2), ("a1,b2".to_string(), 1)]);
+
+    // the bot playing itself is one game for it, and neither a win nor a loss
+    let self_play = vec![record("bot", "bot", Some(PlayerKind::PlayerX), 10, "b2,a1")];
+    assert_eq!(player_stats(&self_play), [PlayerStats { name: "bot".to_string(), games: 1, wins: 0, losses: 0, draws: 1 }]);
+
+    let longest: Vec<u64> = longest_games(&records, 3).iter().map(|record| record.duration.as_secs()).collect();
+    assert_eq!(longest, [50, 30, 20]);
+}
+
+#[test]
+fn recorder_test() {
+    let path = env::temp_dir().join(format!("tic-tac-torture-recorder-{}", process::id())).join(FILE_NAME);
+    let mut recorder = Recorder::new(Some(RecordStore::new(path.clone())));
+    let mut game = GameConfig::new().human(PlayerKind::PlayerO, true).build();
+
+    recorder.update(&game, "ann", "bob").unwrap();
+    game.resign(PlayerKind::PlayerO);
+    // a finished game is only stored once, however often it's seen
+    recorder.update(&game, "ann", "bob").unwrap();
+    recorder.update(&game, "ann", "bob").unwrap();
+    game = GameConfig::new().human(PlayerKind::PlayerO, true).build();
+    recorder.update(&game, "ann", "bob").unwrap();
+    game.make_move(1, 1);
+    game.resign(PlayerKind::PlayerX);
+    recorder.update(&game, "ann", "bob").unwrap();
+
+    let records = recorder.store.as_ref().unwrap().load().unwrap().records;
+    assert_eq!(records.iter().map(|record| record.winner).collect::<Vec<_>>(),
+               [Some(PlayerKind::PlayerX), Some(PlayerKind::PlayerO)]);
+    assert!(records.iter().all(|record| record.resigned));
+    assert_eq!(records[1].squares(), [Coord::new(1, 1)]);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
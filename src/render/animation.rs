@@ -0,0 +1,57 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use gif::{Encoder, Frame, Repeat};
+
+use super::{game_scene, Game, Image, RenderOptions, Scene};
+
+/// How many frame delays the final position stays up before the animation
+/// starts over
+const FINAL_FRAME_HOLD: u32 = 3;
+
+/// How hard the colour reduction for each frame works, from 1, slowest and
+/// best, to 30
+const QUANTIZE_SPEED: i32 = 10;
+
+/// Every position of `game` from before the first move to the last, each
+/// drawn the same way as `game_scene`
+pub fn game_frames(game: &Game, options: &RenderOptions) -> Vec<Scene> {
+    let mut game = game.clone();
+    let mut frames = vec![game_scene(&game, options)];
+    while game.undo() {
+        frames.push(game_scene(&game, options));
+    }
+    frames.reverse();
+    frames
+}
+
+/// The number of hundredths of a second GIF delays are given in
+fn centiseconds(delay: Duration) -> u16 {
+    (delay.as_millis() / 10).min(u16::MAX as u128) as u16
+}
+
+/// `frames` as an animated GIF that loops forever, showing each one for
+/// `delay` and the last for longer. The frames must all be the same size.
+pub fn encode_gif(frames: &[Image], delay: Duration) -> Result<Vec<u8>, String> {
+    let first = frames.first().ok_or_else(|| "A GIF needs at least one frame".to_string())?;
+    let too_big = |_| format!("A {}x{} image is too big for a GIF", first.width, first.height);
+    let (width, height) = (u16::try_from(first.width).map_err(too_big)?, u16::try_from(first.height).map_err(too_big)?);
+
+    let mut bytes = Vec::new();
+    let mut encoder = Encoder::new(&mut bytes, width, height, &[])
+        .map_err(|e| format!("Couldn't encode the GIF: {}", e))?;
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| format!("Couldn't encode the GIF: {}", e))?;
+
+    for (i, image) in frames.iter().enumerate() {
+        if (image.width, image.height) != (first.width, first.height) {
+            return Err("Every frame of a GIF must be the same size".to_string());
+        }
+        let mut pixels = image.pixels.clone();
+        let mut frame = Frame::from_rgba_speed(width, height, &mut pixels, QUANTIZE_SPEED);
+        frame.delay = if i + 1 == frames.len() { centiseconds(delay * FINAL_FRAME_HOLD) } else { centiseconds(delay) };
+        encoder.write_frame(&frame).map_err(|e| format!("Couldn't encode the GIF: {}", e))?;
+    }
+    // the GIF is only finished once the encoder is done with it
+    drop(encoder);
+    Ok(bytes)
+}
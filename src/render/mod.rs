@@ -0,0 +1,357 @@
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{board_status, Board, Coord, Game, GameStatus, Line, PlayerKind, BOARD_SIZE};
+
+#[cfg(test)]
+mod tests;
+mod animation;
+mod raster;
+mod svg;
+mod theme;
+
+pub use animation::{encode_gif, game_frames};
+pub use raster::Image;
+pub use theme::{Palette, Theme};
+
+/// A point as `[x, y]`, with y growing downwards
+pub type Point = [f64; 2];
+
+/// A rectangle as `[x, y, width, height]`, with x and y at its top left
+/// corner
+pub type Rect = [f64; 4];
+
+/// A colour as red, green, blue and opacity, each from 0 to 1
+pub type Color = [f32; 4];
+
+/// A straight line `width` across. Its ends stop square at `from` and `to`,
+/// unless `square_ends` runs them on by half the width, like a square pen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub from: Point,
+    pub to: Point,
+    pub width: f64,
+    pub square_ends: bool,
+}
+
+impl Stroke {
+    /// The first `fraction` of the stroke, from 0 to 1, starting at `from`
+    pub fn partial(&self, fraction: f64) -> Stroke {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let [x0, y0] = self.from;
+        let [x1, y1] = self.to;
+        Stroke {
+            to: [x0 + (x1 - x0) * fraction, y0 + (y1 - y0) * fraction],
+            ..*self
+        }
+    }
+
+    /// The corners of the stroke's outline, going round from the left of
+    /// `from`
+    pub fn corners(&self) -> [Point; 4] {
+        let [x0, y0] = self.from;
+        let [x1, y1] = self.to;
+        let length = (x1 - x0).hypot(y1 - y0).max(f64::EPSILON);
+        let (dx, dy) = ((x1 - x0) / length, (y1 - y0) / length);
+        let extend = if self.square_ends { self.width / 2.0 } else { 0.0 };
+        let (x0, y0, x1, y1) = (x0 - dx * extend, y0 - dy * extend, x1 + dx * extend, y1 + dy * extend);
+        let (nx, ny) = (-dy * self.width / 2.0, dx * self.width / 2.0);
+        [[x0 + nx, y0 + ny], [x1 + nx, y1 + ny], [x1 - nx, y1 - ny], [x0 - nx, y0 - ny]]
+    }
+}
+
+/// A circle drawn with a line `width` across, centred on `radius`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ring {
+    pub center: Point,
+    pub radius: f64,
+    pub width: f64,
+}
+
+fn center_and_short_side(rect: Rect) -> (Point, f64) {
+    let [x, y, width, height] = rect;
+    ([x + width / 2.0, y + height / 2.0], width.min(height))
+}
+
+/// The two strokes of an X drawn in `rect`, each from its top end, the one
+/// from the top left first
+pub fn x_strokes(rect: Rect) -> [Stroke; 2] {
+    let ([center_x, center_y], short_side) = center_and_short_side(rect);
+    let pad = short_side * 0.1;
+    let width = short_side / 8.0;
+    let half = (short_side - 2.0 * pad) / 2.0 * FRAC_1_SQRT_2;
+
+    let stroke = |from: Point, to: Point| Stroke { from, to, width, square_ends: false };
+    [
+        stroke([center_x - half, center_y - half], [center_x + half, center_y + half]),
+        stroke([center_x + half, center_y - half], [center_x - half, center_y + half]),
+    ]
+}
+
+/// The ring of an O drawn in `rect`
+pub fn o_ring(rect: Rect) -> Ring {
+    let (center, short_side) = center_and_short_side(rect);
+    let width = short_side / 15.0;
+    let outer_radius = short_side / 2.0 * 0.8;
+    Ring {
+        center,
+        radius: outer_radius - width / 2.0,
+        width,
+    }
+}
+
+/// The lines between the squares of a board filling `rect`, `squares`
+/// squares a side
+pub fn grid_lines(rect: Rect, squares: usize, width: f64) -> Vec<Stroke> {
+    let [x, y, size, _] = rect;
+    let cell_size = size / squares as f64;
+    let stroke = |from: Point, to: Point| Stroke { from, to, width, square_ends: true };
+    (1..squares)
+        .flat_map(|i| {
+            let offset = i as f64 * cell_size;
+            vec![
+                stroke([x + offset, y], [x + offset, y + size]),
+                stroke([x, y + offset], [x + size, y + offset]),
+            ]
+        })
+        .collect()
+}
+
+/// The square `coord` takes up on a board filling `rect`
+pub fn cell_rect(rect: Rect, coord: Coord) -> Rect {
+    let [x, y, size, _] = rect;
+    let cell_size = size / BOARD_SIZE as f64;
+    [x + coord.col as f64 * cell_size, y + coord.row as f64 * cell_size, cell_size, cell_size]
+}
+
+/// A line through the centers of the squares of `line` on a board filling
+/// `rect`, running a little past the first and last square
+pub fn strike_through(rect: Rect, line: &Line) -> Stroke {
+    strike_between(rect, BOARD_SIZE, line.start(), line.end())
+}
+
+/// A line from the center of `start` to the center of `end` on a board
+/// filling `rect`, `squares` squares a side, running a little past both
+pub fn strike_between(rect: Rect, squares: usize, start: Coord, end: Coord) -> Stroke {
+    let overshoot = 0.35;
+    let [x, y, size, _] = rect;
+    let cell_size = size / squares as f64;
+    let center = |coord: Coord| [x + (coord.col as f64 + 0.5) * cell_size, y + (coord.row as f64 + 0.5) * cell_size];
+    let [x0, y0] = center(start);
+    let [x1, y1] = center(end);
+    // one square's step along the line
+    let steps = start.row.abs_diff(end.row).max(start.col.abs_diff(end.col)).max(1) as f64;
+    let (dx, dy) = ((x1 - x0) / steps, (y1 - y0) / steps);
+    Stroke {
+        from: [x0 - dx * overshoot, y0 - dy * overshoot],
+        to: [x1 + dx * overshoot, y1 + dy * overshoot],
+        width: cell_size / 12.0,
+        square_ends: true,
+    }
+}
+
+/// Something to draw, in the order it's listed in a scene
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Fill(Rect, Color),
+    /// The outline of a rectangle, drawn with a line this wide centred on
+    /// its edges
+    Outline(Rect, f64, Color),
+    Stroke(Stroke, Color),
+    Ring(Ring, Color),
+    /// Text this high, centred on a point. Images only draw digits.
+    Text(String, Point, f64, Color),
+}
+
+/// A picture as a list of shapes, which can be written as an SVG or drawn
+/// into an image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub shapes: Vec<Shape>,
+}
+
+/// How a board is drawn into an image
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderOptions {
+    /// The width and height of the image, in pixels
+    pub size: u32,
+    pub theme: Theme,
+    /// Number each piece by the move that played it
+    pub move_numbers: bool,
+    /// Outline the square of the last move
+    pub highlight_last_move: bool,
+    /// How long each position is shown in an animated GIF
+    pub frame_delay: Duration,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            size: 480,
+            theme: Theme::default(),
+            move_numbers: true,
+            highlight_last_move: true,
+            frame_delay: Duration::from_millis(800),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Reads `--size <pixels>`, `--theme <dark|light|high-contrast>` and
+    /// `--frame-delay <milliseconds>`
+    pub fn from_args(args: &[String]) -> Result<RenderOptions, String> {
+        let mut options = RenderOptions::default();
+        if let Some(i) = args.iter().position(|arg| arg == "--size") {
+            options.size = match args.get(i + 1).map(|size| size.parse::<u32>()) {
+                Some(Ok(size)) if (16..=4096).contains(&size) => size,
+                _ => return Err("--size needs a width in pixels from 16 to 4096".to_string()),
+            };
+        }
+        if let Some(i) = args.iter().position(|arg| arg == "--theme") {
+            options.theme = args.get(i + 1)
+                .ok_or_else(|| "--theme needs a theme, dark, light or high-contrast".to_string())?
+                .parse()?;
+        }
+        if let Some(i) = args.iter().position(|arg| arg == "--frame-delay") {
+            options.frame_delay = match args.get(i + 1).map(|millis| millis.parse::<u64>()) {
+                Some(Ok(millis)) if (10..=60_000).contains(&millis) => Duration::from_millis(millis),
+                _ => return Err("--frame-delay needs a time in milliseconds from 10 to 60000".to_string()),
+            };
+        }
+        Ok(options)
+    }
+}
+
+/// Draws `board`, with `moves` being the order its pieces were played in,
+/// if it's known, for the move numbers and the last move. A board with
+/// three in a row is drawn won, the same as in the GUI.
+pub fn board_scene(board: &Board, moves: &[Coord], options: &RenderOptions) -> Scene {
+    scene(board, moves, &board_status(board), options)
+}
+
+/// Draws the board of `game`, numbering the moves in its history
+pub fn game_scene(game: &Game, options: &RenderOptions) -> Scene {
+    let moves: Vec<Coord> = game.history.iter().map(|&(row, col, _)| Coord::new(row, col)).collect();
+    scene(&game.board, &moves, &game.status, options)
+}
+
+fn scene(board: &Board, moves: &[Coord], status: &GameStatus, options: &RenderOptions) -> Scene {
+    let palette = options.theme.palette();
+    let size = options.size as f64;
+    // the same margin the GUI leaves around its board
+    let margin = size * 0.05;
+    let rect = [margin, margin, size - 2.0 * margin, size - 2.0 * margin];
+    let cell_size = rect[2] / BOARD_SIZE as f64;
+
+    let mut shapes = vec![Shape::Fill([0.0, 0.0, size, size], palette.background)];
+
+    if let (Some(&last), true) = (moves.last(), options.highlight_last_move) {
+        let [x, y, width, height] = cell_rect(rect, last);
+        let pad = cell_size * 0.04;
+        let outline = [x + pad, y + pad, width - 2.0 * pad, height - 2.0 * pad];
+        shapes.push(Shape::Outline(outline, (cell_size / 80.0).max(1.5), palette.selected));
+    }
+
+    // the loser's pieces are greyed out, and both sides' in a tie
+    let (x_color, o_color) = match status {
+        GameStatus::Win(PlayerKind::PlayerX, _) => (palette.x, palette.loss),
+        GameStatus::Win(PlayerKind::PlayerO, _) => (palette.loss, palette.o),
+        GameStatus::Tie => (palette.loss, palette.loss),
+        GameStatus::InProgress => (palette.x, palette.o),
+    };
+    for (i, row) in board.iter().enumerate() {
+        for (j, &piece) in row.iter().enumerate() {
+            let cell = cell_rect(rect, Coord::new(i, j));
+            match piece {
+                Some(PlayerKind::PlayerX) => {
+                    shapes.extend(x_strokes(cell).iter().map(|&stroke| Shape::Stroke(stroke, x_color)));
+                },
+                Some(PlayerKind::PlayerO) => shapes.push(Shape::Ring(o_ring(cell), o_color)),
+                None => (),
+            }
+        }
+    }
+
+    if options.move_numbers {
+        for (number, &coord) in moves.iter().enumerate() {
+            let [x, y, _, _] = cell_rect(rect, coord);
+            let text_size = cell_size * 0.16;
+            let center = [x + cell_size * 0.14, y + cell_size * 0.16];
+            shapes.push(Shape::Text((number + 1).to_string(), center, text_size, palette.text));
+        }
+    }
+
+    let grid_width = (size / 160.0).max(1.0);
+    shapes.extend(grid_lines(rect, BOARD_SIZE, grid_width).into_iter().map(|stroke| Shape::Stroke(stroke, palette.grid)));
+
+    if let GameStatus::Win(player, lines) = status {
+        let color = match player {
+            PlayerKind::PlayerX => palette.x,
+            PlayerKind::PlayerO => palette.o,
+        };
+        shapes.extend(lines.iter().map(|line| Shape::Stroke(strike_through(rect, line), color)));
+    }
+
+    Scene { width: size, height: size, shapes }
+}
+
+/// The kinds of image a board can be saved as. A game saved as a GIF is
+/// animated, a frame to a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+    Gif,
+}
+
+impl Format {
+    /// The format named by the extension of `path`
+    pub fn from_path(path: &Path) -> Result<Format, String> {
+        match path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref() {
+            Some("svg") => Ok(Format::Svg),
+            Some("png") => Ok(Format::Png),
+            Some("gif") => Ok(Format::Gif),
+            _ => Err(format!("Expected a file ending in .svg, .png or .gif, found \"{}\"", path.display())),
+        }
+    }
+}
+
+/// The images asked for by `--export-svg <path>`, `--export-png <path>`
+/// and `--export-gif <path>`
+pub fn exports_from_args(args: &[String]) -> Result<Vec<(Format, PathBuf)>, String> {
+    let mut exports = Vec::new();
+    let flags = [("--export-svg", Format::Svg), ("--export-png", Format::Png), ("--export-gif", Format::Gif)];
+    for (flag, format) in &flags {
+        if let Some(i) = args.iter().position(|arg| arg == flag) {
+            let path = args.get(i + 1).ok_or_else(|| format!("{} needs a file to write", flag))?;
+            exports.push((*format, PathBuf::from(path)));
+        }
+    }
+    Ok(exports)
+}
+
+/// Writes `scene` to `path` as `format`
+pub fn save(scene: &Scene, format: Format, path: &Path) -> Result<(), String> {
+    let bytes = match format {
+        Format::Svg => scene.to_svg().into_bytes(),
+        Format::Png => scene.rasterize().encode_png()?,
+        Format::Gif => encode_gif(&[scene.rasterize()], Duration::from_millis(0))?,
+    };
+    fs::write(path, bytes).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+/// Writes `game` to `path` as `format`: its board as it stands, or every
+/// position from the first move to the last for a GIF
+pub fn save_game(game: &Game, format: Format, path: &Path, options: &RenderOptions) -> Result<(), String> {
+    if format != Format::Gif {
+        return save(&game_scene(game, options), format, path);
+    }
+    let frames: Vec<Image> = game_frames(game, options).iter().map(Scene::rasterize).collect();
+    let bytes = encode_gif(&frames, options.frame_delay)?;
+    fs::write(path, bytes).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
@@ -0,0 +1,170 @@
+use super::{Color, Point, Rect, Scene, Shape};
+
+/// Each digit drawn as 3 by 5 blocks, a row to a number with the left block
+/// in the highest bit
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// A picture as rows of pixels from the top, each pixel being red, green,
+/// blue and opacity bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// Mixes `color` into the pixel at `x`, `y`, `coverage` being how much of
+    /// the pixel it covers, from 0 to 1
+    fn blend(&mut self, x: u32, y: u32, color: Color, coverage: f64) {
+        let alpha = color[3] as f64 * coverage;
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        for (channel, &value) in color[..3].iter().enumerate() {
+            let old = self.pixels[i + channel] as f64;
+            self.pixels[i + channel] = (old + (value as f64 * 255.0 - old) * alpha).round() as u8;
+        }
+        let old = self.pixels[i + 3] as f64;
+        self.pixels[i + 3] = (old + (255.0 - old) * alpha).round() as u8;
+    }
+
+    /// Fills the shape whose signed distance from each point is given by
+    /// `distance`, negative inside, anywhere within `bounds`. Edges are
+    /// smoothed over a pixel.
+    fn fill(&mut self, bounds: Rect, color: Color, distance: impl Fn(Point) -> f64) {
+        let [x, y, width, height] = bounds;
+        let clamp = |value: f64, max: u32| value.max(0.0).min(max as f64) as u32;
+        let (x0, x1) = (clamp(x - 1.0, self.width), clamp((x + width + 1.0).ceil(), self.width));
+        let (y0, y1) = (clamp(y - 1.0, self.height), clamp((y + height + 1.0).ceil(), self.height));
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let coverage = (0.5 - distance([px as f64 + 0.5, py as f64 + 0.5])).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend(px, py, color, coverage);
+                }
+            }
+        }
+    }
+
+    /// The image as a PNG file
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|e| format!("Couldn't encode the PNG: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+/// How far `point` is from the edge of the box around `center` with
+/// `half_size` along `axis` and across it, negative inside
+fn box_distance(point: Point, center: Point, axis: Point, half_size: [f64; 2]) -> f64 {
+    let (dx, dy) = (point[0] - center[0], point[1] - center[1]);
+    let along = (dx * axis[0] + dy * axis[1]).abs() - half_size[0];
+    let across = (dy * axis[0] - dx * axis[1]).abs() - half_size[1];
+    along.max(0.0).hypot(across.max(0.0)) + along.max(across).min(0.0)
+}
+
+fn rect_distance(point: Point, rect: Rect) -> f64 {
+    let [x, y, width, height] = rect;
+    box_distance(point, [x + width / 2.0, y + height / 2.0], [1.0, 0.0], [width / 2.0, height / 2.0])
+}
+
+fn grow(rect: Rect, by: f64) -> Rect {
+    let [x, y, width, height] = rect;
+    [x - by, y - by, width + 2.0 * by, height + 2.0 * by]
+}
+
+/// Draws `text` centred on `center`, `size` high, in blocks. Only digits
+/// are drawn, and anything else is left as a gap.
+fn draw_digits(image: &mut Image, text: &str, center: Point, size: f64, color: Color) {
+    let block = size / 5.0;
+    let advance = block * 4.0;
+    let width = advance * text.chars().count() as f64 - block;
+    let (left, top) = (center[0] - width / 2.0, center[1] - size / 2.0);
+
+    for (i, c) in text.chars().enumerate() {
+        let rows = match c.to_digit(10) {
+            Some(digit) => DIGITS[digit as usize],
+            None => continue,
+        };
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) != 0 {
+                    let rect = [left + i as f64 * advance + column as f64 * block, top + row as f64 * block,
+                                block, block];
+                    image.fill(rect, color, |point| rect_distance(point, rect));
+                }
+            }
+        }
+    }
+}
+
+impl Scene {
+    /// Draws the scene into an image of its size, one pixel to a unit
+    pub fn rasterize(&self) -> Image {
+        let mut image = Image::new(self.width.ceil() as u32, self.height.ceil() as u32);
+        for shape in &self.shapes {
+            match shape {
+                Shape::Fill(rect, color) => image.fill(*rect, *color, |point| rect_distance(point, *rect)),
+                Shape::Outline(rect, width, color) => {
+                    image.fill(grow(*rect, width / 2.0), *color, |point| {
+                        rect_distance(point, *rect).abs() - width / 2.0
+                    });
+                },
+                Shape::Stroke(stroke, color) => {
+                    let corners = stroke.corners();
+                    let xs = corners.iter().map(|corner| corner[0]);
+                    let ys = corners.iter().map(|corner| corner[1]);
+                    let (x0, x1) = (xs.clone().fold(f64::MAX, f64::min), xs.fold(f64::MIN, f64::max));
+                    let (y0, y1) = (ys.clone().fold(f64::MAX, f64::min), ys.fold(f64::MIN, f64::max));
+
+                    let [from, to] = [stroke.from, stroke.to];
+                    let length = (to[0] - from[0]).hypot(to[1] - from[1]).max(f64::EPSILON);
+                    let axis = [(to[0] - from[0]) / length, (to[1] - from[1]) / length];
+                    let center = [(from[0] + to[0]) / 2.0, (from[1] + to[1]) / 2.0];
+                    let extend = if stroke.square_ends { stroke.width / 2.0 } else { 0.0 };
+                    let half_size = [length / 2.0 + extend, stroke.width / 2.0];
+                    image.fill([x0, y0, x1 - x0, y1 - y0], *color, |point| {
+                        box_distance(point, center, axis, half_size)
+                    });
+                },
+                Shape::Ring(ring, color) => {
+                    let [x, y] = ring.center;
+                    let outer = ring.radius + ring.width / 2.0;
+                    image.fill([x - outer, y - outer, 2.0 * outer, 2.0 * outer], *color, |point| {
+                        ((point[0] - x).hypot(point[1] - y) - ring.radius).abs() - ring.width / 2.0
+                    });
+                },
+                Shape::Text(text, center, size, color) => draw_digits(&mut image, text, *center, *size, *color),
+            }
+        }
+        image
+    }
+}
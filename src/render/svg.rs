@@ -0,0 +1,70 @@
+use std::fmt::Write;
+
+use super::{Color, Scene, Shape};
+
+/// A number with at most two decimals and no trailing zeros
+fn number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// `attribute` set to `color`, with its opacity alongside unless it's solid
+fn paint(attribute: &str, color: Color) -> String {
+    let [r, g, b, a] = color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+    let mut text = format!("{}=\"#{:02x}{:02x}{:02x}\"", attribute, r, g, b);
+    if a < 255 {
+        text += &format!(" {}-opacity=\"{}\"", attribute, number(color[3] as f64));
+    }
+    text
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+impl Scene {
+    /// The scene as an SVG document
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (number(self.width), number(self.height));
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+                               viewBox=\"0 0 {} {}\">\n", width, height, width, height);
+        for shape in &self.shapes {
+            // writing to a string can't fail
+            let _ = match shape {
+                Shape::Fill([x, y, width, height], color) => {
+                    writeln!(svg, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>", number(*x), number(*y),
+                             number(*width), number(*height), paint("fill", *color))
+                },
+                Shape::Outline([x, y, width, height], line_width, color) => {
+                    writeln!(svg, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" {} \
+                                   stroke-width=\"{}\"/>", number(*x), number(*y), number(*width),
+                             number(*height), paint("stroke", *color), number(*line_width))
+                },
+                Shape::Stroke(stroke, color) => {
+                    let cap = if stroke.square_ends { "square" } else { "butt" };
+                    writeln!(svg, "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {} stroke-width=\"{}\" \
+                                   stroke-linecap=\"{}\"/>", number(stroke.from[0]), number(stroke.from[1]),
+                             number(stroke.to[0]), number(stroke.to[1]), paint("stroke", *color),
+                             number(stroke.width), cap)
+                },
+                Shape::Ring(ring, color) => {
+                    writeln!(svg, "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" {} stroke-width=\"{}\"/>",
+                             number(ring.center[0]), number(ring.center[1]), number(ring.radius),
+                             paint("stroke", *color), number(ring.width))
+                },
+                Shape::Text(text, [x, y], size, color) => {
+                    writeln!(svg, "  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" \
+                                   text-anchor=\"middle\" dominant-baseline=\"central\" {}>{}</text>",
+                             number(*x), number(*y), number(*size), paint("fill", *color), escape(text))
+                },
+            };
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+use crate::{parse_board_string, GameConfig};
+
+fn args(args: &str) -> Vec<String> {
+    args.split_whitespace().map(String::from).collect()
+}
+
+fn to_bytes(color: Color) -> [u8; 4] {
+    color.map(|channel| (channel * 255.0).round() as u8)
+}
+
+#[test]
+fn piece_geometry_test() {
+    let [first, second] = x_strokes([0.0, 0.0, 100.0, 100.0]);
+    assert_eq!(first.width, 12.5);
+    assert!((first.from[0] - (50.0 - 40.0 * FRAC_1_SQRT_2)).abs() < 1e-9);
+    assert_eq!((first.from[1], second.from[1]), (first.from[0], first.from[0]));
+    assert_eq!(first.partial(0.5).to, [50.0, 50.0]);
+
+    let ring = o_ring([100.0, 0.0, 100.0, 100.0]);
+    assert_eq!(ring.center, [150.0, 50.0]);
+    assert!((ring.radius + ring.width / 2.0 - 40.0).abs() < 1e-9);
+
+    let lines = grid_lines([0.0, 0.0, 90.0, 90.0], BOARD_SIZE, 2.0);
+    assert_eq!(lines.len(), 4);
+    assert_eq!((lines[0].from, lines[0].to), ([30.0, 0.0], [30.0, 90.0]));
+
+    // a strike through four squares of a 5x5 board runs a little past them
+    let strike = strike_between([0.0, 0.0, 100.0, 100.0], 5, Coord::new(0, 0), Coord::new(3, 3));
+    assert_eq!((strike.from, strike.to), ([3.0, 3.0], [77.0, 77.0]));
+}
+
+#[test]
+fn board_scene_test() {
+    let board = parse_board_string("XXX/OO./...").unwrap();
+    let moves: Vec<Coord> = ["a1", "a2", "b1", "b2", "c1"].iter().map(|square| square.parse().unwrap()).collect();
+    let scene = board_scene(&board, &moves, &RenderOptions::default());
+    let palette = Theme::Dark.palette();
+
+    assert_eq!(scene.shapes[0], Shape::Fill([0.0, 0.0, 480.0, 480.0], palette.background));
+    assert!(matches!(scene.shapes[1], Shape::Outline(_, _, color) if color == palette.selected));
+    // O lost, so its pieces are greyed out
+    assert!(scene.shapes.iter().any(|shape| matches!(shape, Shape::Ring(_, color) if *color == palette.loss)));
+    let numbers: Vec<&str> = scene.shapes.iter()
+        .filter_map(|shape| match shape {
+            Shape::Text(text, _, _, _) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(numbers, ["1", "2", "3", "4", "5"]);
+    assert!(matches!(scene.shapes.last(), Some(Shape::Stroke(stroke, color))
+                     if stroke.square_ends && *color == palette.x));
+
+    let plain = RenderOptions { move_numbers: false, highlight_last_move: false, ..RenderOptions::default() };
+    let scene = board_scene(&parse_board_string(".../.X./...").unwrap(), &[], &plain);
+    assert_eq!(scene.shapes.len(), 1 + 2 + 4);
+}
+
+#[test]
+fn svg_test() {
+    let board = parse_board_string("X../.O./...").unwrap();
+    let svg = board_scene(&board, &[Coord::new(0, 0), Coord::new(1, 1)], &RenderOptions::default()).to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"480\" height=\"480\""));
+    assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"480\" height=\"480\" fill=\"#333333\"/>"));
+    assert!(svg.contains("stroke=\"#ff0000\" stroke-width=\"18\" stroke-linecap=\"butt\""));
+    assert!(svg.contains("<circle cx=\"240\" cy=\"240\" r=\"52.8\" fill=\"none\" stroke=\"#0000ff\""));
+    assert!(svg.contains(">2</text>"));
+    assert!(svg.ends_with("</svg>\n"));
+}
+
+#[test]
+fn rasterize_test() {
+    let board = parse_board_string("X../.O./...").unwrap();
+    let options = RenderOptions { size: 90, ..RenderOptions::default() };
+    let image = board_scene(&board, &[], &options).rasterize();
+    let palette = options.theme.palette();
+    assert_eq!((image.width, image.height, image.pixels.len()), (90, 90, 90 * 90 * 4));
+
+    // the corner and the middle of the O are background, while the middle
+    // of the X and the ring of the O are drawn
+    assert_eq!(image.pixel(0, 0), to_bytes(palette.background));
+    assert_eq!(image.pixel(45, 45), to_bytes(palette.background));
+    assert_eq!(image.pixel(18, 18), to_bytes(palette.x));
+    assert_eq!(image.pixel(45, 35), to_bytes(palette.o));
+
+    let png = image.encode_png().unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+fn gif_test() {
+    let moves: Vec<Coord> = ["a1", "b2", "a2", "c3", "a3"].iter().map(|square| square.parse().unwrap()).collect();
+    let mut game = GameConfig::new().build();
+    for coord in &moves {
+        game.make_move(coord.row, coord.col);
+    }
+    let options = RenderOptions { size: 60, ..RenderOptions::default() };
+    let frames = game_frames(&game, &options);
+    assert_eq!(frames.len(), 6);
+    // the empty board is only the background and the grid
+    assert_eq!(frames[0].shapes.len(), 1 + 4);
+    assert_eq!(frames[5], game_scene(&game, &options));
+    let board = parse_board_string("X../XO./..O").unwrap();
+    assert_eq!(frames[4], board_scene(&board, &moves[..4], &options));
+
+    let images: Vec<Image> = frames.iter().map(Scene::rasterize).collect();
+    let bytes = encode_gif(&images, Duration::from_millis(500)).unwrap();
+    let mut decoder = gif::DecodeOptions::new().read_info(bytes.as_slice()).unwrap();
+    let mut delays = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!((frame.width, frame.height), (60, 60));
+        delays.push(frame.delay);
+    }
+    assert_eq!(delays, [50, 50, 50, 50, 50, 150]);
+
+    assert!(encode_gif(&[], Duration::from_millis(500)).is_err());
+    assert!(encode_gif(&[Image::new(10, 10), Image::new(20, 10)], Duration::from_millis(500)).is_err());
+}
+
+#[test]
+fn from_args_test() {
+    let options = RenderOptions::from_args(&args("cli --size 200 --theme light")).unwrap();
+    assert_eq!((options.size, options.theme), (200, Theme::Light));
+    assert_eq!(options.frame_delay, Duration::from_millis(800));
+    assert_eq!(RenderOptions::from_args(&args("cli --frame-delay 250")).unwrap().frame_delay,
+               Duration::from_millis(250));
+    assert!(RenderOptions::from_args(&args("cli --frame-delay soon")).is_err());
+    assert!(RenderOptions::from_args(&args("cli --size 2")).is_err());
+    assert!(RenderOptions::from_args(&args("cli --theme neon")).is_err());
+
+    assert_eq!(exports_from_args(&args("cli --export-gif c.gif --export-png b.png --export-svg a.svg")),
+               Ok(vec![(Format::Svg, PathBuf::from("a.svg")), (Format::Png, PathBuf::from("b.png")),
+                       (Format::Gif, PathBuf::from("c.gif"))]));
+    assert!(exports_from_args(&args("cli --export-svg")).is_err());
+    assert_eq!(Format::from_path(Path::new("board.PNG")), Ok(Format::Png));
+    assert_eq!(Format::from_path(Path::new("game.gif")), Ok(Format::Gif));
+    assert!(Format::from_path(Path::new("board.jpg")).is_err());
+}
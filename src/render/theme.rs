@@ -0,0 +1,107 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The colours boards are drawn with, in the GUI and in images
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    pub background: [f32; 4],
+    /// The status bar's background
+    pub bar: [f32; 4],
+    pub grid: [f32; 4],
+    pub text: [f32; 4],
+    pub x: [f32; 4],
+    pub o: [f32; 4],
+    /// Pieces of the losing side, and of both sides in a tie
+    pub loss: [f32; 4],
+    /// The fill of an option under the cursor or picked with the keys
+    pub hover: [f32; 4],
+    /// The border of a chosen option
+    pub selected: [f32; 4],
+    /// Buttons that start a game
+    pub accent: [f32; 4],
+    /// Messages in the status bar
+    pub message: [f32; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::HighContrast];
+
+    pub fn palette(&self) -> Palette {
+        match self {
+            Theme::Dark => Palette {
+                background: [51.0/255.0, 51.0/255.0, 51.0/255.0, 1.0],
+                bar: [35.0/255.0, 35.0/255.0, 35.0/255.0, 1.0],
+                grid: [150.0/255.0, 150.0/255.0, 150.0/255.0, 1.0],
+                text: [220.0/255.0, 220.0/255.0, 220.0/255.0, 1.0],
+                x: [1.0, 0.0, 0.0, 1.0],
+                o: [0.0, 0.0, 1.0, 1.0],
+                loss: [0.5, 0.5, 0.5, 1.0],
+                hover: [90.0/255.0, 90.0/255.0, 90.0/255.0, 1.0],
+                selected: [220.0/255.0, 220.0/255.0, 220.0/255.0, 1.0],
+                accent: [0.2, 0.8, 0.2, 1.0],
+                message: [1.0, 0.8, 0.2, 1.0],
+            },
+            Theme::Light => Palette {
+                background: [245.0/255.0, 242.0/255.0, 235.0/255.0, 1.0],
+                bar: [225.0/255.0, 220.0/255.0, 210.0/255.0, 1.0],
+                grid: [90.0/255.0, 90.0/255.0, 90.0/255.0, 1.0],
+                text: [30.0/255.0, 30.0/255.0, 30.0/255.0, 1.0],
+                x: [0.85, 0.15, 0.15, 1.0],
+                o: [0.1, 0.3, 0.85, 1.0],
+                loss: [0.7, 0.7, 0.7, 1.0],
+                hover: [200.0/255.0, 195.0/255.0, 185.0/255.0, 1.0],
+                selected: [30.0/255.0, 30.0/255.0, 30.0/255.0, 1.0],
+                accent: [0.1, 0.6, 0.2, 1.0],
+                message: [0.75, 0.4, 0.0, 1.0],
+            },
+            // black and white with bright yellow and cyan pieces, which stay
+            // apart for red-green colour blindness too
+            Theme::HighContrast => Palette {
+                background: [0.0, 0.0, 0.0, 1.0],
+                bar: [0.0, 0.0, 0.0, 1.0],
+                grid: [1.0, 1.0, 1.0, 1.0],
+                text: [1.0, 1.0, 1.0, 1.0],
+                x: [1.0, 0.9, 0.0, 1.0],
+                o: [0.0, 0.9, 1.0, 1.0],
+                loss: [0.45, 0.45, 0.45, 1.0],
+                hover: [0.3, 0.3, 0.3, 1.0],
+                selected: [1.0, 1.0, 1.0, 1.0],
+                accent: [0.0, 1.0, 0.3, 1.0],
+                message: [1.0, 0.5, 1.0, 1.0],
+            },
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::HighContrast => "high-contrast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Theme, String> {
+        Theme::ALL.iter()
+            .find(|theme| theme.to_string() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("Expected a theme, dark, light or high-contrast, found \"{}\"", s))
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::bot::{self, expectimax};
+use super::net;
+use super::records::{GameRecord, RecordStore};
+use super::{Coord, Game, GameConfig, GameStatus, PlayerKind};
+
+#[cfg(test)]
+mod tests;
+
+/// How long autoplay shows each position, unless `--speed` says otherwise
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(1000);
+
+/// How a position ends with best play from both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Outcome {
+    Win(PlayerKind),
+    Draw,
+}
+
+impl Outcome {
+    /// How good the outcome is for `player`, higher being better
+    fn rank(&self, player: PlayerKind) -> u8 {
+        match self {
+            Outcome::Win(winner) if *winner == player => 2,
+            Outcome::Draw => 1,
+            Outcome::Win(_) => 0,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Win(player) => write!(f, "{} wins", player.to_char()),
+            Outcome::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// The game as it stood after some number of moves, with the bot's view of
+/// it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ply {
+    pub game: Game,
+    /// The move that led here and who made it, or `None` at the start
+    pub last_move: Option<(Coord, PlayerKind)>,
+    /// The bot's score for the player to move, from `expectimax::evaluate`,
+    /// or `None` once the game is over
+    pub evaluation: Option<f64>,
+    pub outcome: Outcome,
+    /// The bot's choice for the player to move, while the game goes on
+    pub best_move: Option<Coord>,
+    /// Whether the move that led here changed the outcome against the
+    /// player who made it
+    pub blunder: bool,
+}
+
+impl Ply {
+    fn new(game: &Game, last_move: Option<(Coord, PlayerKind)>, previous: Option<&Ply>) -> Ply {
+        let (evaluation, outcome, best_move) = match &game.status {
+            GameStatus::InProgress => {
+                let player = game.current_player;
+                let score = expectimax::evaluate(player, &game.board, &game.ruleset);
+                let outcome = if score > 0.0 {
+                    Outcome::Win(player)
+                } else if score < 0.0 {
+                    Outcome::Win(player.opposite())
+                } else {
+                    Outcome::Draw
+                };
+                (Some(score), outcome, Some(bot::get_game_move(game).into()))
+            },
+            GameStatus::Win(player, _) => (None, Outcome::Win(*player), None),
+            GameStatus::Tie => (None, Outcome::Draw, None),
+        };
+        let blunder = match (last_move, previous) {
+            (Some((_, player)), Some(previous)) => outcome.rank(player) < previous.outcome.rank(player),
+            _ => false,
+        };
+
+        Ply {
+            game: game.clone(),
+            last_move,
+            evaluation,
+            outcome,
+            best_move,
+            blunder,
+        }
+    }
+}
+
+/// A finished list of moves, rebuilt a move at a time so it can be stepped
+/// through in either direction
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    /// Every position from the empty board to the last move
+    pub plies: Vec<Ply>,
+    /// The ply on show, 0 being the empty board
+    pub current: usize,
+}
+
+impl Replay {
+    /// Plays `moves` from the start of a game set up by `config`, with both
+    /// sides left to the moves. Each move is made by the player given with
+    /// it, or else by whoever the rules give the turn, the players taking
+    /// turns where the rules would flip a coin, so no coins are flipped.
+    /// Fails at the first move that couldn't have been played.
+    pub fn new(config: GameConfig, moves: &[(Coord, Option<PlayerKind>)]) -> Result<Replay, String> {
+        let mut game = config.human(PlayerKind::PlayerX, true).human(PlayerKind::PlayerO, true).build();
+        // the first mover is the configured one, not a coin flip's
+        game.current_player = moves.first().and_then(|&(_, player)| player).unwrap_or(game.starting_player);
+        let mut plies = vec![Ply::new(&game, None, None)];
+        for (i, &(coord, _)) in moves.iter().enumerate() {
+            let player = game.current_player;
+            net::check_move(&game, player, coord).map_err(|e| format!("Move {}, {}: {}", i + 1, coord, e))?;
+            game.place(coord.row, coord.col, player);
+            game.current_player = match moves.get(i + 1) {
+                Some(&(_, Some(next))) => next,
+                _ => next_player(&game, player),
+            };
+            let ply = Ply::new(&game, Some((coord, player)), plies.last());
+            plies.push(ply);
+        }
+        Ok(Replay { plies, current: 0 })
+    }
+
+    /// Replays a game from the record store, under the rules it was played
+    /// by
+    pub fn from_record(record: &GameRecord) -> Result<Replay, String> {
+        let moves: Vec<(Coord, Option<PlayerKind>)> = record.moves.iter()
+            .map(|&(square, player)| (square, Some(player)))
+            .collect();
+        Replay::new(GameConfig::new().ruleset(record.ruleset.clone()).first_player(record.first), &moves)
+    }
+
+    /// Reads the game to replay from the command line: either
+    /// `--replay "<square>..."`, with the squares separated by spaces, by
+    /// file and rank like `b2` or as row,col like `2,2`, and each may start
+    /// with the player who moved there, like `Xb2`, played from the start of
+    /// a game set up by
+    /// the other arguments, or `--replay-game <n>`, the nth most recent game
+    /// in the record store, or in the records file given by
+    /// `--records <file>`. Returns `None` if neither is given.
+    pub fn from_args(args: &[String]) -> Result<Option<Replay>, String> {
+        if let Some(i) = args.iter().position(|arg| arg == "--replay") {
+            let moves = args.get(i + 1).ok_or_else(|| "--replay needs a list of moves like \"b2 a1 c3\"".to_string())?;
+            let moves: Vec<(Coord, Option<PlayerKind>)> = moves.split_whitespace()
+                .map(|square| match square.chars().next().map(|c| c.to_ascii_uppercase()) {
+                    Some('X') => Ok((square[1..].parse()?, Some(PlayerKind::PlayerX))),
+                    Some('O') => Ok((square[1..].parse()?, Some(PlayerKind::PlayerO))),
+                    _ => Ok((square.parse()?, None)),
+                })
+                .collect::<Result<_, String>>()?;
+            return Replay::new(GameConfig::from_args(args)?, &moves).map(Some);
+        }
+
+        if let Some(i) = args.iter().position(|arg| arg == "--replay-game") {
+            let n = match args.get(i + 1).map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => return Err("--replay-game needs a game number, 1 for the most recent".to_string()),
+            };
+            let store = match args.iter().position(|arg| arg == "--records") {
+                Some(i) => {
+                    let path = args.get(i + 1).ok_or_else(|| "--records needs a file of recorded games".to_string())?;
+                    Some(RecordStore::new(PathBuf::from(path)))
+                },
+                None => RecordStore::open_default(),
+            };
+            let records = match store {
+                Some(store) => store.load()?.records,
+                None => Vec::new(),
+            };
+            let record = records.iter().rev().nth(n - 1)
+                .ok_or_else(|| format!("There are only {} recorded games", records.len()))?;
+            return Replay::from_record(record).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    pub fn ply(&self) -> &Ply {
+        &self.plies[self.current]
+    }
+
+    /// The number of the last ply, once every move has been played
+    pub fn last(&self) -> usize {
+        self.plies.len() - 1
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.current == self.last()
+    }
+
+    /// Steps to the next ply, returning false if there isn't one
+    pub fn forward(&mut self) -> bool {
+        let moved = !self.at_end();
+        self.jump(self.current + 1);
+        moved
+    }
+
+    /// Steps to the previous ply, returning false at the start
+    pub fn back(&mut self) -> bool {
+        let moved = self.current > 0;
+        self.jump(self.current.saturating_sub(1));
+        moved
+    }
+
+    /// Shows `ply`, or the last ply if there aren't that many
+    pub fn jump(&mut self, ply: usize) {
+        self.current = ply.min(self.last());
+    }
+}
+
+/// Who moves after `player` in `game` when the moves don't say: whoever the
+/// rules give the turn, or the other player when it's down to a coin flip
+fn next_player(game: &Game, player: PlayerKind) -> PlayerKind {
+    match game.ruleset.next_player_odds(player, &game.board).as_slice() {
+        [(next, _)] => *next,
+        _ => player.opposite(),
+    }
+}
+
+/// How long autoplay shows each position, from `--speed <milliseconds>`
+pub fn delay_from_args(args: &[String]) -> Result<Duration, String> {
+    match args.iter().position(|arg| arg == "--speed") {
+        Some(i) => match args.get(i + 1).map(|millis| millis.parse::<u64>()) {
+            Some(Ok(millis)) => Ok(Duration::from_millis(millis)),
+            _ => Err("--speed needs a time in milliseconds".to_string()),
+        },
+        None => Ok(DEFAULT_DELAY),
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::SystemTime;
+use std::{env, fs, process};
+
+use super::*;
+use crate::{Handicap, Ruleset, TurnOrder};
+
+use PlayerKind::*;
+
+fn squares(squares: &str) -> Vec<(Coord, Option<PlayerKind>)> {
+    squares.split_whitespace().map(|square| (square.parse().unwrap(), None)).collect()
+}
+
+fn args(args: &str) -> Vec<String> {
+    args.split_whitespace().map(String::from).collect()
+}
+
+/// `args` followed by `--replay` and the moves as one argument, as a shell
+/// passes them when they're quoted
+fn replay_args(args_before: &str, moves: &str) -> Vec<String> {
+    let mut args = args(args_before);
+    args.push("--replay".to_string());
+    args.push(moves.to_string());
+    args
+}
+
+#[test]
+fn positions_test() {
+    let replay = Replay::new(GameConfig::new(), &squares("a1 a2 b1 b2 c1")).unwrap();
+    assert_eq!(replay.last(), 5);
+    assert_eq!(replay.plies[0].last_move, None);
+    assert_eq!(replay.plies[2].last_move, Some((Coord::new(1, 0), PlayerO)));
+    assert_eq!(replay.plies[2].game.history.len(), 2);
+
+    let end = &replay.plies[5];
+    assert!(matches!(end.game.status, GameStatus::Win(PlayerX, _)));
+    assert_eq!((end.evaluation, end.best_move, end.outcome), (None, None, Outcome::Win(PlayerX)));
+    assert_eq!(replay.plies[0].evaluation, Some(0.0));
+}
+
+#[test]
+fn blunder_test() {
+    // answering the center with a side square loses, while X keeps the
+    // win from there on
+    let replay = Replay::new(GameConfig::new(), &squares("b2 b1 a1 c3 a3")).unwrap();
+    let blunders: Vec<bool> = replay.plies.iter().map(|ply| ply.blunder).collect();
+    assert_eq!(blunders, [false, false, true, false, false, false]);
+    assert_eq!(replay.plies[2].outcome, Outcome::Win(PlayerX));
+    assert!(replay.plies[2].evaluation.unwrap() > 0.0);
+    assert_eq!(replay.plies[2].outcome.to_string(), "X wins");
+}
+
+#[test]
+fn stepping_test() {
+    let mut replay = Replay::new(GameConfig::new().first_player(PlayerO), &squares("b2 a1 c3")).unwrap();
+    assert_eq!(replay.plies[1].last_move, Some((Coord::new(1, 1), PlayerO)));
+
+    assert!(!replay.back());
+    assert!(replay.forward());
+    assert_eq!(replay.current, 1);
+    replay.jump(10);
+    assert!(replay.at_end() && replay.current == 3);
+    assert!(!replay.forward());
+    assert!(replay.back());
+    assert_eq!(replay.ply().game.history.len(), 2);
+}
+
+#[test]
+fn recorded_rules_test() {
+    // a random-turn game where O moved twice running is replayed as it was
+    // played, whatever the coins would say now
+    let ruleset = Ruleset { turn_order: TurnOrder::Random, handicap: Handicap::Stones(PlayerX, vec![(0, 0)]) };
+    let mut game = GameConfig::new().ruleset(ruleset.clone()).human(PlayerO, true).build();
+    for &(row, col, player) in &[(1, 1, PlayerO), (0, 1, PlayerO), (2, 2, PlayerX), (2, 1, PlayerO)] {
+        game.current_player = player;
+        game.make_move(row, col);
+    }
+    assert_eq!(game.status.winner(), Some(PlayerO));
+    let record = GameRecord::new(&game, "ann", "bob", SystemTime::now()).unwrap();
+
+    for _ in 0..10 {
+        let replay = Replay::from_record(&record).unwrap();
+        assert_eq!(replay.plies[0].game.ruleset, ruleset);
+        assert_eq!(replay.plies[0].game.board[0][0], Some(PlayerX));
+        assert_eq!(replay.plies[0].game.current_player, PlayerO);
+        assert_eq!(replay.plies[2].game.current_player, PlayerX);
+        let end = &replay.plies[replay.last()];
+        assert_eq!(end.game.board, game.board);
+        assert_eq!(end.game.history, game.history);
+        assert_eq!(end.outcome, Outcome::Win(PlayerO));
+    }
+}
+
+#[test]
+fn from_args_test() {
+    let replay = Replay::from_args(&replay_args("cli --first O", "b2 a1")).unwrap().unwrap();
+    assert_eq!(replay.plies[2].last_move, Some((Coord::new(0, 0), PlayerX)));
+    assert!(Replay::from_args(&args("cli")).unwrap().is_none());
+
+    // squares can be written as row,col too, so moves are only split on
+    // spaces
+    let replay = Replay::from_args(&replay_args("cli", "2,2 1,1 X3,3")).unwrap().unwrap();
+    let moves: Vec<_> = replay.plies.iter().skip(1).map(|ply| ply.last_move.unwrap()).collect();
+    assert_eq!(moves, [(Coord::new(1, 1), PlayerX), (Coord::new(0, 0), PlayerO), (Coord::new(2, 2), PlayerX)]);
+
+    assert_eq!(Replay::from_args(&replay_args("cli", "b2 b2")).err(), Some("Move 2, b2: b2 is taken".to_string()));
+    assert!(Replay::from_args(&replay_args("cli", "b2 z9")).is_err());
+    assert!(Replay::from_args(&args("cli --replay-game 0")).is_err());
+
+    let replay = Replay::from_args(&replay_args("cli --random-turn", "Ob2 Oa1 c3")).unwrap().unwrap();
+    let movers: Vec<_> = replay.plies.iter().skip(1).map(|ply| ply.last_move.unwrap().1).collect();
+    assert_eq!(movers, [PlayerO, PlayerO, PlayerX]);
+
+    let path = env::temp_dir().join(format!("tic-tac-torture-replay-{}.redb", process::id()));
+    let store = RecordStore::new(path.clone());
+    for moves in &["a1 b1 a2 b2 a3", "a1 b2 c3 b1 b3 a3 c1 c2 a2"] {
+        let mut game = GameConfig::new().build();
+        for (coord, _) in squares(moves) {
+            game.make_move(coord.row, coord.col);
+        }
+        store.append(&GameRecord::new(&game, "ann", "bob", SystemTime::now()).unwrap()).unwrap();
+    }
+    let replay_game = |n: usize| Replay::from_args(&args(&format!("cli --replay-game {} --records {}", n, path.display())));
+    assert_eq!(replay_game(1).unwrap().unwrap().last(), 9);
+    assert_eq!(replay_game(2).unwrap().unwrap().last(), 5);
+    assert!(replay_game(3).is_err());
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(delay_from_args(&args("cli --speed 250")), Ok(Duration::from_millis(250)));
+    assert_eq!(delay_from_args(&args("cli")), Ok(DEFAULT_DELAY));
+    assert!(delay_from_args(&args("cli --speed fast")).is_err());
+}
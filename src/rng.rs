@@ -0,0 +1,54 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small xorshift* random number generator, good enough for coin flips
+/// and picking moves without pulling in a dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rng {
+    state: u64,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rng {
+    /// Seeds the generator from the system clock
+    pub fn new() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Rng::from_seed(nanos)
+    }
+
+    pub fn from_seed(seed: u64) -> Rng {
+        const MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+        // the state must never be zero, or every output would be zero
+        let state = seed ^ MIX;
+        Rng {
+            state: if state == 0 { MIX } else { state },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn coin_flip(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// Returns a number in the range [0, n). `n` must be above zero, as
+    /// there's no number below zero to return.
+    pub fn below(&mut self, n: usize) -> usize {
+        debug_assert!(n > 0, "Rng::below needs a bound above zero.");
+        (self.next_u64() % n as u64) as usize
+    }
+}
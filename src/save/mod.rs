@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::Game;
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the saved game format written by this build. It goes up
+/// whenever a change to `Game` or the types in it changes the format.
+pub const SAVE_VERSION: u32 = 1;
+
+/// A game as written to a save file, or sent between programs, tagged with
+/// the version of the format it was written in
+/// The module is only built with the `serde` feature, so unlike the other
+/// types this derives serde's traits outright.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub game: Game,
+}
+
+impl SavedGame {
+    pub fn new(game: Game) -> SavedGame {
+        SavedGame {
+            version: SAVE_VERSION,
+            game,
+        }
+    }
+
+    /// The saved game, as long as it was written in a version this build
+    /// can read
+    pub fn into_game(self) -> Result<Game, String> {
+        check_version(self.version.into())?;
+        Ok(self.game)
+    }
+
+    /// Reads a saved game from JSON. The version is read first, so a game
+    /// saved in another version is refused as such, even where its `game`
+    /// wouldn't parse as this build's `Game`.
+    pub fn from_json(text: &str) -> Result<Game, String> {
+        let mut saved: serde_json::Value = serde_json::from_str(text).map_err(|e| format!("Not a saved game: {}", e))?;
+        let version = saved.get("version").and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| "The saved game has no version".to_string())?;
+        check_version(version)?;
+        serde_json::from_value(saved["game"].take()).map_err(|e| format!("Couldn't read the saved game: {}", e))
+    }
+}
+
+fn check_version(version: u64) -> Result<(), String> {
+    if version == u64::from(SAVE_VERSION) {
+        Ok(())
+    } else {
+        Err(format!("Saved games of version {} can't be read, only version {}", version, SAVE_VERSION))
+    }
+}
@@ -0,0 +1,60 @@
+use super::*;
+use super::super::*;
+
+use serde_json::json;
+
+use PlayerKind::*;
+
+#[test]
+fn json_representation_test() {
+    assert_eq!(serde_json::to_value(PlayerX).unwrap(), json!("X"));
+    assert_eq!(serde_json::to_value(Difficulty::Medium).unwrap(), json!("medium"));
+    assert_eq!(serde_json::to_value(Coord::new(1, 2)).unwrap(), json!({ "row": 1, "col": 2 }));
+    assert_eq!(serde_json::to_value(GameStatus::InProgress).unwrap(), json!("in_progress"));
+    assert_eq!(serde_json::to_value(GameStatus::Tie).unwrap(), json!("tie"));
+
+    let mut game = Game::new();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        game.make_move(row, col);
+    }
+    assert_eq!(serde_json::to_value(&game.status).unwrap(), json!({
+        "win": ["X", [{ "direction": "horizontal", "cells": [
+            { "row": 0, "col": 0 }, { "row": 0, "col": 1 }, { "row": 0, "col": 2 },
+        ] }]],
+    }));
+}
+
+#[test]
+fn save_and_load_test() {
+    let mut game = GameConfig::new().difficulty(Difficulty::Easy).first_player(PlayerO).build();
+    game.make_move(1, 1);
+    game.make_move(0, 0);
+
+    let text = serde_json::to_string(&SavedGame::new(game.clone())).unwrap();
+    let loaded = SavedGame::from_json(&text).unwrap();
+    assert_eq!(loaded.board, game.board);
+    assert_eq!(loaded.history, game.history);
+    assert_eq!((loaded.current_player, loaded.status.clone()), (game.current_player, game.status.clone()));
+    assert_eq!((loaded.difficulty, loaded.starting_player), (Difficulty::Easy, PlayerO));
+    // a loaded game is a new position as far as turn ids go
+    assert_ne!(loaded.turn_id, game.turn_id);
+}
+
+#[test]
+fn unknown_version_test() {
+    let mut saved = serde_json::to_value(SavedGame::new(Game::new())).unwrap();
+    assert_eq!(saved["version"], json!(SAVE_VERSION));
+    saved["version"] = json!(SAVE_VERSION + 1);
+
+    let saved: SavedGame = serde_json::from_value(saved).unwrap();
+    assert!(saved.into_game().is_err());
+
+    // the version is checked before the game, which a later version may
+    // write differently
+    let text = json!({ "version": SAVE_VERSION + 1, "game": { "cells": "X.O/.X./..." } }).to_string();
+    assert_eq!(SavedGame::from_json(&text).err(),
+               Some(format!("Saved games of version {} can't be read, only version {}", SAVE_VERSION + 1, SAVE_VERSION)));
+    let text = json!({ "version": SAVE_VERSION, "game": { "cells": "X.O/.X./..." } }).to_string();
+    assert!(SavedGame::from_json(&text).unwrap_err().starts_with("Couldn't read the saved game"));
+    assert!(SavedGame::from_json("{}").is_err());
+}
@@ -0,0 +1,194 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::mnk::{self, MnkGame, MnkStatus};
+use super::notakto::{self, NotaktoGame, NotaktoStatus};
+use super::{bot, Game, GameStatus, Rng};
+
+#[cfg(test)]
+mod tests;
+
+/// Works out bot moves on another thread, so a frontend can keep drawing
+/// and handling input while the bot thinks. Each move is asked for by the
+/// turn id of the position it's for, and is only handed back while that's
+/// still the position, so a move finished after an undo or a new game is
+/// thrown away rather than played in the wrong game.
+pub struct BotScheduler<M> {
+    /// The least time between asking for a move and getting it back, so
+    /// the bot doesn't reply faster than the eye can follow
+    pub delay: Duration,
+    /// The turn the bot is working on, if any, with where its move will
+    /// arrive. Each move gets its own channel, so it closes if the thread
+    /// working it out panics.
+    pending: Option<(u64, Receiver<M>)>,
+    /// The turn the bot last failed to find a move for, which isn't tried
+    /// again
+    failed: Option<u64>,
+}
+
+impl<M: Send + 'static> BotScheduler<M> {
+    pub fn new(delay: Duration) -> BotScheduler<M> {
+        BotScheduler {
+            delay,
+            pending: None,
+            failed: None,
+        }
+    }
+
+    /// Whether the bot is working on a move for `turn_id`
+    pub fn is_thinking(&self, turn_id: u64) -> bool {
+        matches!(self.pending, Some((id, _)) if id == turn_id)
+    }
+
+    /// Whether the bot stopped without a move for `turn_id`, as when the
+    /// thread working it out panicked
+    pub fn has_failed(&self, turn_id: u64) -> bool {
+        self.failed == Some(turn_id)
+    }
+
+    /// Starts `compute` on another thread for `turn_id`, unless it has
+    /// already been started or has failed for that turn. Whatever it was
+    /// working on before is dropped.
+    pub fn start<F>(&mut self, turn_id: u64, compute: F)
+    where
+        F: FnOnce() -> M + Send + 'static,
+    {
+        if self.is_thinking(turn_id) || self.has_failed(turn_id) {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        self.pending = Some((turn_id, receiver));
+
+        let delay = self.delay;
+        thread::spawn(move || {
+            let started = Instant::now();
+            let result = compute();
+            thread::sleep(delay.saturating_sub(started.elapsed()));
+            // the move may have been dropped while the bot was thinking
+            let _ = sender.send(result);
+        });
+    }
+
+    /// Returns the move for `turn_id` if it's ready. Moves for any other
+    /// turn are stale and dropped.
+    pub fn poll(&mut self, turn_id: u64) -> Option<M> {
+        if !self.is_thinking(turn_id) {
+            return None;
+        }
+        let (_, receiver) = self.pending.as_ref()?;
+        match receiver.try_recv() {
+            Ok(result) => {
+                self.pending = None;
+                Some(result)
+            },
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.fail(turn_id);
+                None
+            },
+        }
+    }
+
+    /// Waits for the move for `turn_id`, which must have been started.
+    /// Returns None if the bot stopped without a move, which `has_failed`
+    /// then reports.
+    pub fn wait(&mut self, turn_id: u64) -> Option<M> {
+        assert!(self.is_thinking(turn_id), "The bot wasn't asked for a move.");
+        let (_, receiver) = self.pending.take()?;
+        match receiver.recv() {
+            Ok(result) => Some(result),
+            Err(_) => {
+                self.fail(turn_id);
+                None
+            },
+        }
+    }
+
+    fn fail(&mut self, turn_id: u64) {
+        self.pending = None;
+        self.failed = Some(turn_id);
+    }
+}
+
+/// A copy of `game` for the bot to think about, with its own random numbers
+/// split off the game's
+fn bot_copy(game: &mut Game) -> Game {
+    let mut copy = game.clone();
+    copy.rng = Rng::from_seed(game.rng.next_u64());
+    copy
+}
+
+fn is_bot_turn(game: &Game) -> bool {
+    game.status == GameStatus::InProgress && !game.current_player_is_human()
+}
+
+impl BotScheduler<(usize, usize)> {
+    /// Called on every tick of a frontend's loop. Starts the bot when it
+    /// has the turn in `game`, and returns its move once it's ready, as
+    /// (row, col) for the caller to play. Once the bot has failed for this
+    /// turn, it's not started again and this returns None.
+    pub fn update(&mut self, game: &mut Game) -> Option<(usize, usize)> {
+        if !is_bot_turn(game) || self.has_failed(game.turn_id) {
+            return None;
+        }
+        if let Some(result) = self.poll(game.turn_id) {
+            return Some(result);
+        }
+        // the game's random numbers only move on when the bot starts a move
+        if !self.is_thinking(game.turn_id) {
+            let mut copy = bot_copy(game);
+            self.start(game.turn_id, move || bot::get_bot_move(&mut copy));
+        }
+        None
+    }
+
+    /// Like `update`, but waits for the bot rather than returning while it
+    /// thinks. Returns None if it's not the bot's turn, or if the bot
+    /// stopped without a move, which `has_failed` tells apart.
+    pub fn play(&mut self, game: &mut Game) -> Option<(usize, usize)> {
+        if !is_bot_turn(game) || self.has_failed(game.turn_id) {
+            return None;
+        }
+        if !self.is_thinking(game.turn_id) {
+            let mut copy = bot_copy(game);
+            self.start(game.turn_id, move || bot::get_bot_move(&mut copy));
+        }
+        self.wait(game.turn_id)
+    }
+
+    /// `update` for a game on a bigger board, returning the bot's move as
+    /// (row, col)
+    pub fn update_mnk(&mut self, game: &mut MnkGame) -> Option<(usize, usize)> {
+        if game.status != MnkStatus::InProgress || game.current_player_is_human() || self.has_failed(game.turn_id) {
+            return None;
+        }
+        if let Some(result) = self.poll(game.turn_id) {
+            return Some(result);
+        }
+        // as in `update`, the game's random numbers only move on when the
+        // bot starts a move
+        if !self.is_thinking(game.turn_id) {
+            let mut copy = game.clone();
+            copy.rng = Rng::from_seed(game.rng.next_u64());
+            self.start(game.turn_id, move || mnk::get_bot_move(&mut copy));
+        }
+        None
+    }
+}
+
+impl BotScheduler<(usize, usize, usize)> {
+    /// `update` for Notakto, returning the bot's move as (board, row, col)
+    pub fn update_notakto(&mut self, game: &NotaktoGame) -> Option<(usize, usize, usize)> {
+        if game.status != NotaktoStatus::InProgress || game.current_seat_is_human() {
+            return None;
+        }
+        if let Some(result) = self.poll(game.turn_id) {
+            return Some(result);
+        }
+
+        let boards = game.boards.clone();
+        self.start(game.turn_id, move || notakto::get_move(&boards));
+        None
+    }
+}
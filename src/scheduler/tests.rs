@@ -0,0 +1,193 @@
+use super::*;
+use super::super::*;
+
+use PlayerKind::*;
+
+/// Polls `scheduler` until the bot's move for `game` is ready
+fn wait_for_move(scheduler: &mut BotScheduler<(usize, usize)>, game: &mut Game) -> (usize, usize) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(result) = scheduler.update(game) {
+            return result;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    panic!("The bot didn't move in time.");
+}
+
+#[test]
+fn turn_id_changes_test() {
+    let mut game = Game::new();
+    let mut seen = vec![game.turn_id];
+
+    game.make_move(1, 1);
+    seen.push(game.turn_id);
+    game.undo();
+    seen.push(game.turn_id);
+    game.reset();
+    seen.push(game.turn_id);
+    seen.push(Game::new().turn_id);
+
+    // a position that comes back, like the empty board after the undo and
+    // the reset, still gets a new id
+    let count = seen.len();
+    seen.sort_unstable();
+    seen.dedup();
+    assert_eq!(seen.len(), count);
+
+    // a failed undo leaves the position as it was
+    let turn_id = game.turn_id;
+    assert!(!game.undo());
+    assert_eq!(game.turn_id, turn_id);
+}
+
+#[test]
+fn update_plays_the_bot_test() {
+    let mut game = GameConfig::new().human_side(PlayerO).build();
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+
+    let (row, col) = wait_for_move(&mut scheduler, &mut game);
+    assert_eq!(game.board[row][col], None);
+    game.make_move(row, col);
+
+    // nothing to do on a human's turn
+    assert!(scheduler.update(&mut game).is_none());
+    assert!(!scheduler.is_thinking(game.turn_id));
+}
+
+#[test]
+fn update_splits_rng_once_per_move_test() {
+    let mut game = GameConfig::new().human_side(PlayerO).build();
+    let mut scheduler = BotScheduler::new(Duration::from_millis(50));
+
+    assert!(scheduler.update(&mut game).is_none());
+    let rng = game.rng.clone();
+    // ticks while the bot thinks leave the game's random numbers alone
+    for _ in 0..10 {
+        assert!(scheduler.update(&mut game).is_none());
+    }
+    assert_eq!(game.rng, rng);
+    wait_for_move(&mut scheduler, &mut game);
+    assert_eq!(game.rng, rng);
+}
+
+#[test]
+fn stale_moves_are_dropped_test() {
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+    scheduler.start(1, || "old");
+    // asking again for the same turn doesn't start a second thread
+    scheduler.start(1, || "again");
+    scheduler.start(2, || "new");
+    assert!(!scheduler.is_thinking(1));
+    assert!(scheduler.is_thinking(2));
+
+    assert_eq!(scheduler.wait(2), Some("new"));
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(scheduler.poll(1), None);
+    assert_eq!(scheduler.poll(2), None);
+}
+
+#[test]
+fn reset_while_thinking_test() {
+    // the bot plays X in a game that's reset before its move is ready, and
+    // then has to move again in the new game
+    let mut game = GameConfig::new().human_side(PlayerO).build();
+    let mut scheduler = BotScheduler::new(Duration::from_millis(50));
+
+    assert!(scheduler.update(&mut game).is_none());
+    let old_turn = game.turn_id;
+    assert!(scheduler.is_thinking(old_turn));
+
+    game.reset();
+    let (row, col) = wait_for_move(&mut scheduler, &mut game);
+    assert!(!scheduler.is_thinking(old_turn));
+    game.make_move(row, col);
+    assert_eq!(count_stones(PlayerX, &game.board), 1);
+}
+
+#[test]
+fn panicking_bot_gives_no_move_test() {
+    let mut scheduler: BotScheduler<&str> = BotScheduler::new(Duration::from_millis(0));
+    scheduler.start(1, || panic!("The bot broke."));
+    assert_eq!(scheduler.wait(1), None);
+    assert!(scheduler.has_failed(1));
+    assert!(!scheduler.is_thinking(1));
+
+    // a failed turn isn't tried again, and polling finds the failure too
+    scheduler.start(1, || "again");
+    assert!(!scheduler.is_thinking(1));
+    scheduler.start(2, || panic!("The bot broke again."));
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !scheduler.has_failed(2) {
+        assert_eq!(scheduler.poll(2), None);
+        assert!(Instant::now() < deadline, "The failure wasn't noticed in time.");
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // once the bot has failed a turn, neither asks again, and the game's
+    // random numbers stay where they were
+    let mut game = GameConfig::new().human(PlayerX, false).build();
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+    scheduler.start(game.turn_id, || panic!("The bot broke."));
+    assert_eq!(scheduler.wait(game.turn_id), None);
+    let rng = game.rng.clone();
+    assert_eq!(scheduler.play(&mut game), None);
+    assert_eq!(scheduler.update(&mut game), None);
+    assert_eq!(game.rng, rng);
+}
+
+#[test]
+fn play_waits_for_the_bot_test() {
+    let mut game = GameConfig::new().human(PlayerX, false).build();
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+
+    // the bot plays itself to the end, which is always a tie
+    while let Some((row, col)) = scheduler.play(&mut game) {
+        game.make_move(row, col);
+    }
+    assert_eq!(game.status, GameStatus::Tie);
+
+    let mut game = Game::new();
+    assert_eq!(scheduler.play(&mut game), None);
+}
+
+#[test]
+fn update_notakto_test() {
+    let mut game = NotaktoGame::new(1);
+    game.first_is_human = false;
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let (board, row, col) = loop {
+        if let Some(result) = scheduler.update_notakto(&game) {
+            break result;
+        }
+        assert!(Instant::now() < deadline, "The bot didn't move in time.");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(board, 0);
+    game.make_move(board, row, col);
+    assert!(scheduler.update_notakto(&game).is_none());
+}
+
+#[test]
+fn update_mnk_test() {
+    let mut game = MnkGame::new(5, 4);
+    game.x_is_human = false;
+    game.o_is_human = true;
+    let mut scheduler = BotScheduler::new(Duration::from_millis(0));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let (row, col) = loop {
+        if let Some(result) = scheduler.update_mnk(&mut game) {
+            break result;
+        }
+        assert!(Instant::now() < deadline, "The bot didn't move in time.");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!((row, col), (2, 2));
+    game.make_move(row, col);
+    // and waits while the human plays O
+    assert!(scheduler.update_mnk(&mut game).is_none());
+    assert!(!scheduler.is_thinking(game.turn_id));
+}
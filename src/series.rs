@@ -0,0 +1,172 @@
+use std::fmt;
+
+use super::{Game, GameConfig, GameStatus, PlayerKind};
+
+/// The result of a whole series of games
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MatchStatus {
+    InProgress,
+    Draw,
+    Win(PlayerKind),
+}
+
+/// A best-of-N series of games between the same two sides. Who moves first
+/// alternates from game to game, and the results are tallied per player.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Match {
+    /// The setup every game of the match is played with
+    pub config: GameConfig,
+    pub game: Game,
+    pub best_of: u32,
+    pub x_wins: u32,
+    pub o_wins: u32,
+    pub draws: u32,
+}
+
+impl Match {
+    pub fn new(config: GameConfig, best_of: u32) -> Match {
+        assert!(best_of > 0, "A match needs at least one game.");
+        let config = config.alternate_starts(true);
+        Match {
+            game: config.clone().build(),
+            config,
+            best_of,
+            x_wins: 0,
+            o_wins: 0,
+            draws: 0,
+        }
+    }
+
+    /// Parses a match from command line arguments. `--best-of <N>` sets the
+    /// number of games, one by default, and the rest of the arguments are
+    /// parsed by `GameConfig::from_args`.
+    pub fn from_args(args: &[String]) -> Result<Match, String> {
+        let config = GameConfig::from_args(args)?;
+
+        let mut best_of = 1;
+        if let Some(i) = args.iter().position(|arg| arg == "--best-of") {
+            best_of = match args.get(i + 1).map(|n| n.parse::<u32>()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => return Err("--best-of needs a number of games above 0".to_string()),
+            };
+        }
+
+        Ok(Match::new(config, best_of))
+    }
+
+    /// Makes a move in the current game, and tallies the result if it ends
+    /// the game. Returns false if the square is already taken or the game is
+    /// over.
+    pub fn make_move(&mut self, row: usize, col: usize) -> bool {
+        if !self.game.make_move(row, col) {
+            return false;
+        }
+
+        match &self.game.status {
+            GameStatus::Win(PlayerKind::PlayerX, _) => self.x_wins += 1,
+            GameStatus::Win(PlayerKind::PlayerO, _) => self.o_wins += 1,
+            GameStatus::Tie => self.draws += 1,
+            GameStatus::InProgress => (),
+        }
+        true
+    }
+
+    /// Takes back the last move of the current game, along with its result
+    /// if that move ended the game. Returns false if no moves have been made.
+    pub fn undo(&mut self) -> bool {
+        let status = self.game.status.clone();
+        if !self.game.undo() {
+            return false;
+        }
+
+        match status {
+            GameStatus::Win(PlayerKind::PlayerX, _) => self.x_wins -= 1,
+            GameStatus::Win(PlayerKind::PlayerO, _) => self.o_wins -= 1,
+            GameStatus::Tie => self.draws -= 1,
+            GameStatus::InProgress => (),
+        }
+        true
+    }
+
+    /// Undoes moves until it's a human's turn again, so undoing against the
+    /// bot takes back the bot's reply along with the human's move. Between
+    /// two bots a single move is taken back. Returns false if no moves have
+    /// been made.
+    pub fn undo_to_human(&mut self) -> bool {
+        if !self.undo() {
+            return false;
+        }
+
+        let has_human = self.game.player1.is_human || self.game.player2.is_human;
+        while has_human && !self.game.current_player_is_human() {
+            if !self.undo() {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Starts the next game of the series, if there is one
+    pub fn next_game(&mut self) {
+        if self.status() == MatchStatus::InProgress {
+            self.game.reset();
+        }
+    }
+
+    pub fn wins(&self, player: PlayerKind) -> u32 {
+        match player {
+            PlayerKind::PlayerX => self.x_wins,
+            PlayerKind::PlayerO => self.o_wins,
+        }
+    }
+
+    /// The score so far, e.g. "X 2 - 1 O, 1 draw"
+    pub fn score(&self) -> String {
+        let score = format!("X {} - {} O", self.x_wins, self.o_wins);
+        match self.draws {
+            0 => score,
+            1 => score + ", 1 draw",
+            n => format!("{}, {} draws", score, n),
+        }
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.x_wins + self.o_wins + self.draws
+    }
+
+    /// The match is over once every game has been played, or once one player
+    /// is ahead by more games than are left to play
+    pub fn status(&self) -> MatchStatus {
+        let games_left = self.best_of.saturating_sub(self.games_played());
+
+        if self.x_wins > self.o_wins + games_left {
+            MatchStatus::Win(PlayerKind::PlayerX)
+        } else if self.o_wins > self.x_wins + games_left {
+            MatchStatus::Win(PlayerKind::PlayerO)
+        } else if games_left == 0 {
+            MatchStatus::Draw
+        } else {
+            MatchStatus::InProgress
+        }
+    }
+}
+
+/// Formats the running score along with the game being played, e.g.
+/// "X 2 - 1 O, 1 draw (game 4 of 5)"
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.score())?;
+
+        if self.best_of > 1 {
+            // count the game on the board, unless it has already been tallied
+            let game_number = match self.game.status {
+                GameStatus::InProgress => self.games_played() + 1,
+                _ => self.games_played(),
+            };
+            write!(f, " (game {} of {})", game_number.min(self.best_of), self.best_of)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use super::{Difficulty, GameConfig, Match, PlayerKind, BOARD_SIZE};
+use super::mnk::{self, MnkGame};
+use super::PlayerKind::*;
+use super::render::Theme;
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the settings file inside the config directory. The GUI was
+/// the first to keep settings, and the name stays so they aren't lost.
+const FILE_NAME: &str = "gui.conf";
+
+/// Who the human players are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Side {
+    X,
+    O,
+    Both,
+    Neither,
+}
+
+/// Everything picked on the GUI's settings screen, kept between runs in a
+/// file of `key = value` lines, and shared with the TUI
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    pub x_is_human: bool,
+    pub o_is_human: bool,
+    /// Who moves first in the first game of a match, after which the
+    /// players take turns starting
+    pub first_player: PlayerKind,
+    pub best_of: u32,
+    pub difficulty: Difficulty,
+    pub theme: Theme,
+    /// How long the bot waits before it moves
+    pub bot_delay_millis: u64,
+    /// Fill taken squares with stripes for X and dots for O, so the sides
+    /// can be told apart without their colours
+    pub patterns: bool,
+    /// The squares on each side of the board. Anything but the classic
+    /// 3x3 board with three in a row is played as an `MnkGame`.
+    pub board_size: usize,
+    /// How many in a row win, never more than `board_size`
+    pub win_length: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let config = GameConfig::new();
+        Settings {
+            x_is_human: config.x_is_human,
+            o_is_human: config.o_is_human,
+            first_player: config.first_player,
+            best_of: 1,
+            difficulty: config.difficulty,
+            theme: Theme::default(),
+            bot_delay_millis: 300,
+            patterns: false,
+            board_size: BOARD_SIZE,
+            win_length: BOARD_SIZE,
+        }
+    }
+}
+
+impl Settings {
+    pub fn side(&self) -> Side {
+        match (self.x_is_human, self.o_is_human) {
+            (true, false) => Side::X,
+            (false, true) => Side::O,
+            (true, true) => Side::Both,
+            (false, false) => Side::Neither,
+        }
+    }
+
+    pub fn set_side(&mut self, side: Side) {
+        let (x_is_human, o_is_human) = match side {
+            Side::X => (true, false),
+            Side::O => (false, true),
+            Side::Both => (true, true),
+            Side::Neither => (false, false),
+        };
+        self.x_is_human = x_is_human;
+        self.o_is_human = o_is_human;
+    }
+
+    /// Sets the board size, shortening the win length to fit if needed
+    pub fn set_board_size(&mut self, size: usize) {
+        self.board_size = size;
+        self.win_length = self.win_length.min(size);
+    }
+
+    /// Sets the win length, growing the board to fit it if needed
+    pub fn set_win_length(&mut self, win_length: usize) {
+        self.win_length = win_length;
+        self.board_size = self.board_size.max(win_length);
+    }
+
+    /// Whether the board is the classic 3x3 with three in a row, which the
+    /// classic game plays with its rules and perfect bot
+    pub fn is_classic_board(&self) -> bool {
+        self.board_size == BOARD_SIZE && self.win_length == BOARD_SIZE
+    }
+
+    /// A game on the board these settings pick, with their sides, first
+    /// player and difficulty
+    pub fn mnk_game(&self) -> MnkGame {
+        let mut game = MnkGame::new(self.board_size, self.win_length);
+        game.x_is_human = self.x_is_human;
+        game.o_is_human = self.o_is_human;
+        game.difficulty = self.difficulty;
+        game.starting_player = self.first_player;
+        game.reset();
+        game
+    }
+
+    /// `base` with the sides, first player and difficulty from these
+    /// settings. The rules aren't part of the settings, so they're kept.
+    pub fn config(&self, base: GameConfig) -> GameConfig {
+        base.human(PlayerX, self.x_is_human)
+            .human(PlayerO, self.o_is_human)
+            .first_player(self.first_player)
+            .difficulty(self.difficulty)
+    }
+
+    /// Takes the options given on the command line in `args`, already
+    /// parsed into `series`, over the saved ones. Options that weren't given
+    /// keep their saved values.
+    pub fn apply_args(&mut self, args: &[String], series: &Match) {
+        let given = |flag: &str| args.iter().any(|arg| arg == flag);
+        let config = &series.config;
+        if given("--play-as") {
+            self.x_is_human = config.x_is_human;
+            self.o_is_human = config.o_is_human;
+        }
+        if given("--first") {
+            self.first_player = config.first_player;
+        }
+        if given("--difficulty") {
+            self.difficulty = config.difficulty;
+        }
+        if given("--best-of") {
+            self.best_of = series.best_of;
+        }
+    }
+
+    /// Parses settings written by `to_string`, starting from the defaults.
+    /// Blank lines, `#` comments and unknown keys are skipped, so files
+    /// from other versions still load.
+    pub fn parse(text: &str) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(format!("Line {}: expected \"key = value\", found \"{}\"", number + 1, line)),
+            };
+            settings.set(key, value).map_err(|e| format!("Line {}: {}", number + 1, e))?;
+        }
+        if settings.win_length > settings.board_size {
+            return Err(format!("A win length of {} doesn't fit on a board of {}", settings.win_length,
+                               settings.board_size));
+        }
+        Ok(settings)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "x_player" => self.x_is_human = parse_controller(value)?,
+            "o_player" => self.o_is_human = parse_controller(value)?,
+            "first" => self.first_player = match value.to_uppercase().as_str() {
+                "X" => PlayerX,
+                "O" => PlayerO,
+                _ => return Err(format!("Expected a player, X or O, found \"{}\"", value)),
+            },
+            "best_of" => self.best_of = match value.parse() {
+                Ok(n) if n > 0 => n,
+                _ => return Err(format!("Expected a number of games above 0, found \"{}\"", value)),
+            },
+            "difficulty" => self.difficulty = value.parse()?,
+            "theme" => self.theme = value.parse()?,
+            "bot_delay_millis" => self.bot_delay_millis = value.parse()
+                .map_err(|_| format!("Expected a delay in milliseconds, found \"{}\"", value))?,
+            "patterns" => self.patterns = match value {
+                "on" => true,
+                "off" => false,
+                _ => return Err(format!("Expected on or off, found \"{}\"", value)),
+            },
+            "board_size" => self.board_size = parse_length(value, mnk::MIN_BOARD_SIZE..=mnk::MAX_BOARD_SIZE)?,
+            "win_length" => self.win_length = parse_length(value, mnk::MIN_BOARD_SIZE..=mnk::MAX_BOARD_SIZE)?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Where the settings are kept: `tic-tac-torture/gui.conf` in
+    /// `$XDG_CONFIG_HOME`, `~/.config` or, on Windows, `%APPDATA%`
+    pub fn path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(dir.join("tic-tac-torture").join(FILE_NAME))
+    }
+
+    /// Loads the saved settings, falling back to the defaults if there are
+    /// none. A file that can't be read or parsed is ignored, and returned
+    /// with the settings as a warning for the frontend to show.
+    pub fn load() -> (Settings, Vec<String>) {
+        match Settings::path() {
+            Some(path) => Settings::load_from(&path),
+            None => (Settings::default(), Vec::new()),
+        }
+    }
+
+    fn load_from(path: &Path) -> (Settings, Vec<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return (Settings::default(), Vec::new()),
+            Err(e) => return (Settings::default(), vec![format!("Couldn't read {}: {}", path.display(), e)]),
+        };
+
+        match Settings::parse(&text) {
+            Ok(settings) => (settings, Vec::new()),
+            Err(e) => (Settings::default(), vec![format!("Ignoring {}: {}", path.display(), e)]),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Settings::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_string())
+    }
+}
+
+fn parse_length(value: &str, range: RangeInclusive<usize>) -> Result<usize, String> {
+    match value.parse() {
+        Ok(n) if range.contains(&n) => Ok(n),
+        _ => Err(format!("Expected a number from {} to {}, found \"{}\"", range.start(), range.end(), value)),
+    }
+}
+
+fn parse_controller(value: &str) -> Result<bool, String> {
+    match value {
+        "human" => Ok(true),
+        "bot" => Ok(false),
+        _ => Err(format!("Expected human or bot, found \"{}\"", value)),
+    }
+}
+
+fn controller_name(is_human: bool) -> &'static str {
+    if is_human { "human" } else { "bot" }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "x_player = {}", controller_name(self.x_is_human))?;
+        writeln!(f, "o_player = {}", controller_name(self.o_is_human))?;
+        writeln!(f, "first = {}", self.first_player.to_char())?;
+        writeln!(f, "best_of = {}", self.best_of)?;
+        writeln!(f, "difficulty = {}", self.difficulty)?;
+        writeln!(f, "theme = {}", self.theme)?;
+        writeln!(f, "bot_delay_millis = {}", self.bot_delay_millis)?;
+        writeln!(f, "patterns = {}", if self.patterns { "on" } else { "off" })?;
+        writeln!(f, "board_size = {}", self.board_size)?;
+        writeln!(f, "win_length = {}", self.win_length)
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+use std::process;
+
+use crate::render::Theme;
+
+#[test]
+fn round_trip_test() {
+    let mut settings = Settings::default();
+    settings.set_side(Side::O);
+    settings.first_player = PlayerO;
+    settings.best_of = 5;
+    settings.difficulty = Difficulty::Easy;
+    settings.theme = Theme::HighContrast;
+    settings.bot_delay_millis = 800;
+    settings.patterns = true;
+    settings.board_size = 7;
+    settings.win_length = 5;
+
+    assert_eq!(Settings::parse(&settings.to_string()), Ok(settings.clone()));
+    assert_eq!(Settings::parse(&Settings::default().to_string()), Ok(Settings::default()));
+}
+
+#[test]
+fn parse_test() {
+    // missing keys keep their defaults, and comments and unknown keys are
+    // skipped
+    let text = "# saved settings\n\ntheme = light\nsound = on\n  difficulty=medium  \n";
+    let settings = Settings::parse(text).unwrap();
+    assert_eq!(settings.theme, Theme::Light);
+    assert_eq!(settings.difficulty, Difficulty::Medium);
+    assert_eq!(settings.side(), Side::X);
+    assert_eq!(settings.bot_delay_millis, Settings::default().bot_delay_millis);
+
+    assert_eq!(Settings::parse(""), Ok(Settings::default()));
+
+    for bad in &["theme", "theme = purple", "best_of = 0", "x_player = robot",
+                 "first = Z", "bot_delay_millis = -1", "difficulty = impossible",
+                 "patterns = yes", "board_size = 2", "board_size = 10", "win_length = 4"] {
+        assert!(Settings::parse(bad).is_err(), "{:?} parsed", bad);
+    }
+    assert_eq!(Settings::parse("first = X\nbest_of = none").unwrap_err(),
+               "Line 2: Expected a number of games above 0, found \"none\"");
+}
+
+#[test]
+fn config_test() {
+    let mut settings = Settings::default();
+    settings.set_side(Side::Neither);
+    settings.first_player = PlayerO;
+    settings.difficulty = Difficulty::Medium;
+
+    let config = settings.config(GameConfig::new());
+    assert!(!config.x_is_human && !config.o_is_human);
+    assert_eq!(config.first_player, PlayerO);
+    assert_eq!(config.difficulty, Difficulty::Medium);
+}
+
+#[test]
+fn apply_args_test() {
+    let mut saved = Settings::default();
+    saved.set_side(Side::Both);
+    saved.difficulty = Difficulty::Easy;
+    saved.best_of = 3;
+
+    // only the options that were given replace the saved ones
+    let args: Vec<String> = ["gui", "--difficulty", "medium", "--first", "O"]
+        .iter().map(|s| s.to_string()).collect();
+    let mut settings = saved.clone();
+    settings.apply_args(&args, &Match::from_args(&args).unwrap());
+    assert_eq!(settings.difficulty, Difficulty::Medium);
+    assert_eq!(settings.first_player, PlayerO);
+    assert_eq!(settings.side(), Side::Both);
+    assert_eq!(settings.best_of, 3);
+
+    let args: Vec<String> = ["gui", "--play-as", "none", "--best-of", "5"]
+        .iter().map(|s| s.to_string()).collect();
+    let mut settings = saved.clone();
+    settings.apply_args(&args, &Match::from_args(&args).unwrap());
+    assert_eq!(settings.side(), Side::Neither);
+    assert_eq!(settings.best_of, 5);
+    assert_eq!(settings.difficulty, Difficulty::Easy);
+}
+
+#[test]
+fn board_test() {
+    let mut settings = Settings::default();
+    assert!(settings.is_classic_board());
+
+    // the win length always fits on the board
+    settings.set_win_length(5);
+    assert_eq!((settings.board_size, settings.win_length), (5, 5));
+    settings.set_board_size(4);
+    assert_eq!((settings.board_size, settings.win_length), (4, 4));
+    settings.set_board_size(7);
+    assert_eq!((settings.board_size, settings.win_length), (7, 4));
+    assert!(!settings.is_classic_board());
+
+    settings.set_side(Side::O);
+    settings.first_player = PlayerO;
+    let game = settings.mnk_game();
+    assert_eq!((game.size, game.win_length), (7, 4));
+    assert!(!game.x_is_human && game.o_is_human);
+    assert_eq!(game.current_player, PlayerO);
+}
+
+#[test]
+fn load_test() {
+    let path = env::temp_dir().join(format!("tic-tac-torture-settings-{}.conf", process::id()));
+    let _ = fs::remove_file(&path);
+
+    // no file is no warning
+    assert_eq!(Settings::load_from(&path), (Settings::default(), Vec::new()));
+
+    fs::write(&path, "theme = light\n").unwrap();
+    assert_eq!(Settings::load_from(&path).0.theme, Theme::Light);
+
+    // a file that can't be parsed is ignored with a warning
+    fs::write(&path, "theme = plaid\n").unwrap();
+    let (settings, warnings) = Settings::load_from(&path);
+    assert_eq!(settings, Settings::default());
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with(&format!("Ignoring {}", path.display())), "{:?}", warnings);
+
+    fs::remove_file(&path).unwrap();
+}
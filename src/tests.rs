@@ -81,3 +81,402 @@ fn no_tie_test() {
          [P_O, P_X, P_X]];
     assert!(!is_full(&board));
 }
+
+fn args(s: &str) -> Vec<String> {
+    s.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn ruleset_from_args_test() {
+    assert_eq!(Ruleset::from_args(&args("cli")), Ok(Ruleset::default()));
+
+    let ruleset = Ruleset::from_args(&args("cli --random-turn")).unwrap();
+    assert_eq!(ruleset.turn_order, TurnOrder::Random);
+
+    let ruleset = Ruleset::from_args(&args("cli --double-first-move o")).unwrap();
+    assert_eq!(ruleset.handicap, Handicap::DoubleFirstMove(PlayerO));
+
+    let ruleset = Ruleset::from_args(&args("cli --stones X 1,1 3,2 --random-turn")).unwrap();
+    assert_eq!(ruleset.handicap, Handicap::Stones(PlayerX, vec![(0, 0), (2, 1)]));
+    assert_eq!(ruleset.turn_order, TurnOrder::Random);
+
+    assert!(Ruleset::from_args(&args("cli --stones X")).is_err());
+    assert!(Ruleset::from_args(&args("cli --stones X 4,1")).is_err());
+    assert_eq!(Ruleset::from_args(&args("cli --stones X a1 b2 a1")),
+               Err("--stones has a1 more than once".to_string()));
+    assert!(Ruleset::from_args(&args("cli --stones O a1 b2 c3")).is_err());
+    assert!(Ruleset::from_args(&args("cli --double-first-move")).is_err());
+}
+
+#[test]
+fn double_first_move_test() {
+    let mut game = Game::with_ruleset(Ruleset {
+        turn_order: TurnOrder::Alternating,
+        handicap: Handicap::DoubleFirstMove(PlayerO),
+    });
+
+    game.make_move(1, 1);
+    assert_eq!(game.current_player, PlayerO);
+    game.make_move(0, 0);
+    assert_eq!(game.current_player, PlayerO);
+    game.make_move(0, 1);
+    assert_eq!(game.current_player, PlayerX);
+    game.make_move(2, 2);
+    assert_eq!(game.current_player, PlayerO);
+}
+
+#[test]
+fn handicap_stones_test() {
+    let mut game = Game::with_ruleset(Ruleset {
+        turn_order: TurnOrder::Alternating,
+        handicap: Handicap::Stones(PlayerO, vec![(1, 1)]),
+    });
+    assert_eq!(game.board[1][1], P_O);
+    assert_eq!(game.current_player, PlayerX);
+
+    game.make_move(0, 0);
+    game.reset();
+    assert_eq!(game.board[1][1], P_O);
+    assert_eq!(game.board[0][0], EMP);
+
+    // stones that already make a line end the game before it starts
+    let game = Game::with_ruleset(Ruleset {
+        turn_order: TurnOrder::Alternating,
+        handicap: Handicap::Stones(PlayerX, vec![(0, 0), (0, 1), (0, 2)]),
+    });
+    assert!(matches!(game.status, GameStatus::Win(PlayerX, _)));
+}
+
+#[test]
+fn random_turn_test() {
+    let mut game = Game::with_ruleset(Ruleset {
+        turn_order: TurnOrder::Random,
+        handicap: Handicap::None,
+    });
+    game.rng = Rng::from_seed(7);
+
+    // over a whole game some player should get two turns in a row
+    let mut repeated_turn = false;
+    let mut squares = (0..9).map(|i| (i / 3, i % 3));
+    while let GameStatus::InProgress = game.status {
+        let mover = game.current_player;
+        let (row, col) = squares.next().unwrap();
+        game.make_move(row, col);
+        repeated_turn |= game.current_player == mover;
+    }
+    assert!(repeated_turn);
+}
+
+#[test]
+fn game_config_builder_test() {
+    let game = GameConfig::new()
+        .human_side(PlayerO)
+        .first_player(PlayerO)
+        .build();
+    assert_eq!(game.current_player, PlayerO);
+    assert!(game.current_player_is_human());
+    assert!(!game.player1.is_human);
+    assert!(game.player2.is_human);
+
+    let game = Game::new();
+    assert_eq!(game.current_player, PlayerX);
+    assert!(game.player1.is_human);
+    assert!(!game.player2.is_human);
+}
+
+#[test]
+fn alternate_starts_test() {
+    let mut game = GameConfig::new().alternate_starts(true).build();
+    assert_eq!(game.current_player, PlayerX);
+
+    game.make_move(0, 0);
+    game.reset();
+    assert_eq!(game.current_player, PlayerO);
+    assert_eq!(game.board[0][0], EMP);
+
+    game.reset();
+    assert_eq!(game.current_player, PlayerX);
+
+    // without alternating, X starts every game
+    let mut game = Game::new();
+    game.reset();
+    assert_eq!(game.current_player, PlayerX);
+}
+
+#[test]
+fn game_config_from_args_test() {
+    let config = GameConfig::from_args(&args("cli --play-as o --first O --alternate-starts")).unwrap();
+    assert_eq!(config, GameConfig::new()
+        .human_side(PlayerO)
+        .first_player(PlayerO)
+        .alternate_starts(true));
+
+    let config = GameConfig::from_args(&args("cli --play-as both --random-turn")).unwrap();
+    assert!(config.is_human(PlayerX) && config.is_human(PlayerO));
+    assert_eq!(config.ruleset.turn_order, TurnOrder::Random);
+
+    let config = GameConfig::from_args(&args("cli --play-as none")).unwrap();
+    assert!(!config.is_human(PlayerX) && !config.is_human(PlayerO));
+
+    assert!(GameConfig::from_args(&args("cli --play-as")).is_err());
+    assert!(GameConfig::from_args(&args("cli --first Z")).is_err());
+
+    let config = GameConfig::from_args(&args("cli --difficulty Easy")).unwrap();
+    assert_eq!(config.difficulty, Difficulty::Easy);
+    assert_eq!(GameConfig::new().difficulty, Difficulty::Hard);
+    assert!(GameConfig::from_args(&args("cli --difficulty")).is_err());
+    assert!(GameConfig::from_args(&args("cli --difficulty impossible")).is_err());
+}
+
+/// Plays X along the top row while O plays the middle row, so whoever
+/// starts the game wins it
+fn play_first_player_win(series: &mut Match) {
+    let first = series.game.current_player;
+    for col in 0..3 {
+        series.make_move(0, col);
+        if series.game.status != GameStatus::InProgress {
+            break;
+        }
+        series.make_move(1, col);
+    }
+    assert_eq!(series.game.status.winner(), Some(first));
+}
+
+#[test]
+fn match_alternates_and_tallies_test() {
+    let mut series = Match::new(GameConfig::new(), 3);
+    assert_eq!(series.game.current_player, PlayerX);
+
+    play_first_player_win(&mut series);
+    assert_eq!(series.wins(PlayerX), 1);
+    assert_eq!(series.status(), MatchStatus::InProgress);
+    assert_eq!(series.to_string(), "X 1 - 0 O (game 1 of 3)");
+
+    series.next_game();
+    assert_eq!(series.game.current_player, PlayerO);
+    play_first_player_win(&mut series);
+    assert_eq!(series.wins(PlayerO), 1);
+
+    series.next_game();
+    assert_eq!(series.to_string(), "X 1 - 1 O (game 3 of 3)");
+    play_first_player_win(&mut series);
+    assert_eq!(series.status(), MatchStatus::Win(PlayerX));
+    assert_eq!(series.score(), "X 2 - 1 O");
+
+    // no more games once the match is over
+    series.next_game();
+    assert_eq!(series.game.status.winner(), Some(PlayerX));
+}
+
+#[test]
+fn match_ends_early_test() {
+    let mut series = Match::new(GameConfig::new(), 3);
+    series.x_wins = 2;
+    assert_eq!(series.status(), MatchStatus::Win(PlayerX));
+
+    let mut series = Match::new(GameConfig::new(), 3);
+    series.x_wins = 1;
+    series.draws = 1;
+    assert_eq!(series.status(), MatchStatus::InProgress);
+    series.draws = 2;
+    assert_eq!(series.status(), MatchStatus::Win(PlayerX));
+
+    let mut series = Match::new(GameConfig::new(), 2);
+    series.x_wins = 1;
+    series.o_wins = 1;
+    assert_eq!(series.status(), MatchStatus::Draw);
+}
+
+#[test]
+fn match_from_args_test() {
+    let series = Match::from_args(&args("cli --best-of 5 --play-as O")).unwrap();
+    assert_eq!(series.best_of, 5);
+    assert!(series.game.player2.is_human);
+
+    assert_eq!(Match::from_args(&args("cli")).unwrap().best_of, 1);
+    assert!(Match::from_args(&args("cli --best-of 0")).is_err());
+    assert!(Match::from_args(&args("cli --best-of")).is_err());
+}
+
+#[test]
+fn make_move_refuses_taken_square_test() {
+    let mut game = Game::new();
+    assert!(game.make_move(1, 1));
+    assert!(!game.make_move(1, 1));
+    assert_eq!(game.board[1][1], Some(PlayerX));
+    assert_eq!(game.current_player, PlayerO);
+    assert_eq!(game.history.len(), 1);
+
+    let mut series = Match::new(GameConfig::new(), 3);
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        assert!(series.make_move(row, col));
+    }
+    // a move on the finished board changes nothing, score included
+    assert!(!series.make_move(0, 2));
+    assert_eq!(series.x_wins, 1);
+}
+
+#[test]
+fn finished_game_refuses_moves_test() {
+    let mut game = Game::new();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        assert!(game.make_move(row, col));
+    }
+    assert!(!game.make_move(2, 2));
+    assert!(!game.place(2, 1, PlayerO));
+    assert_eq!(game.board[2], [EMP, EMP, EMP]);
+    assert_eq!(game.history.len(), 5);
+    assert_eq!(game.status.winner(), Some(PlayerX));
+
+    let mut series = Match::new(GameConfig::new(), 3);
+    play_first_player_win(&mut series);
+    assert!(!series.make_move(2, 2));
+    assert!(!series.make_move(2, 1));
+    assert_eq!(series.score(), "X 1 - 0 O");
+}
+
+#[test]
+fn undo_test() {
+    let mut game = Game::new();
+    assert!(!game.undo());
+
+    game.make_move(0, 0);
+    game.make_move(1, 1);
+    assert!(game.undo());
+    assert_eq!(game.board[1][1], None);
+    assert_eq!(game.board[0][0], Some(PlayerX));
+    assert_eq!(game.current_player, PlayerO);
+}
+
+#[test]
+fn match_undo_takes_back_result_test() {
+    let mut series = Match::new(GameConfig::new(), 3);
+    play_first_player_win(&mut series);
+    assert_eq!(series.wins(PlayerX), 1);
+
+    assert!(series.undo());
+    assert_eq!(series.wins(PlayerX), 0);
+    assert!(series.game.status == GameStatus::InProgress);
+    assert_eq!(series.game.current_player, PlayerX);
+}
+
+#[test]
+fn undo_to_human_test() {
+    // X is played by a person and O by the bot
+    let mut series = Match::new(GameConfig::new(), 1);
+    assert!(!series.undo_to_human());
+
+    series.make_move(0, 0);
+    series.make_move(1, 1);
+    series.make_move(0, 1);
+    // the bot hasn't replied yet, so only X's move goes
+    assert!(series.undo_to_human());
+    assert_eq!(series.game.history.len(), 2);
+    assert_eq!(series.game.current_player, PlayerX);
+
+    // otherwise the bot's reply goes with it
+    assert!(series.undo_to_human());
+    assert!(series.game.history.is_empty());
+    assert_eq!(series.game.current_player, PlayerX);
+
+    // between bots, one move at a time
+    let mut series = Match::new(GameConfig::new().human(PlayerX, false), 1);
+    series.make_move(0, 0);
+    series.make_move(1, 1);
+    assert!(series.undo_to_human());
+    assert_eq!(series.game.history.len(), 1);
+}
+
+#[test]
+fn coord_parse_test() {
+    // the file is the column and the rank is the row, from the top left
+    assert_eq!("a1".parse::<Coord>(), Ok(Coord::new(0, 0)));
+    assert_eq!("c1".parse::<Coord>(), Ok(Coord::new(0, 2)));
+    assert_eq!("a3".parse::<Coord>(), Ok(Coord::new(2, 0)));
+    assert_eq!(" B2 ".parse::<Coord>(), Ok(Coord::new(1, 1)));
+
+    // row then column, counted from 1
+    assert_eq!("1 3".parse::<Coord>(), Ok(Coord::new(0, 2)));
+    assert_eq!("3,1".parse::<Coord>(), Ok(Coord::new(2, 0)));
+
+    for bad in &["", "d1", "a0", "a4", "1", "0 1", "1 4", "1 2 3", "x", "2b", "a1b"] {
+        assert!(bad.parse::<Coord>().is_err(), "{:?} should not parse", bad);
+    }
+}
+
+#[test]
+fn coord_display_test() {
+    let coord = Coord::new(2, 0);
+    assert_eq!(coord.file(), 'a');
+    assert_eq!(coord.rank(), 3);
+    assert_eq!(coord.to_string(), "a3");
+
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let coord = Coord::new(row, col);
+            assert_eq!(coord.to_string().parse::<Coord>(), Ok(coord));
+        }
+    }
+}
+
+#[test]
+fn stones_accept_file_and_rank_test() {
+    let ruleset = Ruleset::from_args(&args("cli --stones O b2 1,3")).unwrap();
+    assert!(ruleset.handicap == Handicap::Stones(PlayerO, vec![(1, 1), (0, 2)]));
+}
+
+#[test]
+fn winning_lines_test() {
+    let board: Board = [
+        [P_O, P_X, P_X],
+        [P_O, P_X, EMP],
+        [P_X, P_O, EMP],
+    ];
+    assert_eq!(winning_lines(PlayerX, &board), vec![Line {
+        direction: LineDirection::AntiDiagonal,
+        cells: [Coord::new(0, 2), Coord::new(1, 1), Coord::new(2, 0)],
+    }]);
+    assert_eq!(winning_lines(PlayerO, &board), vec![]);
+
+    let board: Board = [
+        [P_O, P_X, EMP],
+        [P_O, P_X, EMP],
+        [P_O, EMP, P_X],
+    ];
+    let lines = winning_lines(PlayerO, &board);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].direction, LineDirection::Vertical);
+    assert_eq!((lines[0].start(), lines[0].end()), (Coord::new(0, 0), Coord::new(2, 0)));
+}
+
+#[test]
+fn win_reports_every_line_test() {
+    // X's last move at the top left finishes the top row and the left column
+    let mut game = Game::new();
+    for &(row, col) in &[(0, 1), (1, 1), (0, 2), (2, 2), (1, 0), (1, 2), (2, 0), (2, 1)] {
+        game.make_move(row, col);
+    }
+    game.make_move(0, 0);
+
+    match &game.status {
+        GameStatus::Win(PlayerX, lines) => {
+            let directions: Vec<LineDirection> = lines.iter().map(|line| line.direction).collect();
+            assert_eq!(directions, vec![LineDirection::Horizontal, LineDirection::Vertical]);
+        },
+        status => panic!("Expected X to win, found {:?}", status),
+    }
+}
+
+#[test]
+fn set_position_test() {
+    let mut game = Game::new();
+    game.make_move(0, 0);
+    game.set_position(parse_board_string("XX./OO./...").unwrap(), PlayerO);
+    assert_eq!((game.current_player, game.status.clone()), (PlayerO, GameStatus::InProgress));
+    assert!(game.history.is_empty());
+
+    game.set_position(parse_board_string("XX./OOO/X..").unwrap(), PlayerX);
+    assert_eq!(game.status.winner(), Some(PlayerO));
+    game.set_position(parse_board_string("XOX/XOO/OXX").unwrap(), PlayerO);
+    assert_eq!(game.status, GameStatus::Tie);
+}